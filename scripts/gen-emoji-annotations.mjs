@@ -0,0 +1,53 @@
+// Regenerates src-tauri/src/emoji_annotations.tsv from the CLDR English
+// annotations: one `emoji<TAB>short name<TAB>keyword | keyword` line per
+// character. Keys drop U+FE0F like CLDR does, and skin-tone variants are
+// skipped since the picker offers them as actions on the base emoji.
+//
+//   node scripts/gen-emoji-annotations.mjs
+//   node scripts/gen-emoji-annotations.mjs annotations.json annotationsDerived.json
+import { readFileSync, writeFileSync } from "node:fs";
+import process from "node:process";
+
+const CLDR_VERSION = "46.0.0";
+const BASE = `https://raw.githubusercontent.com/unicode-org/cldr-json/${CLDR_VERSION}/cldr-json`;
+const SOURCES = [
+  `${BASE}/cldr-annotations-full/annotations/en/annotations.json`,
+  `${BASE}/cldr-annotations-derived-full/annotationsDerived/en/annotations.json`,
+];
+const OUTPUT = "src-tauri/src/emoji_annotations.tsv";
+const SKIN_TONE = /[\u{1F3FB}-\u{1F3FF}]/u;
+
+async function load(source) {
+  if (!source.startsWith("https://")) {
+    return JSON.parse(readFileSync(source, "utf8"));
+  }
+  const response = await fetch(source);
+  if (!response.ok) {
+    throw new Error(`${source}: HTTP ${response.status}`);
+  }
+  return response.json();
+}
+
+function annotationsOf(json) {
+  const root = json.annotations ?? json.annotationsDerived;
+  return root?.annotations ?? {};
+}
+
+const sources = process.argv.length > 2 ? process.argv.slice(2) : SOURCES;
+const rows = new Map();
+for (const source of sources) {
+  for (const [key, entry] of Object.entries(annotationsOf(await load(source)))) {
+    const value = key.replaceAll("\u{FE0F}", "");
+    const shortName = entry.tts?.[0];
+    if (!shortName || SKIN_TONE.test(value) || rows.has(value)) continue;
+    const keywords = (entry.default ?? []).filter((keyword) => keyword !== shortName);
+    rows.set(value, [shortName, keywords]);
+  }
+}
+
+const lines = [...rows.entries()]
+  .sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0))
+  .map(([value, [shortName, keywords]]) => `${value}\t${shortName}\t${keywords.join(" | ")}`);
+const header = `# CLDR ${CLDR_VERSION} English annotations. Generated by scripts/gen-emoji-annotations.mjs; do not edit.`;
+writeFileSync(OUTPUT, `${header}\n${lines.join("\n")}\n`);
+console.log(`Wrote ${lines.length} annotations to ${OUTPUT}`);
@@ -0,0 +1,610 @@
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::config::BrowserSourceConfig;
+use crate::matcher::{self, ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+const CACHE_TTL: Duration = Duration::from_secs(300);
+/// Microseconds between 1601-01-01 (Chromium/WebKit epoch) and the Unix epoch.
+const WEBKIT_EPOCH_OFFSET_US: i64 = 11_644_473_600_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserKind {
+    Firefox,
+    Chromium,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserProfile {
+    pub id: String,
+    pub browser: String,
+    pub name: String,
+    pub kind: BrowserKind,
+    pub path: PathBuf,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebEntry {
+    pub title: String,
+    pub url: String,
+    pub profile_id: String,
+    pub browser: String,
+    pub is_bookmark: bool,
+    pub visit_count: u32,
+    pub last_visit_ms: i64,
+}
+
+#[derive(Default)]
+struct WebCache {
+    loaded_at: Option<Instant>,
+    config: Option<BrowserSourceConfig>,
+    entries: Arc<Vec<WebEntry>>,
+    /// Config a background reload is running for, if any.
+    refreshing: Option<BrowserSourceConfig>,
+}
+
+static WEB_CACHE: OnceLock<Mutex<WebCache>> = OnceLock::new();
+
+/// Chromium-family browsers: (id prefix, display name, config dir relative to ~/.config).
+const CHROMIUM_BROWSERS: &[(&str, &str, &str)] = &[
+    ("chrome", "Google Chrome", "google-chrome"),
+    ("chromium", "Chromium", "chromium"),
+    ("brave", "Brave", "BraveSoftware/Brave-Browser"),
+    ("edge", "Microsoft Edge", "microsoft-edge"),
+    ("vivaldi", "Vivaldi", "vivaldi"),
+];
+
+/// Firefox profile roots relative to $HOME (native, Flatpak, Snap).
+const FIREFOX_ROOTS: &[&str] = &[
+    ".mozilla/firefox",
+    ".var/app/org.mozilla.firefox/.mozilla/firefox",
+    "snap/firefox/common/.mozilla/firefox",
+];
+
+fn profile_enabled(cfg: &BrowserSourceConfig, id: &str) -> bool {
+    cfg.profiles
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.enabled)
+        .unwrap_or(true)
+}
+
+/// Discover browser profiles under `home`. Profiles without a toggle in config
+/// are enabled by default.
+pub fn discover_profiles_in(home: &Path, cfg: &BrowserSourceConfig) -> Vec<BrowserProfile> {
+    let mut out = Vec::new();
+
+    for root in FIREFOX_ROOTS {
+        let Ok(dirs) = std::fs::read_dir(home.join(root)) else {
+            continue;
+        };
+        for dir in dirs.flatten() {
+            let path = dir.path();
+            if !path.join("places.sqlite").is_file() {
+                continue;
+            }
+            let dir_name = dir.file_name().to_string_lossy().to_string();
+            let id = format!("firefox:{}", dir_name);
+            if out.iter().any(|p: &BrowserProfile| p.id == id) {
+                continue;
+            }
+            let name = dir_name
+                .split_once('.')
+                .map(|(_, n)| n.to_string())
+                .unwrap_or_else(|| dir_name.clone());
+            out.push(BrowserProfile {
+                enabled: profile_enabled(cfg, &id),
+                id,
+                browser: "Firefox".to_string(),
+                name,
+                kind: BrowserKind::Firefox,
+                path,
+            });
+        }
+    }
+
+    for (slug, browser, rel) in CHROMIUM_BROWSERS {
+        let Ok(dirs) = std::fs::read_dir(home.join(".config").join(rel)) else {
+            continue;
+        };
+        for dir in dirs.flatten() {
+            let path = dir.path();
+            if !path.join("Bookmarks").is_file() && !path.join("History").is_file() {
+                continue;
+            }
+            let dir_name = dir.file_name().to_string_lossy().to_string();
+            let id = format!("{}:{}", slug, dir_name);
+            out.push(BrowserProfile {
+                enabled: profile_enabled(cfg, &id),
+                id,
+                browser: browser.to_string(),
+                name: dir_name,
+                kind: BrowserKind::Chromium,
+                path,
+            });
+        }
+    }
+
+    out.sort_by(|a, b| a.id.cmp(&b.id));
+    out
+}
+
+pub fn discover_profiles(cfg: &BrowserSourceConfig) -> Vec<BrowserProfile> {
+    match dirs::home_dir() {
+        Some(home) => discover_profiles_in(&home, cfg),
+        None => Vec::new(),
+    }
+}
+
+/// Browsers keep their databases locked while running, so we read a private copy
+/// (including the WAL so recent visits are visible).
+fn open_sqlite_copy(src: &Path, tmp: &tempfile::TempDir) -> Option<Connection> {
+    let file_name = src.file_name()?;
+    let dest = tmp.path().join(file_name);
+    std::fs::copy(src, &dest).ok()?;
+    for suffix in ["-wal", "-shm"] {
+        let mut side = src.as_os_str().to_owned();
+        side.push(suffix);
+        let side = PathBuf::from(side);
+        if side.is_file() {
+            let mut dest_side = dest.as_os_str().to_owned();
+            dest_side.push(suffix);
+            let _ = std::fs::copy(&side, PathBuf::from(dest_side));
+        }
+    }
+    Connection::open_with_flags(&dest, OpenFlags::SQLITE_OPEN_READ_ONLY).ok()
+}
+
+fn is_openable_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn read_firefox(profile: &BrowserProfile, cfg: &BrowserSourceConfig) -> Vec<WebEntry> {
+    let Ok(tmp) = tempfile::tempdir() else {
+        return Vec::new();
+    };
+    let Some(conn) = open_sqlite_copy(&profile.path.join("places.sqlite"), &tmp) else {
+        log::warn!("Could not read Firefox places for {}", profile.id);
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    let bookmark_sql = "SELECT COALESCE(b.title, p.title, ''), p.url, p.visit_count, COALESCE(p.last_visit_date, 0)
+         FROM moz_bookmarks b JOIN moz_places p ON b.fk = p.id
+         WHERE b.type = 1";
+    let history_sql = "SELECT COALESCE(title, ''), url, visit_count, COALESCE(last_visit_date, 0)
+         FROM moz_places
+         WHERE visit_count > 0 AND hidden = 0
+         ORDER BY frecency DESC
+         LIMIT ?1";
+
+    let mut collect = |sql: &str, limit: Option<usize>, is_bookmark: bool| {
+        let Ok(mut stmt) = conn.prepare(sql) else {
+            return;
+        };
+        let map_row = |row: &rusqlite::Row| -> rusqlite::Result<WebEntry> {
+            Ok(WebEntry {
+                title: row.get(0)?,
+                url: row.get(1)?,
+                profile_id: profile.id.clone(),
+                browser: profile.browser.clone(),
+                is_bookmark,
+                visit_count: row.get::<_, i64>(2)?.max(0) as u32,
+                // moz_places stores microseconds since the Unix epoch.
+                last_visit_ms: row.get::<_, i64>(3)? / 1000,
+            })
+        };
+        let rows = match limit {
+            Some(limit) => stmt.query_map([limit as i64], map_row),
+            None => stmt.query_map([], map_row),
+        };
+        if let Ok(rows) = rows {
+            out.extend(rows.flatten().filter(|e| is_openable_url(&e.url)));
+        }
+    };
+
+    collect(bookmark_sql, None, true);
+    if cfg.include_history {
+        collect(history_sql, Some(cfg.history_limit), false);
+    }
+    out
+}
+
+fn collect_chromium_bookmarks(node: &serde_json::Value, profile: &BrowserProfile, out: &mut Vec<WebEntry>) {
+    match node.get("type").and_then(|t| t.as_str()) {
+        Some("url") => {
+            let url = node.get("url").and_then(|u| u.as_str()).unwrap_or_default();
+            if is_openable_url(url) {
+                out.push(WebEntry {
+                    title: node
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    url: url.to_string(),
+                    profile_id: profile.id.clone(),
+                    browser: profile.browser.clone(),
+                    is_bookmark: true,
+                    visit_count: 0,
+                    last_visit_ms: 0,
+                });
+            }
+        }
+        _ => {
+            if let Some(children) = node.get("children").and_then(|c| c.as_array()) {
+                for child in children {
+                    collect_chromium_bookmarks(child, profile, out);
+                }
+            }
+        }
+    }
+}
+
+pub fn parse_chromium_bookmarks(raw: &str, profile: &BrowserProfile) -> Vec<WebEntry> {
+    let Ok(doc) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    if let Some(roots) = doc.get("roots").and_then(|r| r.as_object()) {
+        for root in roots.values() {
+            collect_chromium_bookmarks(root, profile, &mut out);
+        }
+    }
+    out
+}
+
+fn read_chromium(profile: &BrowserProfile, cfg: &BrowserSourceConfig) -> Vec<WebEntry> {
+    let mut out = std::fs::read_to_string(profile.path.join("Bookmarks"))
+        .map(|raw| parse_chromium_bookmarks(&raw, profile))
+        .unwrap_or_default();
+
+    if !cfg.include_history {
+        return out;
+    }
+    let history = profile.path.join("History");
+    if !history.is_file() {
+        return out;
+    }
+    let Ok(tmp) = tempfile::tempdir() else {
+        return out;
+    };
+    let Some(conn) = open_sqlite_copy(&history, &tmp) else {
+        log::warn!("Could not read Chromium history for {}", profile.id);
+        return out;
+    };
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT COALESCE(title, ''), url, visit_count, last_visit_time
+         FROM urls WHERE hidden = 0 AND visit_count > 0
+         ORDER BY visit_count DESC, last_visit_time DESC
+         LIMIT ?1",
+    ) else {
+        return out;
+    };
+    let rows = stmt.query_map([cfg.history_limit as i64], |row| {
+        let webkit_us: i64 = row.get(3)?;
+        Ok(WebEntry {
+            title: row.get(0)?,
+            url: row.get(1)?,
+            profile_id: profile.id.clone(),
+            browser: profile.browser.clone(),
+            is_bookmark: false,
+            visit_count: row.get::<_, i64>(2)?.max(0) as u32,
+            last_visit_ms: (webkit_us - WEBKIT_EPOCH_OFFSET_US).max(0) / 1000,
+        })
+    });
+    if let Ok(rows) = rows {
+        out.extend(rows.flatten().filter(|e| is_openable_url(&e.url)));
+    }
+    out
+}
+
+/// Collapse duplicates across bookmarks/history (and profiles), keeping the
+/// bookmark flag and the highest visit stats.
+fn merge_entries(entries: Vec<WebEntry>) -> Vec<WebEntry> {
+    let mut by_url: HashMap<String, WebEntry> = HashMap::new();
+    for entry in entries {
+        match by_url.get_mut(&entry.url) {
+            Some(existing) => {
+                existing.is_bookmark |= entry.is_bookmark;
+                existing.visit_count = existing.visit_count.max(entry.visit_count);
+                existing.last_visit_ms = existing.last_visit_ms.max(entry.last_visit_ms);
+                if existing.title.is_empty() || entry.is_bookmark && !entry.title.is_empty() {
+                    existing.title = entry.title;
+                }
+            }
+            None => {
+                by_url.insert(entry.url.clone(), entry);
+            }
+        }
+    }
+    by_url.into_values().collect()
+}
+
+pub fn load_entries(cfg: &BrowserSourceConfig) -> Vec<WebEntry> {
+    let start = Instant::now();
+    let mut all = Vec::new();
+    for profile in discover_profiles(cfg).into_iter().filter(|p| p.enabled) {
+        match profile.kind {
+            BrowserKind::Firefox => all.extend(read_firefox(&profile, cfg)),
+            BrowserKind::Chromium => all.extend(read_chromium(&profile, cfg)),
+        }
+    }
+    let merged = merge_entries(all);
+    log::info!(
+        "Browser source loaded: {} entries in {:?}",
+        merged.len(),
+        start.elapsed()
+    );
+    merged
+}
+
+fn web_cache() -> &'static Mutex<WebCache> {
+    WEB_CACHE.get_or_init(|| Mutex::new(WebCache::default()))
+}
+
+fn refresh_in_background(cfg: BrowserSourceConfig) {
+    std::thread::spawn(move || {
+        let entries = Arc::new(load_entries(&cfg));
+        let Ok(mut guard) = web_cache().lock() else {
+            return;
+        };
+        if guard.config.as_ref() == Some(&cfg) {
+            guard.entries = entries;
+            guard.loaded_at = Some(Instant::now());
+        }
+        if guard.refreshing.as_ref() == Some(&cfg) {
+            guard.refreshing = None;
+        }
+    });
+}
+
+/// Snapshot of the cached browser entries. Reading the databases is slow, so a
+/// stale cache or a browser config change starts a reload on a background
+/// thread and the current snapshot (empty after a config change) is returned.
+pub fn cached_entries(cfg: &BrowserSourceConfig) -> Arc<Vec<WebEntry>> {
+    let Ok(mut guard) = web_cache().lock() else {
+        return Arc::new(Vec::new());
+    };
+    if guard.config.as_ref() != Some(cfg) {
+        guard.config = Some(cfg.clone());
+        guard.entries = Arc::new(Vec::new());
+        guard.loaded_at = None;
+    }
+    let stale = guard
+        .loaded_at
+        .map(|t| t.elapsed() >= CACHE_TTL)
+        .unwrap_or(true);
+    if stale && guard.refreshing.as_ref() != Some(cfg) {
+        guard.refreshing = Some(cfg.clone());
+        refresh_in_background(cfg.clone());
+    }
+    Arc::clone(&guard.entries)
+}
+
+fn frecency_bonus(entry: &WebEntry, now_ms: i64) -> u32 {
+    let visits = ((entry.visit_count as f64 + 1.0).ln() * ranking_config::WEB_VISIT_LN_MULTIPLIER)
+        .round() as u32;
+    let mut bonus = visits.min(ranking_config::WEB_VISIT_CAP);
+    if entry.last_visit_ms > 0 {
+        let age_days = (now_ms - entry.last_visit_ms).max(0) / 86_400_000;
+        if age_days <= 3 {
+            bonus += ranking_config::WEB_RECENT_VISIT_BONUS;
+        } else if age_days <= 30 {
+            bonus += ranking_config::WEB_RECENT_VISIT_BONUS / 2;
+        }
+    }
+    if entry.is_bookmark {
+        bonus += ranking_config::WEB_BOOKMARK_BONUS;
+    }
+    bonus
+}
+
+pub fn search_entries(
+    query: &str,
+    entries: &[WebEntry],
+    limit: usize,
+    weight: u32,
+) -> Vec<SearchResult> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    let mut scored: Vec<(u32, Vec<u32>, &WebEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let display_url = entry
+                .url
+                .trim_start_matches("https://")
+                .trim_start_matches("http://");
+            let (raw, indices) = match matcher::fuzzy_score_text(trimmed, &entry.title) {
+                Some((score, indices)) => (score, indices),
+                None => (matcher::fuzzy_score_text(trimmed, display_url)?.0, Vec::new()),
+            };
+            let base = ranking_config::WEB_FUZZY_BASE
+                .saturating_add(raw.saturating_mul(ranking_config::WEB_FUZZY_MULTIPLIER))
+                .saturating_add(frecency_bonus(entry, now_ms));
+            Some((base, indices, entry))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.truncate(limit);
+
+    scored
+        .into_iter()
+        .map(|(score, indices, entry)| {
+            let title = if entry.title.trim().is_empty() {
+                entry.url.clone()
+            } else {
+                entry.title.clone()
+            };
+            SearchResult {
+                title,
+                subtitle: Some(format!("{} · {}", entry.url, entry.browser)),
+                icon: Some(if entry.is_bookmark {
+                    "fa-solid fa-bookmark".to_string()
+                } else {
+                    "fa-solid fa-globe".to_string()
+                }),
+                exec: format!("open-url:{}", entry.url),
+                score: crate::weighted_score(score, weight),
+                match_indices: indices,
+                source: ResultSource::Web,
+                actions: Some(vec![ActionHint {
+                    label: "Copy URL".to_string(),
+                    exec: format!("copy:{}", entry.url),
+                    shortcut: None,
+                }]),
+                id: Some(format!("web:{}", entry.url)),
+                group: None,
+                section: Some("Web".to_string()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> BrowserProfile {
+        BrowserProfile {
+            id: "chrome:Default".to_string(),
+            browser: "Google Chrome".to_string(),
+            name: "Default".to_string(),
+            kind: BrowserKind::Chromium,
+            path: PathBuf::from("/tmp"),
+            enabled: true,
+        }
+    }
+
+    fn entry(title: &str, url: &str, visits: u32, bookmark: bool) -> WebEntry {
+        WebEntry {
+            title: title.to_string(),
+            url: url.to_string(),
+            profile_id: "chrome:Default".to_string(),
+            browser: "Google Chrome".to_string(),
+            is_bookmark: bookmark,
+            visit_count: visits,
+            last_visit_ms: 0,
+        }
+    }
+
+    #[test]
+    fn parses_nested_chromium_bookmarks() {
+        let raw = r#"{
+            "roots": {
+                "bookmark_bar": {"type": "folder", "children": [
+                    {"type": "url", "name": "Rust", "url": "https://www.rust-lang.org/"},
+                    {"type": "folder", "children": [
+                        {"type": "url", "name": "Docs", "url": "https://docs.rs/"},
+                        {"type": "url", "name": "Local", "url": "chrome://settings"}
+                    ]}
+                ]},
+                "other": {"type": "folder", "children": []}
+            }
+        }"#;
+        let entries = parse_chromium_bookmarks(raw, &profile());
+        let urls: Vec<&str> = entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://www.rust-lang.org/", "https://docs.rs/"]);
+        assert!(entries.iter().all(|e| e.is_bookmark));
+    }
+
+    #[test]
+    fn merge_keeps_bookmark_flag_and_visit_counts() {
+        let merged = merge_entries(vec![
+            entry("", "https://docs.rs/", 12, false),
+            entry("Docs", "https://docs.rs/", 0, true),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert!(merged[0].is_bookmark);
+        assert_eq!(merged[0].visit_count, 12);
+        assert_eq!(merged[0].title, "Docs");
+    }
+
+    #[test]
+    fn frequently_visited_pages_rank_higher() {
+        let entries = vec![
+            entry("Rust Blog", "https://blog.rust-lang.org/", 1, false),
+            entry("Rust Docs", "https://doc.rust-lang.org/", 400, false),
+        ];
+        let results = search_entries("rust", &entries, 10, 100);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Rust Docs");
+        assert_eq!(results[0].exec, "open-url:https://doc.rust-lang.org/");
+        assert!(matches!(results[0].source, ResultSource::Web));
+    }
+
+    #[test]
+    fn matches_on_url_when_title_misses() {
+        let entries = vec![entry("Dashboard", "https://grafana.example.com/d/abc", 3, true)];
+        assert_eq!(search_entries("grafana", &entries, 10, 100).len(), 1);
+        assert!(search_entries("", &entries, 10, 100).is_empty());
+    }
+
+    #[test]
+    fn discovers_profiles_and_applies_toggles() {
+        let home = tempfile::tempdir().expect("tempdir");
+        let ff = home.path().join(".mozilla/firefox/abcd.default-release");
+        std::fs::create_dir_all(&ff).unwrap();
+        std::fs::write(ff.join("places.sqlite"), b"").unwrap();
+        let chrome = home.path().join(".config/google-chrome/Profile 1");
+        std::fs::create_dir_all(&chrome).unwrap();
+        std::fs::write(chrome.join("Bookmarks"), b"{}").unwrap();
+
+        let mut cfg = BrowserSourceConfig::default();
+        cfg.profiles.push(crate::config::BrowserProfileToggle {
+            id: "chrome:Profile 1".to_string(),
+            enabled: false,
+        });
+
+        let profiles = discover_profiles_in(home.path(), &cfg);
+        assert_eq!(profiles.len(), 2);
+        let firefox = profiles.iter().find(|p| p.kind == BrowserKind::Firefox).unwrap();
+        assert_eq!(firefox.id, "firefox:abcd.default-release");
+        assert_eq!(firefox.name, "default-release");
+        assert!(firefox.enabled);
+        let chrome = profiles.iter().find(|p| p.id == "chrome:Profile 1").unwrap();
+        assert!(!chrome.enabled);
+    }
+
+    #[test]
+    fn reads_firefox_places_copy() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = dir.path().join("places.sqlite");
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT, visit_count INTEGER, hidden INTEGER, frecency INTEGER, last_visit_date INTEGER);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER, title TEXT);
+             INSERT INTO moz_places VALUES (1, 'https://example.com/', 'Example', 5, 0, 100, 1700000000000000);
+             INSERT INTO moz_places VALUES (2, 'https://news.example.com/', 'News', 9, 0, 200, 1700000000000000);
+             INSERT INTO moz_bookmarks VALUES (1, 1, 1, 'Example Bookmark');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let profile = BrowserProfile {
+            id: "firefox:test".to_string(),
+            browser: "Firefox".to_string(),
+            name: "test".to_string(),
+            kind: BrowserKind::Firefox,
+            path: dir.path().to_path_buf(),
+            enabled: true,
+        };
+        let entries = merge_entries(read_firefox(&profile, &BrowserSourceConfig::default()));
+        assert_eq!(entries.len(), 2);
+        let bookmark = entries.iter().find(|e| e.url == "https://example.com/").unwrap();
+        assert!(bookmark.is_bookmark);
+        assert_eq!(bookmark.visit_count, 5);
+        assert_eq!(bookmark.last_visit_ms, 1_700_000_000_000);
+    }
+}
@@ -0,0 +1,524 @@
+//! Calculator session state: `ans`, named variables (`rate = 0.19`), user
+//! functions (`net(x) = x / (1 + rate)`) and a calculation history that is
+//! searchable with `type:calc`. Everything is persisted to `calc_session.json`
+//! so reopening Vanta picks up where the last session left off.
+
+use meval::{Context, ContextProvider, Expr, FuncEvalError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::config::CalculatorConfig;
+use crate::errors::VantaError;
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::math::{self, CalcOutput};
+use crate::ranking_config;
+
+/// Evaluates the expression in the session, records it and copies the result.
+pub const EXEC_PREFIX: &str = "calc:";
+
+const SESSION_FILE: &str = "calc_session.json";
+const HISTORY_CAP: usize = 200;
+/// Stops `f(x) = f(x)` style definitions from recursing forever.
+const MAX_CALL_DEPTH: usize = 16;
+
+static IDENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").expect("identifier regex must be valid")
+});
+
+static ASSIGNMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([A-Za-z_][A-Za-z0-9_]*)\s*=\s*([^=].*)$")
+        .expect("assignment regex must be valid")
+});
+
+static FUNCTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*([A-Za-z_][A-Za-z0-9_]*)\s*\(\s*([A-Za-z_][A-Za-z0-9_]*(?:\s*,\s*[A-Za-z_][A-Za-z0-9_]*)*)?\s*\)\s*=\s*([^=].*)$",
+    )
+    .expect("function definition regex must be valid")
+});
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UserFunction {
+    pub params: Vec<String>,
+    pub body: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalcEntry {
+    pub expression: String,
+    pub display: String,
+    pub value: String,
+    pub at_ms: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CalcSession {
+    #[serde(default)]
+    pub ans: Option<f64>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub functions: BTreeMap<String, UserFunction>,
+    #[serde(default)]
+    pub history: Vec<CalcEntry>,
+    #[serde(skip)]
+    file_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement {
+    Expression,
+    Assignment(String),
+    Function(String),
+}
+
+/// What a query would do if committed, without touching the session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    pub statement: Statement,
+    pub output: CalcOutput,
+}
+
+/// Variable and function lookup for `meval`: session names first, then built-ins.
+pub struct Scope<'a> {
+    session: Option<&'a CalcSession>,
+    depth: usize,
+    config: CalculatorConfig,
+}
+
+impl Scope<'_> {
+    /// Built-ins only, for callers without a session.
+    pub fn empty() -> Self {
+        Scope {
+            session: None,
+            depth: 0,
+            config: CalculatorConfig::default(),
+        }
+    }
+
+    pub fn config(&self) -> &CalculatorConfig {
+        &self.config
+    }
+
+    /// `ans` or a stored variable, without built-in constants.
+    pub fn session_value(&self, name: &str) -> Option<f64> {
+        let session = self.session?;
+        if name == "ans" {
+            return session.ans;
+        }
+        session.variables.get(name).copied()
+    }
+
+    fn knows(&self, name: &str) -> bool {
+        self.session.is_some_and(|session| {
+            (name == "ans" && session.ans.is_some())
+                || session.variables.contains_key(name)
+                || session.functions.contains_key(name)
+        })
+    }
+
+    /// Whether `expression` uses a session name, which makes it a calculation even
+    /// without digits (`rate * rate`, `ans`).
+    pub fn mentions_session_names(&self, expression: &str) -> bool {
+        IDENT_RE
+            .find_iter(expression)
+            .any(|name| self.knows(name.as_str()))
+    }
+}
+
+impl ContextProvider for Scope<'_> {
+    fn get_var(&self, name: &str) -> Option<f64> {
+        self.session_value(name)
+            .or_else(|| Context::new().get_var(name))
+    }
+
+    fn eval_func(&self, name: &str, args: &[f64]) -> Result<f64, FuncEvalError> {
+        let Some(function) = self.session.and_then(|session| session.functions.get(name)) else {
+            return Context::new().eval_func(name, args);
+        };
+        if args.len() != function.params.len() {
+            return Err(FuncEvalError::NumberArgs(function.params.len()));
+        }
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(FuncEvalError::UnknownFunction);
+        }
+        let expr: Expr = function
+            .body
+            .parse()
+            .map_err(|_| FuncEvalError::UnknownFunction)?;
+        let params: Vec<(&str, f64)> = function
+            .params
+            .iter()
+            .map(String::as_str)
+            .zip(args.iter().copied())
+            .collect();
+        let inner = Scope {
+            session: self.session,
+            depth: self.depth + 1,
+            config: self.config,
+        };
+        expr.eval_with_context((params, inner))
+            .map_err(|_| FuncEvalError::UnknownFunction)
+    }
+}
+
+/// `ans`, constants and built-in functions can't be reassigned.
+fn is_reserved(name: &str) -> bool {
+    let builtins = Context::new();
+    name == "ans"
+        || builtins.get_var(name).is_some()
+        || !matches!(
+            builtins.eval_func(name, &[]),
+            Err(FuncEvalError::UnknownFunction)
+        )
+}
+
+impl CalcSession {
+    pub fn load_or_create(config_dir: &Path) -> Self {
+        let file_path = config_dir.join(SESSION_FILE);
+        let mut session: CalcSession = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        session.file_path = Some(file_path);
+        session
+    }
+
+    pub fn scope(&self, config: &CalculatorConfig) -> Scope<'_> {
+        Scope {
+            session: Some(self),
+            depth: 0,
+            config: *config,
+        }
+    }
+
+    /// Previews `query` as an expression, assignment or function definition.
+    pub fn evaluate(&self, query: &str, config: &CalculatorConfig) -> Option<Evaluation> {
+        let trimmed = query.trim();
+        if let Some(caps) = FUNCTION_RE.captures(trimmed) {
+            let name = caps.get(1)?.as_str();
+            let params = parse_params(caps.get(2).map_or("", |m| m.as_str()));
+            let body = caps.get(3)?.as_str().trim();
+            if is_reserved(name) || params.iter().any(|param| is_reserved(param)) {
+                return None;
+            }
+            // Dry run with every parameter at 1 to catch unknown names and bad syntax.
+            let expr: Expr = body.parse().ok()?;
+            let probe: Vec<(&str, f64)> = params.iter().map(|p| (p.as_str(), 1.0)).collect();
+            expr.eval_with_context((probe, self.scope(config))).ok()?;
+            let signature = format!("{}({})", name, params.join(", "));
+            return Some(Evaluation {
+                statement: Statement::Function(name.to_string()),
+                output: CalcOutput {
+                    display: format!("{} = {}", signature, body),
+                    copy_value: body.to_string(),
+                    value: None,
+                    rates: None,
+                    alternates: Vec::new(),
+                },
+            });
+        }
+
+        if let Some(caps) = ASSIGNMENT_RE.captures(trimmed) {
+            let name = caps.get(1)?.as_str();
+            if is_reserved(name) {
+                return None;
+            }
+            let output = math::evaluate_scoped(caps.get(2)?.as_str(), &self.scope(config))?;
+            output.value?;
+            return Some(Evaluation {
+                statement: Statement::Assignment(name.to_string()),
+                output: CalcOutput {
+                    display: format!("{} = {}", name, output.display),
+                    ..output
+                },
+            });
+        }
+
+        math::evaluate_scoped(trimmed, &self.scope(config)).map(|output| Evaluation {
+            statement: Statement::Expression,
+            output,
+        })
+    }
+
+    /// Applies `query` to the session and records it. Returns the text to copy,
+    /// or `None` for function definitions.
+    pub fn commit(
+        &mut self,
+        query: &str,
+        config: &CalculatorConfig,
+        now_ms: i64,
+    ) -> Result<Option<String>, VantaError> {
+        let expression = query.trim();
+        let evaluation = self
+            .evaluate(expression, config)
+            .ok_or_else(|| format!("Not a valid calculation: {}", expression))?;
+        let output = evaluation.output;
+
+        let copy = match evaluation.statement {
+            Statement::Function(name) => {
+                let caps = FUNCTION_RE
+                    .captures(expression)
+                    .ok_or("Invalid function definition")?;
+                let params = parse_params(caps.get(2).map_or("", |m| m.as_str()));
+                self.functions.insert(
+                    name,
+                    UserFunction {
+                        params,
+                        body: output.copy_value.clone(),
+                    },
+                );
+                None
+            }
+            Statement::Assignment(name) => {
+                if let Some(value) = output.value {
+                    self.variables.insert(name, value);
+                    self.ans = Some(value);
+                }
+                Some(output.copy_value.clone())
+            }
+            Statement::Expression => {
+                if output.value.is_some() {
+                    self.ans = output.value;
+                }
+                Some(output.copy_value.clone())
+            }
+        };
+
+        self.history.retain(|entry| entry.expression != expression);
+        self.history.insert(
+            0,
+            CalcEntry {
+                expression: expression.to_string(),
+                display: output.display,
+                value: output.copy_value,
+                at_ms: now_ms,
+            },
+        );
+        self.history.truncate(HISTORY_CAP);
+        self.save();
+        Ok(copy)
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.file_path {
+            if let Ok(content) = serde_json::to_string_pretty(self) {
+                let _ = fs::write(path, content);
+            }
+        }
+    }
+
+    /// `type:calc` results: variables and functions, then matching history entries.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let query = query.trim();
+        let mut results = Vec::new();
+
+        let definitions = self
+            .variables
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str(),
+                    format!("{} = {}", name, math::format_number(*value)),
+                )
+            })
+            .chain(self.functions.iter().map(|(name, function)| {
+                (
+                    name.as_str(),
+                    format!(
+                        "{}({}) = {}",
+                        name,
+                        function.params.join(", "),
+                        function.body
+                    ),
+                )
+            }));
+        for (name, title) in definitions {
+            let score = if query.is_empty() {
+                ranking_config::CALC_VARIABLE_SCORE
+            } else {
+                match fuzzy_score_text(query, name) {
+                    Some((raw, _)) => ranking_config::CALC_VARIABLE_SCORE + raw,
+                    None => continue,
+                }
+            };
+            results.push(SearchResult {
+                title,
+                subtitle: Some("Session Variable".to_string()),
+                icon: Some("calculator".to_string()),
+                exec: format!("fill:{}", name),
+                score,
+                match_indices: vec![],
+                source: ResultSource::Calculator,
+                actions: None,
+                id: None,
+                group: None,
+                section: Some("Variables".to_string()),
+            });
+        }
+
+        let mut history = Vec::new();
+        for (position, entry) in self.history.iter().enumerate() {
+            let recency = ranking_config::CALC_HISTORY_RECENCY_BONUS
+                .saturating_sub(position as u32 * ranking_config::CALC_HISTORY_RECENCY_STEP);
+            let score = if query.is_empty() {
+                ranking_config::CALC_HISTORY_FUZZY_BASE + recency
+            } else {
+                let haystack = format!("{} {}", entry.expression, entry.value);
+                match fuzzy_score_text(query, &haystack) {
+                    Some((raw, _)) => {
+                        ranking_config::CALC_HISTORY_FUZZY_BASE
+                            + raw * ranking_config::CALC_HISTORY_FUZZY_MULTIPLIER
+                            + recency
+                    }
+                    None => continue,
+                }
+            };
+            history.push(history_result(entry, score));
+        }
+        history.sort_by_key(|result| std::cmp::Reverse(result.score));
+        history.truncate(limit);
+        results.extend(history);
+        results
+    }
+}
+
+fn parse_params(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn history_result(entry: &CalcEntry, score: u32) -> SearchResult {
+    SearchResult {
+        title: format!("= {}", entry.display),
+        subtitle: Some(entry.expression.clone()),
+        icon: Some("calculator".to_string()),
+        exec: format!("copy:{}", entry.value),
+        score,
+        match_indices: vec![],
+        source: ResultSource::Calculator,
+        actions: Some(vec![
+            ActionHint {
+                label: "Copy Result".to_string(),
+                exec: format!("copy:{}", entry.value),
+                shortcut: None,
+            },
+            ActionHint {
+                label: "Edit Expression".to_string(),
+                exec: format!("fill:{}", entry.expression),
+                shortcut: Some("Tab".to_string()),
+            },
+        ]),
+        id: None,
+        group: None,
+        section: Some("Calculation History".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalcSession, Statement};
+    use crate::config::{CalculatorConfig, RoundingMode};
+
+    const CONFIG: CalculatorConfig = CalculatorConfig {
+        precision: 10,
+        rounding: RoundingMode::HalfUp,
+    };
+
+    #[test]
+    fn binds_ans_variables_and_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = CalcSession::load_or_create(dir.path());
+
+        assert_eq!(
+            session
+                .commit("rate = 0.19", &CONFIG, 1)
+                .unwrap()
+                .as_deref(),
+            Some("0.19")
+        );
+        assert_eq!(
+            session
+                .commit("1200 * rate", &CONFIG, 2)
+                .unwrap()
+                .as_deref(),
+            Some("228")
+        );
+        assert_eq!(
+            session.commit("ans / 2", &CONFIG, 3).unwrap().as_deref(),
+            Some("114")
+        );
+
+        assert_eq!(
+            session
+                .commit("net(x) = x / (1 + rate)", &CONFIG, 4)
+                .unwrap(),
+            None
+        );
+        let eval = session.evaluate("net(119)", &CONFIG).unwrap();
+        assert_eq!(eval.statement, Statement::Expression);
+        assert_eq!(eval.output.copy_value, "100");
+        assert_eq!(
+            session.evaluate("rate", &CONFIG).unwrap().output.copy_value,
+            "0.19"
+        );
+
+        // Reopening restores the whole session.
+        let reloaded = CalcSession::load_or_create(dir.path());
+        assert_eq!(
+            reloaded.evaluate("ans", &CONFIG).unwrap().output.copy_value,
+            "114"
+        );
+        assert_eq!(reloaded.variables.get("rate"), Some(&0.19));
+        assert_eq!(
+            reloaded
+                .evaluate("net(238)", &CONFIG)
+                .unwrap()
+                .output
+                .copy_value,
+            "200"
+        );
+        assert_eq!(reloaded.history.len(), 4);
+    }
+
+    #[test]
+    fn rejects_reserved_names_and_runaway_recursion() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = CalcSession::load_or_create(dir.path());
+        assert!(session.evaluate("pi = 3", &CONFIG).is_none());
+        assert!(session.evaluate("ans = 3", &CONFIG).is_none());
+        assert!(session.evaluate("sqrt(x) = x", &CONFIG).is_none());
+        assert!(session.evaluate("f(x) = x + y", &CONFIG).is_none());
+        assert!(session.commit("x == 3", &CONFIG, 1).is_err());
+
+        session.commit("f(x) = x + 1", &CONFIG, 1).unwrap();
+        session
+            .commit("f(x) = f(x)", &CONFIG, 2)
+            .unwrap_or_default();
+        assert!(session.evaluate("f(2)", &CONFIG).is_none());
+    }
+
+    #[test]
+    fn searches_history_and_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = CalcSession::load_or_create(dir.path());
+        session.commit("vat = 0.2", &CONFIG, 1).unwrap();
+        session.commit("40 * 3", &CONFIG, 2).unwrap();
+        session.commit("99 * vat", &CONFIG, 3).unwrap();
+
+        let all = session.search("", 10);
+        assert_eq!(all[0].title, "vat = 0.2");
+        assert_eq!(all[1].subtitle.as_deref(), Some("99 * vat"));
+
+        let found = session.search("40", 10);
+        assert!(found.iter().any(|r| r.exec == "copy:120"));
+        assert!(!found.iter().any(|r| r.exec == "copy:19.8"));
+    }
+}
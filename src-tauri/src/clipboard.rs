@@ -1,35 +1,110 @@
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, Result};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
-// use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
 
+use crate::clipboard_backend::{self, ClipboardBackend, WatchEnd};
+use crate::clipboard_crypto::{self, Sealer};
+use crate::clipboard_filter::{SensitiveFilter, Verdict};
+use crate::clipboard_index::{self, ClipQuery, IndexedEntry};
+use crate::config::{ClipboardConfig, ClipboardDedup};
+use crate::errors::VantaError;
+use crate::launcher;
+use crate::windows;
+
+/// Longest edge of the thumbnails stored next to copied images.
+const THUMBNAIL_EDGE: u32 = 192;
+/// Images with more pixels than this are stored without a thumbnail.
+const THUMBNAIL_MAX_PIXELS: u64 = 64_000_000;
+
+/// Exec prefix that re-copies a history entry with its original MIME type.
+pub const EXEC_RESTORE_PREFIX: &str = "clip-restore:";
+/// Exec prefix that re-copies an entry's HTML instead of its plain text.
+pub const EXEC_RESTORE_HTML_PREFIX: &str = "clip-restore-html:";
+/// Exec prefix that re-copies an entry and pastes it into the previous window.
+pub const EXEC_PASTE_PREFIX: &str = "clip-paste:";
+/// Exec that drops the history key from memory.
+pub const EXEC_LOCK: &str = "clip-lock";
+/// Exec that reloads the history key.
+pub const EXEC_UNLOCK: &str = "clip-unlock";
+
 #[derive(Debug, Serialize)]
 pub struct ClipboardItem {
     pub id: i64,
+    /// Plain text, or a label such as `Image 1920×1080 (PNG, 245 KB)` for images.
     pub content: String,
     pub timestamp: DateTime<Utc>,
     pub pinned: bool,
+    /// `text`, `url`, `email`, `color`, `path`, `json`, `code`, `html` or `image`.
     pub content_type: String,
+    /// The MIME type the entry was captured as and is restored with.
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_size: Option<u64>,
 }
 
-fn get_db_path() -> PathBuf {
+impl ClipboardItem {
+    pub fn is_image(&self) -> bool {
+        self.image_path.is_some()
+    }
+}
+
+fn vanta_dir() -> PathBuf {
     let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
     path.push("vanta");
     let _ = fs::create_dir_all(&path);
-    path.push("clipboard.db");
+    path
+}
+
+fn get_db_path() -> PathBuf {
+    vanta_dir().join("clipboard.db")
+}
+
+/// Where copied images and their thumbnails are kept.
+pub fn images_dir() -> PathBuf {
+    let path = vanta_dir().join("clipboard-images");
+    let _ = fs::create_dir_all(&path);
     path
 }
 
 pub fn init_db() -> Result<()> {
     let path = get_db_path();
     let conn = Connection::open(path)?;
+    create_schema(&conn)?;
 
+    match clipboard_crypto::sealer() {
+        Ok(sealer) => {
+            let migrated = encrypt_plaintext_rows(&conn, &sealer, &images_dir())?;
+            if migrated > 0 {
+                log::info!("Encrypted {} existing clipboard entries", migrated);
+                // Drop the plaintext left behind in free pages.
+                conn.execute_batch("VACUUM;")?;
+            }
+        }
+        Err(e) => log::warn!("Clipboard history stays unencrypted for now: {}", e),
+    }
+
+    Ok(())
+}
+
+fn create_schema(conn: &Connection) -> Result<()> {
     conn.execute(
         "CREATE TABLE IF NOT EXISTS clipboard (
             id INTEGER PRIMARY KEY,
@@ -39,6 +114,14 @@ pub fn init_db() -> Result<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS clipboard_meta (
+            key TEXT PRIMARY KEY,
+            value INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     conn.execute_batch(
         "ALTER TABLE clipboard ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;",
     ).ok();
@@ -47,9 +130,116 @@ pub fn init_db() -> Result<()> {
         "ALTER TABLE clipboard ADD COLUMN content_type TEXT NOT NULL DEFAULT 'text';",
     ).ok();
 
+    for column in [
+        "mime_type TEXT NOT NULL DEFAULT 'text/plain'",
+        "html TEXT",
+        "image_path TEXT",
+        "thumbnail_path TEXT",
+        "width INTEGER",
+        "height INTEGER",
+        "byte_size INTEGER",
+        // Unix millis after which a sensitive entry is purged.
+        "expires_at INTEGER",
+        // Whether `content`, `html` and image files are sealed with the clipboard key.
+        "encrypted INTEGER NOT NULL DEFAULT 0",
+        // Keyed hash of the plain text, for spotting duplicates.
+        "content_hash TEXT",
+    ] {
+        conn.execute_batch(&format!("ALTER TABLE clipboard ADD COLUMN {};", column))
+            .ok();
+    }
+
     Ok(())
 }
 
+fn seal(sealer: &Sealer, plain: &str) -> Result<String> {
+    sealer
+        .seal(plain)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Encrypts entries written before history was encrypted at rest. Images are
+/// stored again sealed in `dir` and their plaintext files removed.
+fn encrypt_plaintext_rows(conn: &Connection, sealer: &Sealer, dir: &Path) -> Result<usize> {
+    let text_rows = encrypt_plaintext_text(conn, sealer)?;
+    let image_rows = encrypt_plaintext_images(conn, sealer, dir)?;
+    Ok(text_rows + image_rows)
+}
+
+fn encrypt_plaintext_text(conn: &Connection, sealer: &Sealer) -> Result<usize> {
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, content, html FROM clipboard WHERE encrypted = 0 AND image_path IS NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?;
+        rows.collect::<Result<Vec<_>>>()?
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, content, html) in &rows {
+        let html = html.as_deref().map(|html| seal(sealer, html)).transpose()?;
+        tx.execute(
+            "UPDATE clipboard SET content = ?1, html = ?2, content_hash = ?3, encrypted = 1 WHERE id = ?4",
+            params![seal(sealer, content)?, html, sealer.fingerprint(content), id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(rows.len())
+}
+
+fn encrypt_plaintext_images(conn: &Connection, sealer: &Sealer, dir: &Path) -> Result<usize> {
+    let rows = {
+        let mut stmt = conn.prepare(
+            "SELECT id, content, mime_type, image_path, thumbnail_path FROM clipboard
+             WHERE encrypted = 0 AND image_path IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+        rows.collect::<Result<Vec<_>>>()?
+    };
+
+    let mut old_paths = Vec::new();
+    for (id, label, mime, image_path, thumbnail_path) in &rows {
+        // A missing file can't be restored either way; only the label is sealed.
+        let stored = match fs::read(image_path) {
+            Ok(bytes) => Some(
+                store_image(dir, sealer, &bytes, mime)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+            ),
+            Err(e) => {
+                log::warn!("Clipboard image {} could not be encrypted: {}", id, e);
+                None
+            }
+        };
+        let (image_path, thumbnail_path) = match stored {
+            Some(stored) => {
+                old_paths.push(image_path.clone());
+                old_paths.extend(thumbnail_path.clone());
+                (
+                    stored.path.to_string_lossy().to_string(),
+                    stored.thumbnail.map(|p| p.to_string_lossy().to_string()),
+                )
+            }
+            None => (image_path.clone(), thumbnail_path.clone()),
+        };
+        conn.execute(
+            "UPDATE clipboard SET content = ?1, image_path = ?2, thumbnail_path = ?3, encrypted = 1 WHERE id = ?4",
+            params![seal(sealer, label)?, image_path, thumbnail_path, id],
+        )?;
+    }
+    remove_unreferenced_files(conn, old_paths)?;
+    Ok(rows.len())
+}
+
 fn detect_content_type(content: &str) -> &'static str {
     let trimmed = content.trim();
     if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
@@ -89,106 +279,865 @@ fn detect_content_type(content: &str) -> &'static str {
     "text"
 }
 
-fn save_item(content: &str) -> Result<()> {
-    let path = get_db_path();
-    let conn = Connection::open(path)?;
+/// What to read from the clipboard, decided from the offered MIME types.
+#[derive(Debug, PartialEq)]
+enum Capture {
+    /// Plain text in the offered type to read it as, plus whether an HTML
+    /// rendering is on offer too.
+    Text { mime: String, html: bool },
+    Image(String),
+}
 
-    let mut stmt = conn.prepare("SELECT content FROM clipboard ORDER BY id DESC LIMIT 1")?;
-    let exists = stmt
-        .query_row([], |row| row.get::<_, String>(0))
-        .optional()?;
+fn is_plain_text_type(mime: &str) -> bool {
+    mime.starts_with("text/plain") || matches!(mime, "UTF8_STRING" | "STRING" | "TEXT")
+}
+
+/// Text wins when both are offered (spreadsheet cells also come as a rendered
+/// image); otherwise the first image type, preferring PNG. UTF-8 text types are
+/// preferred over the legacy X11 `STRING`/`TEXT` targets.
+fn pick_capture(types: &[String]) -> Option<Capture> {
+    let text = ["text/plain;charset=utf-8", "text/plain", "UTF8_STRING"]
+        .iter()
+        .find_map(|wanted| types.iter().find(|t| t.as_str() == *wanted))
+        .or_else(|| types.iter().find(|t| is_plain_text_type(t)));
+    if let Some(mime) = text {
+        return Some(Capture::Text {
+            mime: mime.clone(),
+            html: types.iter().any(|t| t == "text/html"),
+        });
+    }
+    if types.iter().any(|t| t == "image/png") {
+        return Some(Capture::Image("image/png".to_string()));
+    }
+    types
+        .iter()
+        .find(|t| t.starts_with("image/"))
+        .map(|t| Capture::Image(t.clone()))
+}
+
+fn image_extension(mime: &str) -> &str {
+    match mime {
+        "image/jpeg" => "jpg",
+        "image/svg+xml" => "svg",
+        other => other
+            .strip_prefix("image/")
+            .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+    }
+}
+
+fn human_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
 
-    if let Some(last_content) = exists {
-        if last_content == content {
-            return Ok(());
+fn image_label(mime: &str, dimensions: Option<(u32, u32)>, bytes: u64) -> String {
+    let format = image_extension(mime).to_uppercase();
+    match dimensions {
+        Some((w, h)) => format!("Image {}×{} ({}, {})", w, h, format, human_size(bytes)),
+        None => format!("Image ({}, {})", format, human_size(bytes)),
+    }
+}
+
+/// Decodes a PNG to RGBA8, returning `(width, height, pixels)`. Only the header
+/// is read when the image is too large to thumbnail.
+fn decode_png(bytes: &[u8]) -> Option<(u32, u32, Option<Vec<u8>>)> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width as u64 * height as u64 > THUMBNAIL_MAX_PIXELS {
+        return Some((width, height, None));
+    }
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(frame.buffer_size());
+    let rgba: Vec<u8> = match frame.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buf.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Some((width, height, None)),
+    };
+    Some((width, height, Some(rgba)))
+}
+
+/// Box-filters RGBA pixels down so the longest edge is at most `max_edge`.
+fn downscale(rgba: &[u8], width: u32, height: u32, max_edge: u32) -> (u32, u32, Vec<u8>) {
+    let scale = (max_edge as f64 / width.max(height) as f64).min(1.0);
+    let tw = ((width as f64 * scale).round() as u32).max(1);
+    let th = ((height as f64 * scale).round() as u32).max(1);
+    let mut out = Vec::with_capacity((tw * th * 4) as usize);
+    for ty in 0..th {
+        let (y0, y1) = (ty * height / th, ((ty + 1) * height / th).max(ty * height / th + 1));
+        for tx in 0..tw {
+            let (x0, x1) = (tx * width / tw, ((tx + 1) * width / tw).max(tx * width / tw + 1));
+            let mut sum = [0u64; 4];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let i = ((y * width + x) * 4) as usize;
+                    for (c, total) in sum.iter_mut().enumerate() {
+                        *total += rgba[i + c] as u64;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u64;
+            out.extend(sum.iter().map(|total| (total / count) as u8));
+        }
+    }
+    (tw, th, out)
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().ok()?.write_image_data(rgba).ok()?;
+    Some(out)
+}
+
+/// A copied image sealed into `dir`, named by keyed content hash so repeats
+/// share a file.
+#[derive(Debug)]
+struct StoredImage {
+    path: PathBuf,
+    thumbnail: Option<PathBuf>,
+    dimensions: Option<(u32, u32)>,
+}
+
+fn store_image(dir: &Path, sealer: &Sealer, bytes: &[u8], mime: &str) -> std::io::Result<StoredImage> {
+    let hash = sealer.fingerprint_bytes(bytes);
+    let path = dir.join(format!("{}.{}.sealed", &hash[..32], image_extension(mime)));
+    if !path.exists() {
+        fs::write(&path, sealer.seal_bytes(bytes).map_err(std::io::Error::other)?)?;
+    }
+
+    let decoded = (mime == "image/png").then(|| decode_png(bytes)).flatten();
+    let dimensions = decoded.as_ref().map(|(w, h, _)| (*w, *h));
+    let thumbnail = decoded.and_then(|(w, h, rgba)| {
+        let (tw, th, small) = downscale(&rgba?, w, h, THUMBNAIL_EDGE);
+        let thumb_path = dir.join(format!("{}.thumb.png.sealed", &hash[..32]));
+        let sealed = sealer.seal_bytes(&encode_png(tw, th, &small)?).ok()?;
+        fs::write(&thumb_path, sealed).ok()?;
+        Some(thumb_path)
+    });
+
+    Ok(StoredImage {
+        path,
+        thumbnail,
+        dimensions,
+    })
+}
+
+/// Removes image files once no row refers to them any more.
+fn remove_unreferenced_files(conn: &Connection, paths: Vec<String>) -> Result<()> {
+    for path in paths {
+        let still_used: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM clipboard WHERE image_path = ?1 OR thumbnail_path = ?1",
+            params![path],
+            |row| row.get(0),
+        )?;
+        if still_used == 0 {
+            let _ = fs::remove_file(&path);
         }
     }
+    Ok(())
+}
+
+fn file_paths_where(conn: &Connection, clause: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT image_path, thumbnail_path FROM clipboard WHERE {}",
+        clause
+    ))?;
+    let rows = stmt.query_map(args, |row| {
+        Ok([
+            row.get::<_, Option<String>>(0)?,
+            row.get::<_, Option<String>>(1)?,
+        ])
+    })?;
+    let mut paths = Vec::new();
+    for row in rows {
+        paths.extend(row?.into_iter().flatten());
+    }
+    Ok(paths)
+}
+
+/// Deletes the rows matching `clause` along with image files nothing else uses.
+fn delete_where(conn: &Connection, clause: &str, args: &[&dyn rusqlite::ToSql]) -> Result<()> {
+    let ids = conn
+        .prepare(&format!("SELECT id FROM clipboard WHERE {}", clause))?
+        .query_map(args, |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let paths = file_paths_where(conn, clause, args)?;
+    conn.execute(&format!("DELETE FROM clipboard WHERE {}", clause), args)?;
+    clipboard_index::update(|index| index.remove(&ids));
+    remove_unreferenced_files(conn, paths)
+}
+
+/// Applies the retention settings. Pinned entries are always kept.
+fn prune(conn: &Connection, config: &ClipboardConfig) -> Result<()> {
+    let max_items = config.max_items as i64;
+    delete_where(
+        conn,
+        "pinned = 0 AND id NOT IN (SELECT id FROM clipboard ORDER BY pinned DESC, id DESC LIMIT ?1)",
+        &[&max_items],
+    )?;
+
+    for (content_type, days) in &config.retention_days {
+        let cutoff = format!("-{} days", days);
+        delete_where(
+            conn,
+            "pinned = 0 AND content_type = ?1 AND julianday(timestamp) < julianday('now', ?2)",
+            &[content_type, &cutoff],
+        )?;
+    }
+
+    if let Some(days) = config.max_age_days {
+        let cutoff = format!("-{} days", days);
+        let overridden = config.retention_days.keys().collect::<Vec<_>>();
+        let placeholders = (0..overridden.len())
+            .map(|i| format!("?{}", i + 2))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut args: Vec<&dyn rusqlite::ToSql> = vec![&cutoff];
+        args.extend(overridden.iter().map(|ct| *ct as &dyn rusqlite::ToSql));
+        delete_where(
+            conn,
+            &format!(
+                "pinned = 0 AND julianday(timestamp) < julianday('now', ?1) AND content_type NOT IN ({})",
+                placeholders
+            ),
+            &args,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Drops sensitive entries whose time is up; pinning one keeps it.
+fn purge_expired(conn: &Connection) -> Result<()> {
+    let now = Utc::now().timestamp_millis();
+    delete_where(
+        conn,
+        "pinned = 0 AND expires_at IS NOT NULL AND expires_at <= ?1",
+        &[&now],
+    )
+}
+
+/// Rebuilds the database file once `interval_hours` have passed since the last
+/// run, so deleted entries don't keep it large (or linger in free pages).
+fn vacuum_if_due(conn: &Connection, interval_hours: u32) -> Result<()> {
+    if interval_hours == 0 {
+        return Ok(());
+    }
+    let now = Utc::now().timestamp_millis();
+    let last: Option<i64> = conn
+        .query_row(
+            "SELECT value FROM clipboard_meta WHERE key = 'last_vacuum_ms'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if last.is_some_and(|last| now - last < i64::from(interval_hours) * 3_600_000) {
+        return Ok(());
+    }
+    conn.execute_batch("VACUUM;")?;
+    conn.execute(
+        "INSERT INTO clipboard_meta (key, value) VALUES ('last_vacuum_ms', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![now],
+    )?;
+    Ok(())
+}
+
+fn maintain(conn: &Connection, config: &ClipboardConfig) -> Result<()> {
+    purge_expired(conn)?;
+    prune(conn, config)?;
+    vacuum_if_due(conn, config.vacuum_interval_hours)
+}
+
+/// Handles a copy of something already in the history, found by `column = key`,
+/// according to `config.dedup`. Returns true when nothing should be inserted.
+fn dedup(
+    conn: &Connection,
+    config: &ClipboardConfig,
+    column: &str,
+    key: &str,
+    expires_at: Option<i64>,
+) -> Result<bool> {
+    let lookup = match config.dedup {
+        ClipboardDedup::Off => return Ok(false),
+        ClipboardDedup::Consecutive => format!(
+            "SELECT id FROM clipboard WHERE id = (SELECT MAX(id) FROM clipboard) AND {} = ?1",
+            column
+        ),
+        ClipboardDedup::BumpToTop => format!(
+            "SELECT id FROM clipboard WHERE {} = ?1 ORDER BY id DESC LIMIT 1",
+            column
+        ),
+    };
+    let Some(id) = conn
+        .query_row(&lookup, params![key], |row| row.get::<_, i64>(0))
+        .optional()?
+    else {
+        return Ok(false);
+    };
+
+    if config.dedup == ClipboardDedup::BumpToTop {
+        let now = Utc::now();
+        conn.execute(
+            "UPDATE clipboard SET timestamp = ?1, expires_at = ?2 WHERE id = ?3",
+            params![now.to_rfc3339(), expires_at, id],
+        )?;
+        let newest: i64 = conn.query_row("SELECT MAX(id) FROM clipboard", [], |row| row.get(0))?;
+        let new_id = if newest > id {
+            conn.execute(
+                "UPDATE clipboard SET id = ?1 WHERE id = ?2",
+                params![newest + 1, id],
+            )?;
+            newest + 1
+        } else {
+            id
+        };
+        clipboard_index::update(|index| index.bump(id, new_id, now.timestamp_millis()));
+    }
+    Ok(true)
+}
+
+fn save_item(
+    config: &ClipboardConfig,
+    sealer: &Sealer,
+    content: &str,
+    html: Option<&str>,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    let path = get_db_path();
+    let conn = Connection::open(path)?;
+
+    let hash = sealer.fingerprint(content);
+    if dedup(&conn, config, "content_hash", &hash, expires_at)? {
+        return prune(&conn, config);
+    }
 
     let now = Utc::now().to_rfc3339();
-    let ct = detect_content_type(content);
+    let ct = match (detect_content_type(content), html) {
+        ("text", Some(_)) => "html",
+        (detected, _) => detected,
+    };
+    let mime = if html.is_some() { "text/html" } else { "text/plain" };
+    let html = html.map(|html| seal(sealer, html)).transpose()?;
     conn.execute(
-        "INSERT INTO clipboard (content, timestamp, pinned, content_type) VALUES (?1, ?2, 0, ?3)",
-        params![content, now, ct],
+        "INSERT INTO clipboard (content, timestamp, pinned, content_type, mime_type, html, expires_at, encrypted, content_hash)
+         VALUES (?1, ?2, 0, ?3, ?4, ?5, ?6, 1, ?7)",
+        params![seal(sealer, content)?, now, ct, mime, html, expires_at, hash],
     )?;
+    index_new_entry(conn.last_insert_rowid(), content, ct);
+
+    prune(&conn, config)
+}
+
+fn save_image(
+    config: &ClipboardConfig,
+    sealer: &Sealer,
+    stored: StoredImage,
+    mime: &str,
+    byte_size: u64,
+) -> Result<()> {
+    let path = get_db_path();
+    let conn = Connection::open(path)?;
 
+    let image_path = stored.path.to_string_lossy().to_string();
+    if dedup(&conn, config, "image_path", &image_path, None)? {
+        return prune(&conn, config);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let label = image_label(mime, stored.dimensions, byte_size);
     conn.execute(
-        "DELETE FROM clipboard WHERE pinned = 0 AND id NOT IN (SELECT id FROM clipboard ORDER BY pinned DESC, id DESC LIMIT 100)",
-        [],
+        "INSERT INTO clipboard (content, timestamp, pinned, content_type, mime_type, image_path, thumbnail_path, width, height, byte_size, encrypted)
+         VALUES (?1, ?2, 0, 'image', ?3, ?4, ?5, ?6, ?7, ?8, 1)",
+        params![
+            seal(sealer, &label)?,
+            now,
+            mime,
+            image_path,
+            stored.thumbnail.map(|p| p.to_string_lossy().to_string()),
+            stored.dimensions.map(|(w, _)| w),
+            stored.dimensions.map(|(_, h)| h),
+            byte_size as i64,
+        ],
     )?;
+    index_new_entry(conn.last_insert_rowid(), &label, "image");
 
-    Ok(())
+    prune(&conn, config)
 }
 
-pub fn start_watcher() {
-    thread::spawn(|| {
-        println!("Clipboard watcher started");
-        let mut last_content = String::new();
+fn index_new_entry(id: i64, content: &str, content_type: &str) {
+    clipboard_index::update(|index| {
+        index.insert(&IndexedEntry {
+            id,
+            content: content.to_string(),
+            content_type: content_type.to_string(),
+            timestamp_ms: Utc::now().timestamp_millis(),
+            pinned: false,
+        })
+    });
+}
 
-        loop {
-            // Run wl-paste once (request text/plain to avoid binary/images)
-            if let Ok(output) = Command::new("wl-paste")
-                .args(["--type", "text/plain"])
-                .output()
-            {
-                // Truncate output to prevent massive memory/DB spikes (e.g. 100,000 chars limit)
-                let raw_content = String::from_utf8_lossy(&output.stdout);
-                let content = if raw_content.len() > 100_000 {
-                    format!("{}... [Truncated by Vanta]", &raw_content[..100_000])
-                } else {
-                    raw_content.to_string()
-                };
-
-                if !content.is_empty() && content != last_content {
-                    println!(
-                        "Clipboard changed: {}",
-                        content.chars().take(20).collect::<String>()
-                    );
-                    if let Err(e) = save_item(&content) {
-                        eprintln!("Failed to save clipboard: {}", e);
+/// Truncate text to prevent massive memory/DB spikes (`clipboard.max_text_bytes`).
+fn truncate_text(raw: &[u8], limit: usize) -> String {
+    let raw_content = String::from_utf8_lossy(raw);
+    if raw_content.len() > limit {
+        let mut end = limit;
+        while !raw_content.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}... [Truncated by Vanta]", &raw_content[..end])
+    } else {
+        raw_content.to_string()
+    }
+}
+
+/// Reads whatever the clipboard currently holds and records it if it changed.
+struct Capturer {
+    backend: Arc<dyn ClipboardBackend>,
+    config: ClipboardConfig,
+    filter: SensitiveFilter,
+    /// Plain text for text entries, `mime:sha256` for images.
+    last_seen: String,
+}
+
+impl Capturer {
+    fn capture(&mut self) {
+        // Nothing is recorded while history is locked.
+        let Ok(sealer) = clipboard_crypto::sealer() else {
+            return;
+        };
+        let backend = self.backend.clone();
+        let Some(types) = backend.list_types() else {
+            return;
+        };
+        if self.filter.is_password(&types, |mime| backend.read(mime)) {
+            log::debug!("Clipboard entry skipped: flagged by a password manager");
+            // Forget the previous entry so copying it again after the password is recorded.
+            self.last_seen.clear();
+            return;
+        }
+        match pick_capture(&types) {
+            Some(Capture::Text { mime, html }) => {
+                if let Some(raw) = backend.read(&mime) {
+                    let content = truncate_text(&raw, self.config.max_text_bytes);
+                    if !content.is_empty() && content != self.last_seen {
+                        if self.copied_in_excluded_window() {
+                            self.last_seen = content;
+                            return;
+                        }
+                        let expires_at = match self.filter.check_text(&content) {
+                            Verdict::Keep => None,
+                            Verdict::Expire(after) => {
+                                Some(Utc::now().timestamp_millis() + after.as_millis() as i64)
+                            }
+                            Verdict::Skip(reason) => {
+                                log::debug!("Clipboard entry skipped: {}", reason);
+                                self.last_seen = content;
+                                return;
+                            }
+                        };
+                        let html = html
+                            .then(|| backend.read("text/html"))
+                            .flatten()
+                            .map(|raw| truncate_text(&raw, self.config.max_text_bytes))
+                            .filter(|html| !html.trim().is_empty());
+                        if let Err(e) = save_item(&self.config, &sealer, &content, html.as_deref(), expires_at) {
+                            log::warn!("Failed to save clipboard: {}", e);
+                        }
+                        record_change();
+                        self.last_seen = content;
+                    }
+                }
+            }
+            Some(Capture::Image(mime)) => {
+                if let Some(bytes) = backend.read(&mime) {
+                    let fingerprint = format!("{}:{}", mime, hex::encode(Sha256::digest(&bytes)));
+                    if fingerprint == self.last_seen || bytes.is_empty() {
+                        return;
+                    }
+                    if self.copied_in_excluded_window() {
+                        self.last_seen = fingerprint;
+                        return;
                     }
-                    last_content = content;
+                    if bytes.len() as u64 > self.config.max_image_bytes {
+                        log::info!(
+                            "Clipboard image skipped: {} exceeds the size cap",
+                            human_size(bytes.len() as u64)
+                        );
+                    } else {
+                        let saved = store_image(&images_dir(), &sealer, &bytes, &mime)
+                            .map_err(|e| e.to_string())
+                            .and_then(|stored| {
+                                save_image(&self.config, &sealer, stored, &mime, bytes.len() as u64)
+                                    .map_err(|e| e.to_string())
+                            });
+                        if let Err(e) = saved {
+                            log::warn!("Failed to save clipboard image: {}", e);
+                        }
+                        record_change();
+                    }
+                    self.last_seen = fingerprint;
                 }
-            } else {
-                eprintln!("Failed to run wl-paste");
             }
+            None => {}
+        }
+    }
+
+    /// Whether the focused window, which the copy most likely came from, is one
+    /// whose copies are never recorded.
+    fn copied_in_excluded_window(&self) -> bool {
+        if !self.filter.checks_source_window() {
+            return false;
+        }
+        match windows::focused_window() {
+            Some(window) if self.filter.excludes_window(&window.class) => {
+                log::debug!("Clipboard entry skipped: copied from {}", window.class);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// What the clipboard watcher is doing, shown on the health dashboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatcherState {
+    Starting,
+    Watching,
+    /// The watch process exited; it is restarted after a back-off.
+    Restarting,
+    /// No clipboard tool could be started.
+    BackendMissing,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct WatcherStatus {
+    pub state: WatcherState,
+    pub detail: String,
+    pub last_change_ms: Option<i64>,
+    /// Consecutive failed starts since the watcher last ran healthily.
+    pub failures: u32,
+}
+
+static WATCHER_STATUS: LazyLock<Mutex<WatcherStatus>> = LazyLock::new(|| {
+    Mutex::new(WatcherStatus {
+        state: WatcherState::Starting,
+        detail: "Clipboard watcher has not started".to_string(),
+        last_change_ms: None,
+        failures: 0,
+    })
+});
+
+const BACKOFF_INITIAL: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(300);
+/// A watch that lasted this long was healthy, so the back-off starts over.
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+/// How often retention, expiry and vacuuming run.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60);
+/// The interval instead when sensitive entries expire.
+const EXPIRY_SWEEP: Duration = Duration::from_secs(5);
+
+pub fn watcher_status() -> WatcherStatus {
+    WATCHER_STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+/// Updates the watcher state, logging only transitions so a missing backend
+/// doesn't flood the log.
+fn set_status(state: WatcherState, detail: String, failures: u32) {
+    let Ok(mut status) = WATCHER_STATUS.lock() else {
+        return;
+    };
+    if status.state != state || status.detail != detail {
+        match state {
+            WatcherState::BackendMissing | WatcherState::Restarting => {
+                log::warn!("Clipboard watcher: {}", detail)
+            }
+            _ => log::info!("Clipboard watcher: {}", detail),
+        }
+    }
+    status.state = state;
+    status.detail = detail;
+    status.failures = failures;
+}
+
+fn record_change() {
+    if let Ok(mut status) = WATCHER_STATUS.lock() {
+        status.last_change_ms = Some(Utc::now().timestamp_millis());
+    }
+}
+
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(BACKOFF_MAX)
+}
+
+/// Watches the clipboard through the active backend, capturing on each change.
+/// Returns when the backend's watch ends.
+fn watch(capturer: &mut Capturer, failures: u32) -> WatchEnd {
+    let backend = capturer.backend.clone();
+    set_status(
+        WatcherState::Watching,
+        format!("Watching with {}", backend.name()),
+        failures,
+    );
+    backend.watch(&mut || capturer.capture())
+}
+
+pub fn start_watcher(config: ClipboardConfig) {
+    let maintenance = config.clone();
+    thread::spawn(move || {
+        // Sensitive entries need a tighter sweep to leave on time.
+        let interval = if maintenance.sensitive_expire_secs.is_some() {
+            EXPIRY_SWEEP
+        } else {
+            MAINTENANCE_INTERVAL
+        };
+        loop {
+            let done = Connection::open(get_db_path()).and_then(|conn| maintain(&conn, &maintenance));
+            if let Err(e) = done {
+                log::warn!("Clipboard maintenance failed: {}", e);
+            }
+            thread::sleep(interval);
+        }
+    });
 
-            thread::sleep(Duration::from_millis(1000));
+    thread::spawn(move || {
+        let mut capturer = Capturer {
+            backend: clipboard_backend::active(),
+            filter: SensitiveFilter::new(&config),
+            config,
+            last_seen: String::new(),
+        };
+        let mut backoff = BACKOFF_INITIAL;
+        let mut failures = 0u32;
+
+        loop {
+            let started = Instant::now();
+            let outcome = watch(&mut capturer, failures);
+            if started.elapsed() >= HEALTHY_RUN {
+                backoff = BACKOFF_INITIAL;
+                failures = 0;
+            }
+            let (state, reason) = match outcome {
+                WatchEnd::Stopped => (
+                    WatcherState::Restarting,
+                    format!("{} watch stopped", capturer.backend.name()),
+                ),
+                WatchEnd::Missing(reason) => {
+                    failures += 1;
+                    (WatcherState::BackendMissing, reason)
+                }
+                WatchEnd::Failed(reason) => {
+                    failures += 1;
+                    (WatcherState::Restarting, reason)
+                }
+            };
+            set_status(
+                state,
+                format!("{}; retrying in {}s", reason, backoff.as_secs()),
+                failures,
+            );
+            thread::sleep(backoff);
+            backoff = next_backoff(backoff);
         }
     });
 }
 
-pub fn get_history() -> Result<Vec<ClipboardItem>> {
+const ITEM_COLUMNS: &str = "id, content, timestamp, pinned, content_type, mime_type, html, image_path, thumbnail_path, width, height, byte_size, encrypted";
+
+/// Decrypts a sealed column, falling back to a placeholder for entries written
+/// under a different key.
+fn open_sealed(sealer: &Sealer, id: i64, sealed: String) -> String {
+    sealer.open(&sealed).unwrap_or_else(|e| {
+        log::warn!("Clipboard entry {}: {}", id, e);
+        "[Encrypted entry could not be decrypted]".to_string()
+    })
+}
+
+fn item_from_row(row: &rusqlite::Row, sealer: &Sealer) -> Result<ClipboardItem> {
+    let id: i64 = row.get(0)?;
+    let mut content: String = row.get(1)?;
+    let mut html: Option<String> = row.get(6)?;
+    if row.get::<_, i64>(12).unwrap_or(0) != 0 {
+        content = open_sealed(sealer, id, content);
+        html = html.map(|html| open_sealed(sealer, id, html));
+    }
+    Ok(ClipboardItem {
+        id,
+        content,
+        timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now()),
+        pinned: row.get::<_, i64>(3).unwrap_or(0) != 0,
+        content_type: row.get::<_, String>(4).unwrap_or_else(|_| "text".to_string()),
+        mime_type: row.get::<_, String>(5).unwrap_or_else(|_| "text/plain".to_string()),
+        html,
+        image_path: row.get(7)?,
+        thumbnail_path: row.get(8)?,
+        width: row.get(9)?,
+        height: row.get(10)?,
+        byte_size: row.get::<_, Option<i64>>(11)?.map(|size| size.max(0) as u64),
+    })
+}
+
+fn read_error(e: rusqlite::Error) -> VantaError {
+    VantaError::Clipboard(format!("Failed to read clipboard history: {}", e))
+}
+
+/// The decrypted history, pinned entries first. Fails while history is locked.
+pub fn get_history() -> Result<Vec<ClipboardItem>, VantaError> {
+    let sealer = clipboard_crypto::sealer()?;
     let path = get_db_path();
-    let conn = Connection::open(path)?;
+    let conn = Connection::open(path).map_err(read_error)?;
+    purge_expired(&conn).map_err(read_error)?;
 
-    let mut stmt = conn.prepare(
-        "SELECT id, content, timestamp, pinned, content_type FROM clipboard ORDER BY pinned DESC, id DESC"
-    )?;
-    let rows = stmt.query_map([], |row| {
-        Ok(ClipboardItem {
-            id: row.get(0)?,
-            content: row.get(1)?,
-            timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now()),
-            pinned: row.get::<_, i64>(3).unwrap_or(0) != 0,
-            content_type: row.get::<_, String>(4).unwrap_or_else(|_| "text".to_string()),
-        })
-    })?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM clipboard ORDER BY pinned DESC, id DESC",
+            ITEM_COLUMNS
+        ))
+        .map_err(read_error)?;
+    let rows = stmt
+        .query_map([], |row| item_from_row(row, &sealer))
+        .map_err(read_error)?;
 
     let mut history = Vec::new();
     for row in rows {
-        history.push(row?);
+        history.push(row.map_err(read_error)?);
     }
 
     Ok(history)
 }
 
+/// Searches the whole history through the full-text index, best first, with
+/// each entry's relevance score. Fails while history is locked.
+pub fn search_history(query: &str, limit: usize) -> Result<Vec<(ClipboardItem, u32)>, VantaError> {
+    let sealer = clipboard_crypto::sealer()?;
+    let parsed = ClipQuery::parse(query);
+    if parsed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let hits = clipboard_index::search(&parsed, limit, Utc::now().timestamp_millis(), || {
+        Ok(get_history()?
+            .into_iter()
+            .map(|item| IndexedEntry {
+                id: item.id,
+                content: item.content,
+                content_type: item.content_type,
+                timestamp_ms: item.timestamp.timestamp_millis(),
+                pinned: item.pinned,
+            })
+            .collect())
+    })?;
+    if hits.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let conn = Connection::open(get_db_path()).map_err(read_error)?;
+    let placeholders = vec!["?"; hits.len()].join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM clipboard WHERE id IN ({})",
+            ITEM_COLUMNS, placeholders
+        ))
+        .map_err(read_error)?;
+    let mut items = stmt
+        .query_map(
+            rusqlite::params_from_iter(hits.iter().map(|hit| hit.id)),
+            |row| item_from_row(row, &sealer),
+        )
+        .map_err(read_error)?
+        .collect::<Result<Vec<_>>>()
+        .map_err(read_error)?;
+
+    Ok(hits
+        .iter()
+        .filter_map(|hit| {
+            let at = items.iter().position(|item| item.id == hit.id)?;
+            Some((items.swap_remove(at), hit.score))
+        })
+        .collect())
+}
+
+/// Locks history and drops the decrypted search index with the key.
+pub fn lock_history() {
+    clipboard_crypto::lock();
+    clipboard_index::clear();
+}
+
+pub fn get_item(id: i64) -> Result<Option<ClipboardItem>, VantaError> {
+    let sealer = clipboard_crypto::sealer()?;
+    let path = get_db_path();
+    let conn = Connection::open(path).map_err(read_error)?;
+    purge_expired(&conn).map_err(read_error)?;
+    conn.query_row(
+        &format!("SELECT {} FROM clipboard WHERE id = ?1", ITEM_COLUMNS),
+        params![id],
+        |row| item_from_row(row, &sealer),
+    )
+    .optional()
+    .map_err(read_error)
+}
+
+/// Copies a history entry back with the MIME type it was captured as: image
+/// bytes for images, the HTML rendering when `as_html` is set, plain text otherwise.
+pub fn restore_item(id: i64, as_html: bool) -> Result<(), VantaError> {
+    let item = get_item(id)?
+        .ok_or_else(|| VantaError::Clipboard(format!("Clipboard entry {} no longer exists", id)))?;
+
+    if let Some(image_path) = &item.image_path {
+        let bytes = read_sealed_file(&clipboard_crypto::sealer()?, image_path)?;
+        return launcher::copy_bytes_to_clipboard(&bytes, &item.mime_type);
+    }
+    match item.html.as_deref().filter(|_| as_html) {
+        Some(html) => launcher::copy_bytes_to_clipboard(html.as_bytes(), "text/html"),
+        None => launcher::copy_to_clipboard(&item.content),
+    }
+}
+
+fn read_sealed_file(sealer: &Sealer, path: &str) -> Result<Vec<u8>, VantaError> {
+    let sealed = fs::read(path)
+        .map_err(|e| VantaError::Clipboard(format!("Clipboard image is missing: {}", e)))?;
+    sealer.open_bytes(&sealed)
+}
+
+/// An image entry, or its thumbnail, decrypted into a data URL for display.
+/// Fails while history is locked.
+pub fn image_data_url(id: i64, thumbnail: bool) -> Result<String, VantaError> {
+    let item = get_item(id)?
+        .ok_or_else(|| VantaError::Clipboard(format!("Clipboard entry {} no longer exists", id)))?;
+    let (path, mime) = match (thumbnail, &item.thumbnail_path, &item.image_path) {
+        (true, Some(path), _) => (path, "image/png"),
+        (_, _, Some(path)) => (path, item.mime_type.as_str()),
+        _ => return Err(VantaError::Clipboard(format!("Clipboard entry {} is not an image", id))),
+    };
+    let bytes = read_sealed_file(&clipboard_crypto::sealer()?, path)?;
+    Ok(format!("data:{};base64,{}", mime, B64.encode(bytes)))
+}
+
 pub fn delete_item(id: i64) -> Result<()> {
     let path = get_db_path();
     let conn = Connection::open(path)?;
-    conn.execute("DELETE FROM clipboard WHERE id = ?1", params![id])?;
-    Ok(())
+    delete_where(&conn, "id = ?1", &[&id])
 }
 
 pub fn toggle_pin(id: i64) -> Result<bool> {
@@ -204,7 +1153,226 @@ pub fn toggle_pin(id: i64) -> Result<bool> {
         "UPDATE clipboard SET pinned = ?1 WHERE id = ?2",
         params![new_val, id],
     )?;
+    clipboard_index::update(|index| index.set_pinned(id, new_val != 0));
     Ok(new_val != 0)
 }
 
 use rusqlite::OptionalExtension;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        create_schema, decode_png, dedup, downscale, encode_png, encrypt_plaintext_rows,
+        image_label, item_from_row, next_backoff, pick_capture, prune, read_sealed_file,
+        store_image, Capture, BACKOFF_INITIAL, BACKOFF_MAX, ITEM_COLUMNS,
+    };
+    use crate::clipboard_crypto::Sealer;
+    use crate::config::{ClipboardConfig, ClipboardDedup};
+    use rusqlite::{params, Connection};
+
+    fn types(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn picks_text_html_or_image_from_offered_types() {
+        assert_eq!(
+            pick_capture(&types(&["text/html", "text/plain;charset=utf-8", "UTF8_STRING"])),
+            Some(Capture::Text {
+                mime: "text/plain;charset=utf-8".to_string(),
+                html: true
+            })
+        );
+        assert_eq!(
+            pick_capture(&types(&["image/jpeg", "image/png", "text/html"])),
+            Some(Capture::Image("image/png".to_string()))
+        );
+        assert_eq!(
+            pick_capture(&types(&["image/webp"])),
+            Some(Capture::Image("image/webp".to_string()))
+        );
+        assert_eq!(
+            pick_capture(&types(&["TARGETS", "STRING", "UTF8_STRING"])),
+            Some(Capture::Text {
+                mime: "UTF8_STRING".to_string(),
+                html: false
+            })
+        );
+        assert_eq!(pick_capture(&types(&["x-special/gnome-copied-files"])), None);
+    }
+
+    #[test]
+    fn stores_png_with_thumbnail_and_dimensions() {
+        let (w, h) = (400u32, 100u32);
+        let pixels: Vec<u8> = (0..w * h).flat_map(|i| [(i % 256) as u8, 0, 0, 255]).collect();
+        let png = encode_png(w, h, &pixels).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let sealer = Sealer::new([5u8; 32]).unwrap();
+        let stored = store_image(dir.path(), &sealer, &png, "image/png").unwrap();
+        assert_eq!(stored.dimensions, Some((400, 100)));
+        let sealed = std::fs::read(&stored.path).unwrap();
+        assert_ne!(sealed, png);
+        assert_eq!(sealer.open_bytes(&sealed).unwrap(), png);
+
+        let thumb = std::fs::read(stored.thumbnail.unwrap()).unwrap();
+        assert!(decode_png(&thumb).is_none());
+        let (tw, th, _) = decode_png(&sealer.open_bytes(&thumb).unwrap()).unwrap();
+        assert_eq!((tw, th), (192, 48));
+
+        // Same bytes, same file.
+        let again = store_image(dir.path(), &sealer, &png, "image/png").unwrap();
+        assert_eq!(again.path, stored.path);
+    }
+
+    #[test]
+    fn downscales_by_averaging() {
+        let rgba = [0, 0, 0, 255, 255, 255, 255, 255];
+        let (w, h, out) = downscale(&rgba, 2, 1, 1);
+        assert_eq!((w, h), (1, 1));
+        assert_eq!(out, vec![127, 127, 127, 255]);
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        let mut backoff = BACKOFF_INITIAL;
+        let mut seen = Vec::new();
+        for _ in 0..10 {
+            seen.push(backoff.as_secs());
+            backoff = next_backoff(backoff);
+        }
+        assert_eq!(&seen[..5], &[2, 4, 8, 16, 32]);
+        assert_eq!(backoff, BACKOFF_MAX);
+    }
+
+    #[test]
+    fn labels_images() {
+        assert_eq!(
+            image_label("image/png", Some((1920, 1080)), 250_880),
+            "Image 1920×1080 (PNG, 245 KB)"
+        );
+        assert_eq!(image_label("image/jpeg", None, 512), "Image (JPG, 512 B)");
+    }
+
+    #[test]
+    fn migrates_plaintext_rows_to_encrypted() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO clipboard (content, timestamp, html) VALUES (?1, ?2, ?3)",
+            params!["hunter2", "2024-01-01T00:00:00Z", "<b>hunter2</b>"],
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let png = encode_png(2, 2, &[200; 16]).unwrap();
+        let plain_path = dir.path().join("plain.png");
+        std::fs::write(&plain_path, &png).unwrap();
+        conn.execute(
+            "INSERT INTO clipboard (content, timestamp, content_type, mime_type, image_path) VALUES ('Image 2×2 (PNG, 1 KB)', '2024-01-01T00:00:00Z', 'image', 'image/png', ?1)",
+            params![plain_path.to_string_lossy()],
+        )
+        .unwrap();
+
+        let sealer = Sealer::new([9u8; 32]).unwrap();
+        assert_eq!(encrypt_plaintext_rows(&conn, &sealer, dir.path()).unwrap(), 2);
+        assert_eq!(encrypt_plaintext_rows(&conn, &sealer, dir.path()).unwrap(), 0);
+
+        let (raw, hash): (String, String) = conn
+            .query_row("SELECT content, content_hash FROM clipboard WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert!(!raw.contains("hunter2"));
+        assert_eq!(hash, sealer.fingerprint("hunter2"));
+
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM clipboard ORDER BY id", ITEM_COLUMNS))
+            .unwrap();
+        let items = stmt
+            .query_map([], |row| item_from_row(row, &sealer))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(items[0].content, "hunter2");
+        assert_eq!(items[0].html.as_deref(), Some("<b>hunter2</b>"));
+        assert_eq!(items[1].content, "Image 2×2 (PNG, 1 KB)");
+
+        // The image is stored again sealed and the plaintext file is gone.
+        assert!(!plain_path.exists());
+        let image_path = items[1].image_path.as_deref().unwrap();
+        let sealed = std::fs::read(image_path).unwrap();
+        assert_ne!(sealed, png);
+        assert_eq!(read_sealed_file(&sealer, image_path).unwrap(), png);
+        let thumb = read_sealed_file(&sealer, items[1].thumbnail_path.as_deref().unwrap()).unwrap();
+        assert_eq!(decode_png(&thumb).unwrap().0, 2);
+    }
+
+    fn insert(conn: &Connection, hash: &str, content_type: &str, days_ago: i64, pinned: bool) {
+        let timestamp = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+        conn.execute(
+            "INSERT INTO clipboard (content, timestamp, pinned, content_type, content_hash) VALUES ('x', ?1, ?2, ?3, ?4)",
+            params![timestamp, pinned as i64, content_type, hash],
+        )
+        .unwrap();
+    }
+
+    fn hashes(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT content_hash FROM clipboard ORDER BY id")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn prunes_by_age_type_and_count() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        insert(&conn, "old-url", "url", 10, false);
+        insert(&conn, "old-text", "text", 10, false);
+        insert(&conn, "ancient-url", "url", 40, false);
+        insert(&conn, "pinned", "text", 400, true);
+        insert(&conn, "new-text", "text", 0, false);
+
+        let mut config = ClipboardConfig {
+            max_age_days: Some(30),
+            ..ClipboardConfig::default()
+        };
+        config.retention_days.insert("text".to_string(), 7);
+        prune(&conn, &config).unwrap();
+        assert_eq!(hashes(&conn), vec!["old-url", "pinned", "new-text"]);
+
+        config.max_items = 2;
+        prune(&conn, &config).unwrap();
+        assert_eq!(hashes(&conn), vec!["pinned", "new-text"]);
+    }
+
+    #[test]
+    fn dedup_bumps_or_skips_existing_entries() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_schema(&conn).unwrap();
+        insert(&conn, "a", "text", 3, false);
+        insert(&conn, "b", "text", 2, false);
+
+        let bump = ClipboardConfig::default();
+        assert!(dedup(&conn, &bump, "content_hash", "a", None).unwrap());
+        assert_eq!(hashes(&conn), vec!["b", "a"]);
+        assert!(!dedup(&conn, &bump, "content_hash", "c", None).unwrap());
+
+        let consecutive = ClipboardConfig {
+            dedup: ClipboardDedup::Consecutive,
+            ..ClipboardConfig::default()
+        };
+        assert!(dedup(&conn, &consecutive, "content_hash", "a", None).unwrap());
+        assert!(!dedup(&conn, &consecutive, "content_hash", "b", None).unwrap());
+
+        let off = ClipboardConfig {
+            dedup: ClipboardDedup::Off,
+            ..ClipboardConfig::default()
+        };
+        assert!(!dedup(&conn, &off, "content_hash", "a", None).unwrap());
+        assert_eq!(hashes(&conn), vec!["b", "a"]);
+    }
+}
@@ -0,0 +1,478 @@
+//! Clipboard access behind one trait: wl-clipboard on Wayland, xclip or xsel on
+//! X11 and XWayland, and an in-process clipboard when no tool is installed.
+//! History capture and `copy:` actions both go through the active backend.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::ClipboardBackendPreference;
+use crate::errors::VantaError;
+use crate::launcher::on_path;
+
+/// Backends without change notifications re-read the clipboard this often.
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Why a watch ended.
+pub enum WatchEnd {
+    /// The watch process exited cleanly (e.g. the compositor restarted).
+    Stopped,
+    /// The backend's tools can't be started.
+    Missing(String),
+    Failed(String),
+}
+
+pub trait ClipboardBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// MIME types (or X11 targets) currently on offer.
+    fn list_types(&self) -> Option<Vec<String>>;
+    fn read(&self, mime: &str) -> Option<Vec<u8>>;
+    /// Puts `bytes` on the clipboard, labelled `mime` when given.
+    fn write(&self, bytes: &[u8], mime: Option<&str>) -> Result<(), VantaError>;
+    /// Calls `on_change` for every clipboard change until the watch ends.
+    fn watch(&self, on_change: &mut dyn FnMut()) -> WatchEnd;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    WlClipboard,
+    Xclip,
+    Xsel,
+    InProcess,
+}
+
+impl BackendKind {
+    fn is_available(self) -> bool {
+        match self {
+            BackendKind::WlClipboard => on_path("wl-paste") && on_path("wl-copy"),
+            BackendKind::Xclip => on_path("xclip"),
+            BackendKind::Xsel => on_path("xsel"),
+            BackendKind::InProcess => true,
+        }
+    }
+
+    fn build(self) -> Arc<dyn ClipboardBackend> {
+        match self {
+            BackendKind::WlClipboard => Arc::new(WlClipboard),
+            BackendKind::Xclip => Arc::new(Xclip),
+            BackendKind::Xsel => Arc::new(Xsel),
+            BackendKind::InProcess => Arc::new(InProcess),
+        }
+    }
+}
+
+/// Picks a backend: the configured one if its tools exist, otherwise the best
+/// match for `XDG_SESSION_TYPE`, ending with the in-process clipboard.
+pub fn resolve(
+    preference: ClipboardBackendPreference,
+    session_type: Option<&str>,
+    available: impl Fn(BackendKind) -> bool,
+) -> BackendKind {
+    if let Some(kind) = preferred_kind(preference).filter(|kind| available(*kind)) {
+        return kind;
+    }
+
+    let order: &[BackendKind] = match session_type.map(str::to_ascii_lowercase).as_deref() {
+        Some("x11") => &[
+            BackendKind::Xclip,
+            BackendKind::Xsel,
+            BackendKind::WlClipboard,
+        ],
+        // XWayland apps still share the Wayland clipboard, so xclip is a usable fallback.
+        _ => &[
+            BackendKind::WlClipboard,
+            BackendKind::Xclip,
+            BackendKind::Xsel,
+        ],
+    };
+    order
+        .iter()
+        .copied()
+        .find(|kind| available(*kind))
+        .unwrap_or(BackendKind::InProcess)
+}
+
+static ACTIVE: LazyLock<RwLock<Arc<dyn ClipboardBackend>>> =
+    LazyLock::new(|| RwLock::new(select(ClipboardBackendPreference::Auto)));
+
+fn preferred_kind(preference: ClipboardBackendPreference) -> Option<BackendKind> {
+    match preference {
+        ClipboardBackendPreference::Auto => None,
+        ClipboardBackendPreference::WlClipboard => Some(BackendKind::WlClipboard),
+        ClipboardBackendPreference::Xclip => Some(BackendKind::Xclip),
+        ClipboardBackendPreference::Xsel => Some(BackendKind::Xsel),
+        ClipboardBackendPreference::InProcess => Some(BackendKind::InProcess),
+    }
+}
+
+fn select(preference: ClipboardBackendPreference) -> Arc<dyn ClipboardBackend> {
+    let session_type = std::env::var("XDG_SESSION_TYPE").ok();
+    let kind = resolve(
+        preference,
+        session_type.as_deref(),
+        BackendKind::is_available,
+    );
+    let backend = kind.build();
+    if let Some(wanted) = preferred_kind(preference).filter(|wanted| *wanted != kind) {
+        log::warn!(
+            "Clipboard backend {} is not installed; using {}",
+            wanted.build().name(),
+            backend.name()
+        );
+    }
+    backend
+}
+
+/// Chooses the backend for this session; called once at startup.
+pub fn init(preference: ClipboardBackendPreference) -> Arc<dyn ClipboardBackend> {
+    let backend = select(preference);
+    log::info!("Clipboard backend: {}", backend.name());
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = backend.clone();
+    }
+    backend
+}
+
+pub fn active() -> Arc<dyn ClipboardBackend> {
+    ACTIVE
+        .read()
+        .map(|active| active.clone())
+        .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+}
+
+fn run_output(bin: &str, args: &[&str]) -> Option<Vec<u8>> {
+    Command::new(bin)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| output.stdout)
+}
+
+fn lines(bytes: Vec<u8>) -> Vec<String> {
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Pipes `bytes` into a clipboard tool. xclip/xsel keep serving the selection
+/// after we hand it over, so the child is reaped off-thread.
+fn pipe_into(bin: &str, args: &[&str], bytes: &[u8]) -> Result<(), VantaError> {
+    let mut child = Command::new(bin)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| VantaError::Clipboard(format!("Failed to run {}: {}", bin, e)))?;
+    let written = child
+        .stdin
+        .take()
+        .map(|mut stdin| stdin.write_all(bytes).is_ok())
+        .unwrap_or(false);
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    if written {
+        Ok(())
+    } else {
+        Err(VantaError::Clipboard(format!("Failed to write to {}", bin)))
+    }
+}
+
+/// For backends without notifications: report a change every `POLL_INTERVAL`
+/// and let the capturer's last-seen check drop repeats.
+fn poll(on_change: &mut dyn FnMut()) -> WatchEnd {
+    loop {
+        on_change();
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+pub struct WlClipboard;
+
+impl ClipboardBackend for WlClipboard {
+    fn name(&self) -> &'static str {
+        "wl-clipboard"
+    }
+
+    fn list_types(&self) -> Option<Vec<String>> {
+        run_output("wl-paste", &["--list-types"]).map(lines)
+    }
+
+    fn read(&self, mime: &str) -> Option<Vec<u8>> {
+        run_output("wl-paste", &["--no-newline", "--type", mime])
+    }
+
+    fn write(&self, bytes: &[u8], mime: Option<&str>) -> Result<(), VantaError> {
+        match mime {
+            Some(mime) => pipe_into("wl-copy", &["--type", mime], bytes),
+            None => pipe_into("wl-copy", &[], bytes),
+        }
+    }
+
+    /// `wl-paste --watch` prints a line for every selection change.
+    fn watch(&self, on_change: &mut dyn FnMut()) -> WatchEnd {
+        use std::io::{BufRead, BufReader, Read};
+
+        let spawned = Command::new("wl-paste")
+            .args(["--watch", "echo", "changed"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                return WatchEnd::Missing(format!(
+                    "wl-paste is not available ({}); install wl-clipboard",
+                    e
+                ))
+            }
+        };
+
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines() {
+                if line.is_err() {
+                    break;
+                }
+                on_change();
+            }
+        }
+
+        let status = match child.wait() {
+            Ok(status) if status.success() => return WatchEnd::Stopped,
+            Ok(status) => status,
+            Err(e) => return WatchEnd::Failed(e.to_string()),
+        };
+        let mut stderr = String::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        WatchEnd::Failed(match stderr.lines().next().map(str::trim) {
+            Some(reason) if !reason.is_empty() => format!("wl-paste exited: {}", reason),
+            _ => format!("wl-paste exited with {}", status),
+        })
+    }
+}
+
+pub struct Xclip;
+
+impl ClipboardBackend for Xclip {
+    fn name(&self) -> &'static str {
+        "xclip"
+    }
+
+    fn list_types(&self) -> Option<Vec<String>> {
+        run_output("xclip", &["-selection", "clipboard", "-o", "-t", "TARGETS"]).map(lines)
+    }
+
+    fn read(&self, mime: &str) -> Option<Vec<u8>> {
+        run_output("xclip", &["-selection", "clipboard", "-o", "-t", mime])
+    }
+
+    fn write(&self, bytes: &[u8], mime: Option<&str>) -> Result<(), VantaError> {
+        match mime {
+            Some(mime) => pipe_into("xclip", &["-selection", "clipboard", "-t", mime], bytes),
+            None => pipe_into("xclip", &["-selection", "clipboard"], bytes),
+        }
+    }
+
+    /// `clipnotify` blocks until the selection changes; without it, poll.
+    fn watch(&self, on_change: &mut dyn FnMut()) -> WatchEnd {
+        if !on_path("xclip") {
+            return WatchEnd::Missing("xclip is not installed".to_string());
+        }
+        if !on_path("clipnotify") {
+            return poll(on_change);
+        }
+        loop {
+            on_change();
+            match Command::new("clipnotify").status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => {
+                    return WatchEnd::Failed(format!("clipnotify exited with {}", status))
+                }
+                Err(e) => return WatchEnd::Failed(e.to_string()),
+            }
+        }
+    }
+}
+
+/// xsel only speaks plain text, so images and HTML aren't captured or restored.
+pub struct Xsel;
+
+impl ClipboardBackend for Xsel {
+    fn name(&self) -> &'static str {
+        "xsel"
+    }
+
+    fn list_types(&self) -> Option<Vec<String>> {
+        let text = run_output("xsel", &["--clipboard", "--output"])?;
+        Some(if text.is_empty() {
+            Vec::new()
+        } else {
+            vec!["text/plain".to_string()]
+        })
+    }
+
+    fn read(&self, mime: &str) -> Option<Vec<u8>> {
+        mime.starts_with("text/plain")
+            .then(|| run_output("xsel", &["--clipboard", "--output"]))
+            .flatten()
+    }
+
+    fn write(&self, bytes: &[u8], mime: Option<&str>) -> Result<(), VantaError> {
+        if mime.is_some_and(|mime| !mime.starts_with("text/plain")) {
+            return Err(VantaError::Clipboard(
+                "xsel can only copy plain text; install xclip or wl-clipboard".to_string(),
+            ));
+        }
+        pipe_into("xsel", &["--clipboard", "--input"], bytes)
+    }
+
+    fn watch(&self, on_change: &mut dyn FnMut()) -> WatchEnd {
+        if !on_path("xsel") {
+            return WatchEnd::Missing("xsel is not installed".to_string());
+        }
+        poll(on_change)
+    }
+}
+
+#[derive(Default)]
+struct InProcessState {
+    content: Option<(String, Vec<u8>)>,
+    generation: u64,
+}
+
+static IN_PROCESS: LazyLock<(Mutex<InProcessState>, Condvar)> =
+    LazyLock::new(|| (Mutex::new(InProcessState::default()), Condvar::new()));
+
+/// Last resort when no clipboard tool is installed: Vanta's own copies are kept
+/// in memory, so history and re-copying still work inside the launcher.
+pub struct InProcess;
+
+impl ClipboardBackend for InProcess {
+    fn name(&self) -> &'static str {
+        "in-process"
+    }
+
+    fn list_types(&self) -> Option<Vec<String>> {
+        let state = IN_PROCESS.0.lock().ok()?;
+        Some(state.content.iter().map(|(mime, _)| mime.clone()).collect())
+    }
+
+    fn read(&self, mime: &str) -> Option<Vec<u8>> {
+        let state = IN_PROCESS.0.lock().ok()?;
+        state
+            .content
+            .as_ref()
+            .filter(|(stored, _)| stored == mime)
+            .map(|(_, bytes)| bytes.clone())
+    }
+
+    fn write(&self, bytes: &[u8], mime: Option<&str>) -> Result<(), VantaError> {
+        let (lock, changed) = &*IN_PROCESS;
+        let mut state = lock.lock().map_err(|_| {
+            VantaError::Clipboard("In-process clipboard is unavailable".to_string())
+        })?;
+        state.content = Some((mime.unwrap_or("text/plain").to_string(), bytes.to_vec()));
+        state.generation += 1;
+        changed.notify_all();
+        Ok(())
+    }
+
+    fn watch(&self, on_change: &mut dyn FnMut()) -> WatchEnd {
+        let (lock, changed) = &*IN_PROCESS;
+        let mut seen = None;
+        loop {
+            let generation = {
+                let Ok(state) = lock.lock() else {
+                    return WatchEnd::Failed("In-process clipboard is unavailable".to_string());
+                };
+                let Ok(state) = changed.wait_while(state, |state| Some(state.generation) == seen)
+                else {
+                    return WatchEnd::Failed("In-process clipboard is unavailable".to_string());
+                };
+                state.generation
+            };
+            seen = Some(generation);
+            on_change();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve, BackendKind, ClipboardBackend, InProcess};
+    use crate::config::ClipboardBackendPreference;
+
+    #[test]
+    fn picks_backend_for_session_type() {
+        let everything = |_: BackendKind| true;
+        let auto = ClipboardBackendPreference::Auto;
+        assert_eq!(
+            resolve(auto, Some("wayland"), everything),
+            BackendKind::WlClipboard
+        );
+        assert_eq!(resolve(auto, Some("x11"), everything), BackendKind::Xclip);
+        assert_eq!(resolve(auto, None, everything), BackendKind::WlClipboard);
+
+        let only_xsel = |kind: BackendKind| kind == BackendKind::Xsel;
+        assert_eq!(resolve(auto, Some("x11"), only_xsel), BackendKind::Xsel);
+        // XWayland: no wl-clipboard installed, xclip still reaches the clipboard.
+        let xclip = |kind: BackendKind| kind == BackendKind::Xclip;
+        assert_eq!(resolve(auto, Some("wayland"), xclip), BackendKind::Xclip);
+        assert_eq!(
+            resolve(auto, Some("wayland"), |_| false),
+            BackendKind::InProcess
+        );
+    }
+
+    #[test]
+    fn honours_configured_backend_when_installed() {
+        let everything = |_: BackendKind| true;
+        assert_eq!(
+            resolve(
+                ClipboardBackendPreference::Xsel,
+                Some("wayland"),
+                everything
+            ),
+            BackendKind::Xsel
+        );
+        assert_eq!(
+            resolve(ClipboardBackendPreference::InProcess, Some("x11"), |_| {
+                false
+            }),
+            BackendKind::InProcess
+        );
+        let no_xclip = |kind: BackendKind| kind != BackendKind::Xclip;
+        assert_eq!(
+            resolve(ClipboardBackendPreference::Xclip, Some("x11"), no_xclip),
+            BackendKind::Xsel
+        );
+    }
+
+    #[test]
+    fn in_process_clipboard_round_trips_and_notifies() {
+        let backend = InProcess;
+        backend.write(b"<b>hi</b>", Some("text/html")).unwrap();
+        assert_eq!(backend.list_types().unwrap(), vec!["text/html".to_string()]);
+        assert_eq!(backend.read("text/html").unwrap(), b"<b>hi</b>");
+        assert!(backend.read("text/plain").is_none());
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || InProcess.watch(&mut || tx.send(()).unwrap()));
+        let wait = std::time::Duration::from_secs(2);
+        rx.recv_timeout(wait).expect("current content reported");
+        backend.write(b"again", None).unwrap();
+        rx.recv_timeout(wait).expect("write reported");
+        assert_eq!(backend.read("text/plain").unwrap(), b"again");
+    }
+}
@@ -0,0 +1,204 @@
+//! Encryption for clipboard history at rest. Entry text, HTML and image files
+//! are sealed with AES-256-GCM under a dedicated key, kept next to the database like the
+//! workflow secrets key. Locking drops the key from memory until unlocked.
+
+use std::sync::{LazyLock, Mutex};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::config;
+use crate::errors::VantaError;
+
+const NONCE_LEN: usize = 12;
+
+enum KeyState {
+    /// Not read yet; the first use loads (or creates) the key file.
+    Unloaded,
+    Loaded([u8; 32]),
+    /// Locked by the user; nothing is decrypted or recorded until unlocked.
+    Locked,
+}
+
+static KEY: LazyLock<Mutex<KeyState>> = LazyLock::new(|| Mutex::new(KeyState::Unloaded));
+
+fn key_path() -> std::path::PathBuf {
+    config::config_dir().join("clipboard.key")
+}
+
+/// Seals and opens history entries with one key.
+pub struct Sealer {
+    key: [u8; 32],
+    cipher: Aes256Gcm,
+}
+
+impl Sealer {
+    pub fn new(key: [u8; 32]) -> Result<Self, VantaError> {
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| format!("Failed to initialize clipboard cipher: {}", e))?;
+        Ok(Self { key, cipher })
+    }
+
+    /// Encrypts `plain` into base64 of nonce followed by ciphertext.
+    pub fn seal(&self, plain: &str) -> Result<String, VantaError> {
+        Ok(B64.encode(self.seal_bytes(plain.as_bytes())?))
+    }
+
+    pub fn open(&self, sealed: &str) -> Result<String, VantaError> {
+        let raw = B64
+            .decode(sealed)
+            .map_err(|e| format!("Invalid clipboard entry encoding: {}", e))?;
+        String::from_utf8(self.open_bytes(&raw)?)
+            .map_err(|_| "Decrypted clipboard entry is not UTF-8".into())
+    }
+
+    /// Encrypts `plain` into the nonce followed by ciphertext, for image files.
+    pub fn seal_bytes(&self, plain: &[u8]) -> Result<Vec<u8>, VantaError> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce_bytes);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), plain)
+            .map_err(|_| "Failed to encrypt clipboard entry".to_string())?;
+        let mut sealed = nonce_bytes.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn open_bytes(&self, sealed: &[u8]) -> Result<Vec<u8>, VantaError> {
+        if sealed.len() < NONCE_LEN {
+            return Err("Clipboard entry is too short to decrypt".into());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        Ok(self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Failed to decrypt clipboard entry".to_string())?)
+    }
+
+    /// Keyed hash of `plain`, so duplicates can be found without decrypting and
+    /// without storing a plain hash that could be matched against guesses.
+    pub fn fingerprint(&self, plain: &str) -> String {
+        self.fingerprint_bytes(plain.as_bytes())
+    }
+
+    pub fn fingerprint_bytes(&self, plain: &[u8]) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(plain);
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// The sealer for the current key, loading it on first use. Fails while the
+/// history is locked.
+pub fn sealer() -> Result<Sealer, VantaError> {
+    let mut state = KEY
+        .lock()
+        .map_err(|_| "Clipboard key is unavailable".to_string())?;
+    match *state {
+        KeyState::Loaded(key) => Sealer::new(key),
+        KeyState::Locked => Err(VantaError::Clipboard(
+            "Clipboard history is locked".to_string(),
+        )),
+        KeyState::Unloaded => {
+            let key = crate::secrets::read_or_create_key_file(&key_path(), "clipboard")?;
+            *state = KeyState::Loaded(key);
+            Sealer::new(key)
+        }
+    }
+}
+
+pub fn is_locked() -> bool {
+    KEY.lock()
+        .map(|state| matches!(*state, KeyState::Locked))
+        .unwrap_or(true)
+}
+
+/// Drops the key from memory. History can't be read or recorded until `unlock`.
+pub fn lock() {
+    if let Ok(mut state) = KEY.lock() {
+        if let KeyState::Loaded(key) = &mut *state {
+            key.fill(0);
+        }
+        *state = KeyState::Locked;
+    }
+}
+
+/// Reads the key back from disk.
+pub fn unlock() -> Result<(), VantaError> {
+    let key = crate::secrets::read_or_create_key_file(&key_path(), "clipboard")?;
+    let mut state = KEY
+        .lock()
+        .map_err(|_| "Clipboard key is unavailable".to_string())?;
+    *state = KeyState::Loaded(key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sealer;
+
+    #[test]
+    fn seals_and_opens_entries() {
+        let sealer = Sealer::new([7u8; 32]).unwrap();
+        let sealed = sealer.seal("hunter2 ✓").unwrap();
+        assert!(!sealed.contains("hunter2"));
+        assert_eq!(sealer.open(&sealed).unwrap(), "hunter2 ✓");
+        // Fresh nonce per entry, so equal text doesn't give equal ciphertext.
+        assert_ne!(sealer.seal("hunter2 ✓").unwrap(), sealed);
+    }
+
+    #[test]
+    fn rejects_other_keys_and_tampering() {
+        let sealed = Sealer::new([1u8; 32]).unwrap().seal("secret").unwrap();
+        assert!(Sealer::new([2u8; 32]).unwrap().open(&sealed).is_err());
+
+        let mut tampered = sealed.into_bytes();
+        let last = tampered.len() - 3;
+        tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(Sealer::new([1u8; 32]).unwrap().open(&tampered).is_err());
+        assert!(Sealer::new([1u8; 32]).unwrap().open("c2hvcnQ=").is_err());
+    }
+
+    #[test]
+    fn seals_and_opens_bytes() {
+        let sealer = Sealer::new([3u8; 32]).unwrap();
+        let png = b"\x89PNG\r\n\x1a\nnot really";
+        let sealed = sealer.seal_bytes(png).unwrap();
+        assert!(!sealed.windows(4).any(|w| w == b"\x89PNG"));
+        assert_eq!(sealer.open_bytes(&sealed).unwrap(), png);
+        assert!(Sealer::new([4u8; 32]).unwrap().open_bytes(&sealed).is_err());
+        assert!(sealer.open_bytes(&sealed[..8]).is_err());
+    }
+
+    #[test]
+    fn fingerprints_depend_on_key_and_text() {
+        let a = Sealer::new([1u8; 32]).unwrap();
+        let b = Sealer::new([2u8; 32]).unwrap();
+        assert_eq!(a.fingerprint("x"), a.fingerprint("x"));
+        assert_ne!(a.fingerprint("x"), a.fingerprint("y"));
+        assert_ne!(a.fingerprint("x"), b.fingerprint("x"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn creates_a_private_key_file_once() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/clipboard.key");
+        let key = crate::secrets::read_or_create_key_file(&path, "clipboard").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            crate::secrets::read_or_create_key_file(&path, "clipboard").unwrap(),
+            key
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Full-text search over clipboard history. History is encrypted at rest, so
+//! the FTS5 index lives in an in-memory database: built from the decrypted
+//! history on the first search, updated as entries are added or removed, and
+//! dropped when history is locked.
+
+use std::sync::{LazyLock, Mutex};
+
+use rusqlite::{params_from_iter, Connection, Result, ToSql};
+
+use crate::errors::VantaError;
+use crate::ranking_config;
+
+/// What the index keeps per history entry.
+pub struct IndexedEntry {
+    pub id: i64,
+    pub content: String,
+    pub content_type: String,
+    pub timestamp_ms: i64,
+    pub pinned: bool,
+}
+
+/// A parsed clipboard query: free text plus `type:url` / `is:pinned` filters.
+#[derive(Debug, Default, PartialEq)]
+pub struct ClipQuery {
+    pub terms: Vec<String>,
+    pub types: Vec<String>,
+    pub pinned_only: bool,
+}
+
+impl ClipQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = ClipQuery::default();
+        for token in query.split_whitespace() {
+            let lower = token.to_lowercase();
+            if let Some(kind) = lower.strip_prefix("type:").filter(|k| !k.is_empty()) {
+                parsed.types.push(kind.to_string());
+            } else if lower == "is:pinned" {
+                parsed.pinned_only = true;
+            } else {
+                parsed.terms.push(token.to_string());
+            }
+        }
+        parsed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.types.is_empty() && !self.pinned_only
+    }
+}
+
+/// A matching entry and its combined BM25, recency and pinned score.
+#[derive(Debug, PartialEq)]
+pub struct Hit {
+    pub id: i64,
+    pub score: u32,
+}
+
+/// The trigram tokenizer can't match fewer than three characters, so shorter
+/// terms fall back to `LIKE`.
+const MIN_MATCH_CHARS: usize = 3;
+
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+impl SearchIndex {
+    pub fn new(entries: &[IndexedEntry]) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE entries USING fts5(
+                content,
+                content_type UNINDEXED,
+                timestamp_ms UNINDEXED,
+                pinned UNINDEXED,
+                tokenize = 'trigram'
+            );",
+        )?;
+        let index = Self { conn };
+        for entry in entries {
+            index.insert(entry)?;
+        }
+        Ok(index)
+    }
+
+    pub fn insert(&self, entry: &IndexedEntry) -> Result<()> {
+        self.remove(&[entry.id])?;
+        self.conn.execute(
+            "INSERT INTO entries (rowid, content, content_type, timestamp_ms, pinned)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                entry.id,
+                entry.content,
+                entry.content_type,
+                entry.timestamp_ms,
+                entry.pinned as i64
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove(&self, ids: &[i64]) -> Result<()> {
+        for id in ids {
+            self.conn
+                .execute("DELETE FROM entries WHERE rowid = ?1", [id])?;
+        }
+        Ok(())
+    }
+
+    pub fn set_pinned(&self, id: i64, pinned: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE entries SET pinned = ?1 WHERE rowid = ?2",
+            rusqlite::params![pinned as i64, id],
+        )?;
+        Ok(())
+    }
+
+    /// Follows an entry bumped to the top, which gives it a new id.
+    pub fn bump(&self, old_id: i64, new_id: i64, timestamp_ms: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE entries SET rowid = ?1, timestamp_ms = ?2 WHERE rowid = ?3",
+            rusqlite::params![new_id, timestamp_ms, old_id],
+        )?;
+        Ok(())
+    }
+
+    /// Best `limit` entries for `query`. Text relevance is BM25, plus a recency
+    /// bonus that is halved after `CLIPBOARD_RECENCY_HALF_LIFE_DAYS` and the
+    /// pinned bonus.
+    pub fn search(&self, query: &ClipQuery, limit: usize, now_ms: i64) -> Result<Vec<Hit>> {
+        let mut clauses = Vec::new();
+        let mut args: Vec<Box<dyn ToSql>> = Vec::new();
+
+        let (long, short): (Vec<&String>, Vec<&String>) = query
+            .terms
+            .iter()
+            .partition(|term| term.chars().count() >= MIN_MATCH_CHARS);
+        let relevance = if long.is_empty() {
+            "0.0".to_string()
+        } else {
+            let expression = long
+                .iter()
+                .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            clauses.push("entries MATCH ?".to_string());
+            args.push(Box::new(expression));
+            format!(
+                "-bm25(entries) * {}",
+                ranking_config::CLIPBOARD_BM25_MULTIPLIER
+            )
+        };
+        for term in short {
+            clauses.push("content LIKE ? ESCAPE '\\'".to_string());
+            let escaped = term
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            args.push(Box::new(format!("%{}%", escaped)));
+        }
+        if !query.types.is_empty() {
+            clauses.push(format!(
+                "content_type IN ({})",
+                vec!["?"; query.types.len()].join(", ")
+            ));
+            for kind in &query.types {
+                args.push(Box::new(kind.clone()));
+            }
+        }
+        if query.pinned_only {
+            clauses.push("pinned = 1".to_string());
+        }
+        if clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT rowid, {relevance}
+                + {recency} / (1.0 + MAX(? - timestamp_ms, 0) / {half_life})
+                + pinned * {pinned} AS score
+             FROM entries WHERE {clauses}
+             ORDER BY score DESC, rowid DESC LIMIT ?",
+            relevance = relevance,
+            recency = ranking_config::CLIPBOARD_RECENCY_BONUS,
+            half_life = ranking_config::CLIPBOARD_RECENCY_HALF_LIFE_DAYS * 86_400_000.0,
+            pinned = ranking_config::CLIPBOARD_PINNED_BONUS,
+            clauses = clauses.join(" AND "),
+        );
+        // The `now` placeholder comes first in the statement, `limit` last.
+        args.insert(0, Box::new(now_ms));
+        args.push(Box::new(limit as i64));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let hits = stmt.query_map(params_from_iter(args.iter()), |row| {
+            Ok(Hit {
+                id: row.get(0)?,
+                score: row.get::<_, f64>(1)?.max(0.0) as u32,
+            })
+        })?;
+        hits.collect()
+    }
+}
+
+static INDEX: LazyLock<Mutex<Option<SearchIndex>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Searches the index, building it with `load` first if needed.
+pub fn search(
+    query: &ClipQuery,
+    limit: usize,
+    now_ms: i64,
+    load: impl FnOnce() -> Result<Vec<IndexedEntry>, VantaError>,
+) -> Result<Vec<Hit>, VantaError> {
+    let mut index = INDEX
+        .lock()
+        .map_err(|_| "Clipboard search index is unavailable".to_string())?;
+    if index.is_none() {
+        let entries = load()?;
+        *index = Some(SearchIndex::new(&entries).map_err(search_error)?);
+    }
+    index
+        .as_ref()
+        .map(|index| index.search(query, limit, now_ms))
+        .unwrap_or_else(|| Ok(Vec::new()))
+        .map_err(search_error)
+}
+
+fn search_error(e: rusqlite::Error) -> VantaError {
+    VantaError::Clipboard(format!("Clipboard search failed: {}", e))
+}
+
+/// Applies a history change to the index if it's loaded. An unloaded index is
+/// built from the database on the next search, so there's nothing to do; a
+/// failed update drops the index so it gets rebuilt.
+pub fn update(change: impl FnOnce(&SearchIndex) -> Result<()>) {
+    let Ok(mut index) = INDEX.lock() else {
+        return;
+    };
+    if let Some(loaded) = index.as_ref() {
+        if let Err(e) = change(loaded) {
+            log::warn!("Rebuilding clipboard search index: {}", e);
+            *index = None;
+        }
+    }
+}
+
+/// Drops the index and the decrypted text it holds.
+pub fn clear() {
+    if let Ok(mut index) = INDEX.lock() {
+        *index = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClipQuery, IndexedEntry, SearchIndex};
+
+    const DAY_MS: i64 = 86_400_000;
+    const NOW: i64 = 1_700_000_000_000;
+
+    fn entry(
+        id: i64,
+        content: &str,
+        content_type: &str,
+        days_ago: i64,
+        pinned: bool,
+    ) -> IndexedEntry {
+        IndexedEntry {
+            id,
+            content: content.to_string(),
+            content_type: content_type.to_string(),
+            timestamp_ms: NOW - days_ago * DAY_MS,
+            pinned,
+        }
+    }
+
+    fn ids(index: &SearchIndex, query: &str) -> Vec<i64> {
+        index
+            .search(&ClipQuery::parse(query), 10, NOW)
+            .unwrap()
+            .into_iter()
+            .map(|hit| hit.id)
+            .collect()
+    }
+
+    #[test]
+    fn parses_filters_and_terms() {
+        assert_eq!(
+            ClipQuery::parse("type:URL github is:pinned \"quoted"),
+            ClipQuery {
+                terms: vec!["github".to_string(), "\"quoted".to_string()],
+                types: vec!["url".to_string()],
+                pinned_only: true,
+            }
+        );
+        assert!(ClipQuery::parse("  ").is_empty());
+        assert_eq!(ClipQuery::parse("type:").terms, vec!["type:".to_string()]);
+    }
+
+    #[test]
+    fn finds_substrings_in_the_whole_history() {
+        let entries = (0..500)
+            .map(|i| entry(i, &format!("note number {}", i), "text", 400 - i / 2, false))
+            .chain([entry(
+                1000,
+                "https://github.com/vanta/launcher",
+                "url",
+                300,
+                false,
+            )])
+            .collect::<Vec<_>>();
+        let index = SearchIndex::new(&entries).unwrap();
+        assert_eq!(ids(&index, "hub.com/van"), vec![1000]);
+        assert_eq!(ids(&index, "GITHUB"), vec![1000]);
+        assert_eq!(ids(&index, "type:url"), vec![1000]);
+        assert!(ids(&index, "type:url number").is_empty());
+        // Short terms and quotes don't break the query.
+        assert_eq!(ids(&index, "number 7 type:text").len(), 10);
+        assert!(ids(&index, "\"*").is_empty());
+    }
+
+    #[test]
+    fn ranks_recent_and_pinned_entries_higher() {
+        let index = SearchIndex::new(&[
+            entry(1, "deploy token for staging", "text", 60, false),
+            entry(2, "deploy token for prod", "text", 0, false),
+            entry(3, "deploy token backup", "text", 90, true),
+        ])
+        .unwrap();
+        assert_eq!(ids(&index, "deploy token"), vec![3, 2, 1]);
+        assert_eq!(ids(&index, "is:pinned"), vec![3]);
+    }
+
+    #[test]
+    fn follows_removals_pins_and_bumps() {
+        let index = SearchIndex::new(&[
+            entry(1, "alpha one", "text", 5, false),
+            entry(2, "alpha two", "text", 1, false),
+        ])
+        .unwrap();
+        index.remove(&[2]).unwrap();
+        assert_eq!(ids(&index, "alpha"), vec![1]);
+
+        index.bump(1, 7, super::tests::NOW).unwrap();
+        index.set_pinned(7, true).unwrap();
+        index
+            .insert(&entry(8, "alpha three", "text", 0, false))
+            .unwrap();
+        assert_eq!(ids(&index, "alpha"), vec![7, 8]);
+        assert_eq!(ids(&index, "is:pinned alpha"), vec![7]);
+    }
+}
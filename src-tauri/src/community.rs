@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::{Emitter, State};
 
-use crate::config::{self, AppearanceConfig, ProfileConfig, WindowConfig, WorkflowMacro};
+use crate::config::{self, AppearanceConfig, ProfileConfig, Snippet, WindowConfig, WorkflowMacro};
 use crate::errors::VantaError;
 use crate::permissions::Capability;
 use crate::AppState;
@@ -182,6 +182,11 @@ enum CommunityImportRollback {
     Theme {
         previous: ThemeSnippetData,
     },
+    Snippet {
+        keyword: String,
+        #[serde(default)]
+        previous: Option<Snippet>,
+    },
 }
 
 fn default_schema() -> u32 {
@@ -356,6 +361,14 @@ fn apply_import_rollback(
             cfg.appearance = restored.appearance;
             cfg.window = restored.window;
         }
+        CommunityImportRollback::Snippet { keyword, previous } => {
+            cfg.snippets
+                .entries
+                .retain(|s| !s.keyword.eq_ignore_ascii_case(keyword));
+            if let Some(prev_snippet) = previous {
+                cfg.snippets.entries.push(prev_snippet.clone());
+            }
+        }
     }
 
     Ok(())
@@ -527,8 +540,31 @@ fn evaluate_snippet_preview(
             );
             true
         }
+        "snippet" => {
+            let text_snippet: Snippet = serde_json::from_value(snippet.payload.clone())
+                .map_err(|e| format!("Invalid text snippet: {}", e))?;
+
+            target_id = Some(text_snippet.keyword.clone());
+
+            if !crate::snippets::is_valid_keyword(&text_snippet.keyword) {
+                blockers.push("Snippet keyword must be a single word".to_string());
+            }
+
+            let will_replace_existing = cfg
+                .snippets
+                .entries
+                .iter()
+                .any(|s| s.keyword.eq_ignore_ascii_case(&text_snippet.keyword));
+            if will_replace_existing {
+                warnings.push(format!(
+                    "Snippet '{}' already exists and will be replaced",
+                    text_snippet.keyword
+                ));
+            }
+            will_replace_existing
+        }
         _ => {
-            return Err("Unsupported snippet kind. Use workflow, profile, or theme.".into());
+            return Err("Unsupported snippet kind. Use workflow, profile, snippet, or theme.".into());
         }
     };
 
@@ -878,8 +914,21 @@ pub async fn export_community_snippet(
                 .map_err(|e| format!("Failed to serialize theme snippet: {}", e))?;
             (cfg.appearance.theme.clone(), payload)
         }
+        "snippet" => {
+            let keyword = target_id.ok_or_else(|| "Snippet export requires target_id".to_string())?;
+            let text_snippet = cfg
+                .snippets
+                .entries
+                .iter()
+                .find(|s| s.keyword.eq_ignore_ascii_case(&keyword))
+                .cloned()
+                .ok_or_else(|| format!("Snippet '{}' not found", keyword))?;
+            let payload = serde_json::to_value(&text_snippet)
+                .map_err(|e| format!("Failed to serialize snippet: {}", e))?;
+            (text_snippet.keyword, payload)
+        }
         _ => {
-            return Err("Unsupported snippet kind. Use workflow, profile, or theme.".into());
+            return Err("Unsupported snippet kind. Use workflow, profile, snippet, or theme.".into());
         }
     };
 
@@ -1032,8 +1081,27 @@ pub async fn import_community_snippet(
             cfg.window = theme.window;
             Some(CommunityImportRollback::Theme { previous })
         }
+        "snippet" => {
+            let text_snippet: Snippet = serde_json::from_value(snippet.payload.clone())
+                .map_err(|e| format!("Invalid text snippet: {}", e))?;
+            let keyword = text_snippet.keyword.clone();
+            let previous = cfg
+                .snippets
+                .entries
+                .iter()
+                .find(|s| s.keyword.eq_ignore_ascii_case(&keyword))
+                .cloned();
+            if previous.is_some() && conflict_strategy == CommunityImportConflictStrategy::KeepLocal {
+                return Ok(format!(
+                    "Skipped snippet import '{}' because local item already exists.",
+                    keyword
+                ));
+            }
+            crate::snippets::merge(&mut cfg.snippets, vec![text_snippet])?;
+            Some(CommunityImportRollback::Snippet { keyword, previous })
+        }
         _ => {
-            return Err("Unsupported snippet kind. Use workflow, profile, or theme.".into());
+            return Err("Unsupported snippet kind. Use workflow, profile, snippet, or theme.".into());
         }
     };
 
@@ -1235,6 +1303,41 @@ mod tests {
         assert!(!cfg.workflows.macros.iter().any(|m| m.id == "temp-flow"));
     }
 
+    #[test]
+    fn rollback_restores_replaced_text_snippet() {
+        let mut cfg = config::VantaConfig::default();
+        cfg.snippets.entries.push(Snippet {
+            keyword: "sig".to_string(),
+            body: "Imported".to_string(),
+            tags: Vec::new(),
+        });
+        cfg.snippets.entries.push(Snippet {
+            keyword: "new".to_string(),
+            body: "Created by import".to_string(),
+            tags: Vec::new(),
+        });
+
+        for rollback in [
+            CommunityImportRollback::Snippet {
+                keyword: "sig".to_string(),
+                previous: Some(Snippet {
+                    keyword: "sig".to_string(),
+                    body: "Local".to_string(),
+                    tags: vec!["email".to_string()],
+                }),
+            },
+            CommunityImportRollback::Snippet {
+                keyword: "NEW".to_string(),
+                previous: None,
+            },
+        ] {
+            apply_import_rollback(&mut cfg, &rollback).unwrap();
+        }
+
+        assert_eq!(cfg.snippets.entries.len(), 1);
+        assert_eq!(cfg.snippets.entries[0].body, "Local");
+    }
+
     #[test]
     fn rollback_restores_previous_profile() {
         let mut cfg = config::VantaConfig::default();
@@ -51,7 +51,19 @@ pub struct VantaConfig {
     #[serde(default)]
     pub notes: NotesConfig,
     #[serde(default)]
+    pub snippets: SnippetsConfig,
+    #[serde(default)]
     pub bookmarks: BookmarksConfig,
+    #[serde(default)]
+    pub browser: BrowserSourceConfig,
+    #[serde(default)]
+    pub currency: CurrencyConfig,
+    #[serde(default)]
+    pub calculator: CalculatorConfig,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub paste: PasteConfig,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -81,6 +93,331 @@ impl Default for BookmarksConfig {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct BrowserProfileToggle {
+    pub id: String,
+    pub enabled: bool,
+}
+
+/// Browser bookmarks/history source. Enable/weight live in `search.web`;
+/// profiles missing from `profiles` are enabled.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct BrowserSourceConfig {
+    #[serde(default = "default_source_enabled")]
+    pub include_history: bool,
+    #[serde(default = "default_browser_history_limit")]
+    pub history_limit: usize,
+    #[serde(default)]
+    pub profiles: Vec<BrowserProfileToggle>,
+}
+
+fn default_browser_history_limit() -> usize {
+    500
+}
+
+impl Default for BrowserSourceConfig {
+    fn default() -> Self {
+        Self {
+            include_history: true,
+            history_limit: default_browser_history_limit(),
+            profiles: Vec::new(),
+        }
+    }
+}
+
+/// Exchange rates used by calculator currency conversion. `provider` is `ecb`
+/// (daily reference rate XML) or `json` (`{"base", "date", "rates"}`).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct CurrencyConfig {
+    #[serde(default = "default_source_enabled")]
+    pub auto_refresh: bool,
+    #[serde(default = "default_currency_provider")]
+    pub provider: String,
+    #[serde(default = "default_currency_rates_url")]
+    pub rates_url: String,
+    #[serde(default = "default_currency_refresh_hours")]
+    pub refresh_hours: u64,
+    #[serde(default = "default_currency_stale_after_days")]
+    pub stale_after_days: i64,
+}
+
+fn default_currency_provider() -> String {
+    "ecb".to_string()
+}
+
+fn default_currency_rates_url() -> String {
+    "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml".to_string()
+}
+
+fn default_currency_refresh_hours() -> u64 {
+    12
+}
+
+fn default_currency_stale_after_days() -> i64 {
+    4
+}
+
+impl Default for CurrencyConfig {
+    fn default() -> Self {
+        Self {
+            auto_refresh: true,
+            provider: default_currency_provider(),
+            rates_url: default_currency_rates_url(),
+            refresh_hours: default_currency_refresh_hours(),
+            stale_after_days: default_currency_stale_after_days(),
+        }
+    }
+}
+
+/// How exact calculator results are rounded for display and copying.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct CalculatorConfig {
+    /// Decimal places kept after rounding.
+    #[serde(default = "default_calculator_precision")]
+    pub precision: u32,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Ties away from zero (2.5 → 3, -2.5 → -3).
+    #[default]
+    HalfUp,
+    /// Ties toward zero.
+    HalfDown,
+    /// Ties to the even neighbour (banker's rounding).
+    HalfEven,
+    /// Truncate toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+    Floor,
+    Ceiling,
+}
+
+fn default_calculator_precision() -> u32 {
+    10
+}
+
+impl Default for CalculatorConfig {
+    fn default() -> Self {
+        Self {
+            precision: default_calculator_precision(),
+            rounding: RoundingMode::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardBackendPreference {
+    /// wl-clipboard on Wayland, xclip/xsel on X11, from `XDG_SESSION_TYPE`.
+    #[default]
+    Auto,
+    WlClipboard,
+    Xclip,
+    Xsel,
+    /// Keeps Vanta's own copies in memory; nothing reaches other apps.
+    InProcess,
+}
+
+/// What happens when something already in the history is copied again.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardDedup {
+    /// Move the existing entry to the top instead of adding a copy.
+    #[default]
+    BumpToTop,
+    /// Only skip a copy identical to the newest entry.
+    Consecutive,
+    /// Record every copy.
+    Off,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ClipboardConfig {
+    /// Unpinned entries beyond this count are dropped, oldest first.
+    #[serde(default = "default_clipboard_max_items")]
+    pub max_items: usize,
+    /// Unpinned entries older than this many days are purged; unset keeps them.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// Per content type (`url`, `text`, `image`, …) overrides of `max_age_days`.
+    #[serde(default)]
+    pub retention_days: HashMap<String, u32>,
+    /// Copied text longer than this many bytes is truncated.
+    #[serde(default = "default_clipboard_max_text_bytes")]
+    pub max_text_bytes: usize,
+    #[serde(default)]
+    pub dedup: ClipboardDedup,
+    /// Hours between `VACUUM`s of the history database; 0 disables them.
+    #[serde(default = "default_clipboard_vacuum_interval_hours")]
+    pub vacuum_interval_hours: u32,
+    /// Copied images larger than this are left out of the history.
+    #[serde(default = "default_clipboard_max_image_bytes")]
+    pub max_image_bytes: u64,
+    /// Clipboard tool used for history and copies; falls back to auto-detection
+    /// when the chosen one isn't installed.
+    #[serde(default)]
+    pub backend: ClipboardBackendPreference,
+    /// Skip copies that password managers flag with `x-kde-passwordManagerHint`.
+    #[serde(default = "default_clipboard_respect_password_hint")]
+    pub respect_password_hint: bool,
+    /// Text matching any of these regexes is treated as sensitive.
+    #[serde(default = "default_clipboard_deny_patterns")]
+    pub deny_patterns: Vec<String>,
+    /// Nothing is recorded while a window of one of these classes has focus.
+    #[serde(default = "default_clipboard_excluded_window_classes")]
+    pub excluded_window_classes: Vec<String>,
+    /// Keep sensitive-looking text for this many seconds instead of skipping it.
+    #[serde(default)]
+    pub sensitive_expire_secs: Option<u64>,
+}
+
+fn default_clipboard_max_image_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_clipboard_max_items() -> usize {
+    100
+}
+
+fn default_clipboard_max_text_bytes() -> usize {
+    100_000
+}
+
+fn default_clipboard_vacuum_interval_hours() -> u32 {
+    24
+}
+
+fn default_clipboard_respect_password_hint() -> bool {
+    true
+}
+
+fn default_clipboard_deny_patterns() -> Vec<String> {
+    [
+        // Card numbers in 4-4-4-4 or Amex 4-6-5 grouping.
+        r"\b(?:\d{4}[ -]?){3}\d{4}\b",
+        r"\b3[47]\d{2}[ -]?\d{6}[ -]?\d{5}\b",
+        // JSON Web Tokens.
+        r"\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        // AWS, GitHub, Slack, Stripe and `sk-` style API keys.
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        r"\b[rsp]k_(?:live|test)_[A-Za-z0-9]{16,}\b",
+        r"\bsk-[A-Za-z0-9_-]{20,}",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----",
+    ]
+    .iter()
+    .map(|pattern| pattern.to_string())
+    .collect()
+}
+
+fn default_clipboard_excluded_window_classes() -> Vec<String> {
+    ["org.keepassxc.KeePassXC", "KeePassXC", "1Password", "Bitwarden"]
+        .iter()
+        .map(|class| class.to_string())
+        .collect()
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            max_items: default_clipboard_max_items(),
+            max_age_days: None,
+            retention_days: HashMap::new(),
+            max_text_bytes: default_clipboard_max_text_bytes(),
+            dedup: ClipboardDedup::default(),
+            vacuum_interval_hours: default_clipboard_vacuum_interval_hours(),
+            max_image_bytes: default_clipboard_max_image_bytes(),
+            backend: ClipboardBackendPreference::default(),
+            respect_password_hint: default_clipboard_respect_password_hint(),
+            deny_patterns: default_clipboard_deny_patterns(),
+            excluded_window_classes: default_clipboard_excluded_window_classes(),
+            sensitive_expire_secs: None,
+        }
+    }
+}
+
+/// How the "Paste" action delivers text to the previously focused window.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMode {
+    /// Put the text on the clipboard and press the paste chord.
+    #[default]
+    Keystroke,
+    /// Type the text character by character; the clipboard is left alone.
+    Type,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct PasteConfig {
+    #[serde(default)]
+    pub mode: PasteMode,
+    /// Key chord sent to paste, like `ctrl+v` or `shift+insert`.
+    #[serde(default = "default_paste_chord")]
+    pub chord: String,
+    /// Chord sent instead when the target window is a terminal.
+    #[serde(default = "default_paste_terminal_chord")]
+    pub terminal_chord: String,
+    /// Window classes that get `terminal_chord`, matched case-insensitively.
+    #[serde(default = "default_paste_terminal_classes")]
+    pub terminal_classes: Vec<String>,
+    /// Milliseconds to wait after hiding Vanta before focusing and pasting.
+    #[serde(default = "default_paste_delay_ms")]
+    pub delay_ms: u64,
+}
+
+fn default_paste_chord() -> String {
+    "ctrl+v".to_string()
+}
+
+fn default_paste_terminal_chord() -> String {
+    "ctrl+shift+v".to_string()
+}
+
+fn default_paste_terminal_classes() -> Vec<String> {
+    [
+        "kitty",
+        "Alacritty",
+        "foot",
+        "footclient",
+        "org.wezfurlong.wezterm",
+        "com.mitchellh.ghostty",
+        "org.gnome.Console",
+        "gnome-terminal-server",
+        "org.kde.konsole",
+        "konsole",
+        "xterm",
+        "URxvt",
+        "st-256color",
+        "Terminator",
+        "tilix",
+    ]
+    .iter()
+    .map(|class| class.to_string())
+    .collect()
+}
+
+fn default_paste_delay_ms() -> u64 {
+    150
+}
+
+impl Default for PasteConfig {
+    fn default() -> Self {
+        Self {
+            mode: PasteMode::default(),
+            chord: default_paste_chord(),
+            terminal_chord: default_paste_terminal_chord(),
+            terminal_classes: default_paste_terminal_classes(),
+            delay_ms: default_paste_delay_ms(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct QuickNote {
     pub id: String,
@@ -109,6 +446,22 @@ impl Default for NotesConfig {
     }
 }
 
+/// Reusable text expanded by typing its keyword. The body may contain
+/// `{date:%Y-%m-%d}`, `{clipboard}`, `{cursor}`, `{uuid}` and `{input:Name}`.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Snippet {
+    pub keyword: String,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct SnippetsConfig {
+    #[serde(default)]
+    pub entries: Vec<Snippet>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct PolicyConfig {
     #[serde(default = "default_policy_restricted_mode")]
@@ -205,10 +558,83 @@ pub struct SearchConfig {
     pub calculator: SourcePreference,
     #[serde(default)]
     pub files: SourcePreference,
+    #[serde(default)]
+    pub web: SourcePreference,
+    #[serde(default)]
+    pub recent: SourcePreference,
+    #[serde(default)]
+    pub ssh: SourcePreference,
+    #[serde(default)]
+    pub emoji: SourcePreference,
+    #[serde(default)]
+    pub systemd: SourcePreference,
+    #[serde(default)]
+    pub repos: SourcePreference,
+    #[serde(default)]
+    pub transforms: SourcePreference,
     #[serde(default = "default_windows_cap")]
     pub windows_max_results: usize,
     #[serde(default = "default_show_explain_panel")]
     pub show_explain_panel: bool,
+    #[serde(default)]
+    pub quicklinks: QuicklinksConfig,
+}
+
+/// Keyword shortcut expanding a URL or local target template.
+/// `{query}` is replaced URL-encoded, `{raw}` verbatim.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct Quicklink {
+    pub keyword: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default = "default_source_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct QuicklinksConfig {
+    #[serde(default = "default_quicklinks")]
+    pub entries: Vec<Quicklink>,
+    #[serde(default = "default_web_search_fallback")]
+    pub web_search_fallback: bool,
+    #[serde(default = "default_fallback_keyword")]
+    pub fallback_keyword: String,
+}
+
+fn default_quicklinks() -> Vec<Quicklink> {
+    let entry = |keyword: &str, name: &str, url: &str, icon: &str| Quicklink {
+        keyword: keyword.to_string(),
+        name: name.to_string(),
+        url: url.to_string(),
+        icon: Some(icon.to_string()),
+        enabled: true,
+    };
+    vec![
+        entry("ddg", "DuckDuckGo", "https://duckduckgo.com/?q={query}", "fa-solid fa-magnifying-glass"),
+        entry("g", "Google", "https://www.google.com/search?q={query}", "fa-brands fa-google"),
+        entry("gh", "GitHub", "https://github.com/search?q={query}", "fa-brands fa-github"),
+        entry("wiki", "Wikipedia", "https://en.wikipedia.org/w/index.php?search={query}", "fa-brands fa-wikipedia-w"),
+    ]
+}
+
+fn default_web_search_fallback() -> bool {
+    true
+}
+
+fn default_fallback_keyword() -> String {
+    "ddg".to_string()
+}
+
+impl Default for QuicklinksConfig {
+    fn default() -> Self {
+        Self {
+            entries: default_quicklinks(),
+            web_search_fallback: default_web_search_fallback(),
+            fallback_keyword: default_fallback_keyword(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -243,8 +669,16 @@ impl Default for SearchConfig {
             windows: SourcePreference::default(),
             calculator: SourcePreference::default(),
             files: SourcePreference::default(),
+            web: SourcePreference::default(),
+            recent: SourcePreference::default(),
+            ssh: SourcePreference::default(),
+            emoji: SourcePreference::default(),
+            systemd: SourcePreference::default(),
+            repos: SourcePreference::default(),
+            transforms: SourcePreference::default(),
             windows_max_results: default_windows_cap(),
             show_explain_panel: default_show_explain_panel(),
+            quicklinks: QuicklinksConfig::default(),
         }
     }
 }
@@ -279,12 +713,21 @@ pub struct FilesConfig {
     pub type_filter: String, // "any" | "file" | "dir"
     #[serde(default)]
     pub indexed_at: Option<u64>, // epoch millis, informational freshness marker
+    /// Roots scanned for git repositories; empty means the file index root.
+    #[serde(default)]
+    pub repo_roots: Vec<String>,
+    #[serde(default = "default_repo_max_depth")]
+    pub repo_max_depth: usize,
 }
 
 fn default_max_depth() -> usize {
     3
 }
 
+fn default_repo_max_depth() -> usize {
+    6
+}
+
 fn default_opener() -> String {
     "default".to_string()
 }
@@ -310,6 +753,8 @@ impl Default for FilesConfig {
             allowed_extensions: Vec::new(),
             type_filter: default_type_filter(),
             indexed_at: None,
+            repo_roots: Vec::new(),
+            repo_max_depth: default_repo_max_depth(),
         }
     }
 }
@@ -809,13 +1254,21 @@ impl Default for VantaConfig {
                 allowed_extensions: Vec::new(),
                 type_filter: default_type_filter(),
                 indexed_at: None,
+                repo_roots: Vec::new(),
+                repo_max_depth: default_repo_max_depth(),
             },
             search: SearchConfig::default(),
             workflows: WorkflowsConfig::default(),
             profiles: ProfilesConfig::default(),
             policy: PolicyConfig::default(),
             notes: NotesConfig::default(),
+            snippets: SnippetsConfig::default(),
             bookmarks: BookmarksConfig::default(),
+            browser: BrowserSourceConfig::default(),
+            currency: CurrencyConfig::default(),
+            calculator: CalculatorConfig::default(),
+            clipboard: ClipboardConfig::default(),
+            paste: PasteConfig::default(),
         }
     }
 }
@@ -1549,6 +2002,21 @@ mod tests {
                 assert!(parsed.notes.entries.is_empty());
                 assert_eq!(parsed.bookmarks.max_entries, 200);
                 assert!(parsed.bookmarks.entries.is_empty());
+                assert!(parsed.search.quicklinks.web_search_fallback);
+                assert!(parsed.search.quicklinks.entries.iter().any(|q| q.keyword == "gh"));
+                assert!(parsed.search.web.enabled);
+                assert!(parsed.search.recent.enabled);
+                assert!(parsed.search.ssh.enabled);
+                assert!(parsed.search.emoji.enabled);
+                assert!(parsed.search.systemd.enabled);
+                assert!(parsed.search.repos.enabled);
+                assert!(parsed.search.transforms.enabled);
+                assert_eq!(parsed.files.repo_max_depth, 6);
+                assert!(parsed.browser.include_history);
+                assert!(parsed.browser.profiles.is_empty());
+                assert_eq!(parsed.currency.provider, "ecb");
+                assert_eq!(parsed.calculator.precision, 10);
+                assert_eq!(parsed.clipboard.max_image_bytes, 10 * 1024 * 1024);
         }
 
             #[test]
@@ -1734,8 +2202,26 @@ mod tests {
                 windows: SourcePreference { enabled: true, weight: 110 },
                 calculator: SourcePreference { enabled: true, weight: 80 },
                 files: SourcePreference { enabled: true, weight: 140 },
+                web: SourcePreference { enabled: false, weight: 100 },
+                recent: SourcePreference { enabled: true, weight: 120 },
+                ssh: SourcePreference { enabled: true, weight: 90 },
+                emoji: SourcePreference { enabled: true, weight: 100 },
+                systemd: SourcePreference { enabled: true, weight: 100 },
+                repos: SourcePreference { enabled: true, weight: 100 },
+                transforms: SourcePreference { enabled: true, weight: 100 },
                 windows_max_results: 6,
                 show_explain_panel: true,
+                quicklinks: QuicklinksConfig {
+                    entries: vec![Quicklink {
+                        keyword: "jira".to_string(),
+                        name: "Jira".to_string(),
+                        url: "https://jira.example.com/browse/{raw}".to_string(),
+                        icon: None,
+                        enabled: true,
+                    }],
+                    web_search_fallback: false,
+                    fallback_keyword: "jira".to_string(),
+                },
             },
         });
 
@@ -1746,6 +2232,9 @@ mod tests {
         assert_eq!(cfg.appearance.theme, "high-contrast");
         assert_eq!(cfg.search.files.weight, 140);
         assert_eq!(cfg.search.windows_max_results, 6);
+        assert_eq!(cfg.search.quicklinks.entries[0].keyword, "jira");
+        assert!(!cfg.search.quicklinks.web_search_fallback);
+        assert!(!cfg.search.web.enabled);
     }
 
     #[test]
@@ -0,0 +1,453 @@
+//! Exchange rates for calculator currency conversion.
+//!
+//! Rates come from a [`RateProvider`] (ECB daily XML or a JSON rates endpoint), are
+//! cached in `currency_rates.json` under the config dir, and fall back to a bundled
+//! table when nothing has been fetched yet.
+
+use crate::config::CurrencyConfig;
+use crate::errors::VantaError;
+use chrono::{NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+pub const RATES_FILE: &str = "currency_rates.json";
+pub const BUNDLED_PROVIDER: &str = "bundled";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the background worker re-checks whether a refresh is due.
+const WORKER_TICK: Duration = Duration::from_secs(30 * 60);
+
+/// USD value of one unit, used until a provider has been fetched successfully.
+const BUNDLED_USD_PER_UNIT: &[(&str, f64)] = &[
+    ("EUR", 1.09),
+    ("GBP", 1.28),
+    ("JPY", 0.0067),
+    ("INR", 0.012),
+    ("CNY", 0.14),
+    ("CAD", 0.74),
+    ("AUD", 0.66),
+    ("CHF", 1.11),
+    ("SEK", 0.096),
+    ("NOK", 0.094),
+    ("SGD", 0.74),
+    ("AED", 0.272),
+];
+
+static ECB_TIME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<Cube\s+time=['"](\d{4}-\d{2}-\d{2})['"]"#).expect("ECB time regex must be valid")
+});
+
+static ECB_RATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<Cube\s+currency=['"]([A-Z]{3})['"]\s+rate=['"]([0-9.]+)['"]"#)
+        .expect("ECB rate regex must be valid")
+});
+
+static CURRENT: LazyLock<RwLock<RateTable>> = LazyLock::new(|| RwLock::new(bundled_table()));
+
+/// Units of each currency per one unit of `base`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RateTable {
+    pub base: String,
+    pub provider: String,
+    /// Publication date of the rates, when the provider reports one.
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+    #[serde(default)]
+    pub fetched_at_ms: Option<i64>,
+    pub rates: BTreeMap<String, f64>,
+}
+
+impl RateTable {
+    fn units_per_base(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            return Some(1.0);
+        }
+        self.rates.get(code).copied().filter(|r| *r > 0.0)
+    }
+
+    pub fn supports(&self, code: &str) -> bool {
+        self.units_per_base(code).is_some()
+    }
+
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Option<f64> {
+        Some(value / self.units_per_base(from)? * self.units_per_base(to)?)
+    }
+
+    pub fn stamp(&self) -> RateStamp {
+        RateStamp {
+            provider: self.provider.clone(),
+            date: self.date,
+        }
+    }
+}
+
+/// Where a converted amount's rate came from, for the result subtitle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateStamp {
+    pub provider: String,
+    pub date: Option<NaiveDate>,
+}
+
+impl RateStamp {
+    pub fn is_bundled(&self) -> bool {
+        self.provider == BUNDLED_PROVIDER
+    }
+
+    pub fn is_stale(&self, stale_after_days: i64, today: NaiveDate) -> bool {
+        match self.date {
+            Some(date) => (today - date).num_days() > stale_after_days,
+            None => true,
+        }
+    }
+
+    /// `ECB rates · 2026-10-16`, with a warning prefix once they are out of date.
+    pub fn describe(&self, stale_after_days: i64, today: NaiveDate) -> String {
+        if self.is_bundled() {
+            return "⚠ Offline fallback rates — may be out of date".to_string();
+        }
+        let label = match self.provider.as_str() {
+            "ecb" => "ECB rates",
+            _ => "Rates",
+        };
+        match self.date {
+            Some(date) if self.is_stale(stale_after_days, today) => format!(
+                "⚠ {} from {} ({} days old)",
+                label,
+                date,
+                (today - date).num_days()
+            ),
+            Some(date) => format!("{} · {}", label, date),
+            None => format!("⚠ {} · date unknown", label),
+        }
+    }
+}
+
+pub trait RateProvider: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn fetch(&self) -> Result<RateTable, VantaError>;
+}
+
+/// European Central Bank daily reference rates (`eurofxref-daily.xml`).
+pub struct EcbXmlProvider {
+    pub url: String,
+}
+
+/// Any endpoint returning `{"base": "USD", "date": "2026-10-16", "rates": {"EUR": 0.92, …}}`.
+pub struct JsonRatesProvider {
+    pub url: String,
+}
+
+fn fetch_body(url: &str) -> Result<String, VantaError> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| format!("Failed to fetch rates from {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Rates request to {} failed: HTTP {}",
+            url,
+            response.status()
+        )
+        .into());
+    }
+    response
+        .text()
+        .map_err(|e| format!("Failed to read rates response: {}", e).into())
+}
+
+impl RateProvider for EcbXmlProvider {
+    fn id(&self) -> &'static str {
+        "ecb"
+    }
+
+    fn fetch(&self) -> Result<RateTable, VantaError> {
+        let body = fetch_body(&self.url)?;
+        parse_ecb_xml(&body).ok_or_else(|| "Rates response is not ECB reference rate XML".into())
+    }
+}
+
+impl RateProvider for JsonRatesProvider {
+    fn id(&self) -> &'static str {
+        "json"
+    }
+
+    fn fetch(&self) -> Result<RateTable, VantaError> {
+        let body = fetch_body(&self.url)?;
+        parse_json_rates(&body).ok_or_else(|| "Rates response has no `base` and `rates`".into())
+    }
+}
+
+pub fn provider_for(config: &CurrencyConfig) -> Box<dyn RateProvider> {
+    match config.provider.as_str() {
+        "json" => Box::new(JsonRatesProvider {
+            url: config.rates_url.clone(),
+        }),
+        _ => Box::new(EcbXmlProvider {
+            url: config.rates_url.clone(),
+        }),
+    }
+}
+
+pub fn parse_ecb_xml(body: &str) -> Option<RateTable> {
+    let date = ECB_TIME_RE
+        .captures(body)
+        .and_then(|caps| NaiveDate::parse_from_str(&caps[1], "%Y-%m-%d").ok());
+    let rates: BTreeMap<String, f64> = ECB_RATE_RE
+        .captures_iter(body)
+        .filter_map(|caps| Some((caps[1].to_string(), caps[2].parse::<f64>().ok()?)))
+        .collect();
+    if rates.is_empty() {
+        return None;
+    }
+    Some(RateTable {
+        base: "EUR".to_string(),
+        provider: "ecb".to_string(),
+        date,
+        fetched_at_ms: Some(Utc::now().timestamp_millis()),
+        rates,
+    })
+}
+
+pub fn parse_json_rates(body: &str) -> Option<RateTable> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let base = value
+        .get("base")
+        .or_else(|| value.get("base_code"))?
+        .as_str()?
+        .to_ascii_uppercase();
+    let rates: BTreeMap<String, f64> = value
+        .get("rates")
+        .or_else(|| value.get("conversion_rates"))?
+        .as_object()?
+        .iter()
+        .filter_map(|(code, rate)| Some((code.to_ascii_uppercase(), rate.as_f64()?)))
+        .collect();
+    let date = value
+        .get("date")
+        .and_then(|d| d.as_str())
+        .and_then(|d| NaiveDate::parse_from_str(d.get(..10)?, "%Y-%m-%d").ok());
+    Some(RateTable {
+        base,
+        provider: "json".to_string(),
+        date,
+        fetched_at_ms: Some(Utc::now().timestamp_millis()),
+        rates,
+    })
+}
+
+pub fn bundled_table() -> RateTable {
+    RateTable {
+        base: "USD".to_string(),
+        provider: BUNDLED_PROVIDER.to_string(),
+        date: None,
+        fetched_at_ms: None,
+        rates: BUNDLED_USD_PER_UNIT
+            .iter()
+            .map(|(code, usd)| (code.to_string(), 1.0 / usd))
+            .collect(),
+    }
+}
+
+fn cache_path(dir: &Path) -> PathBuf {
+    dir.join(RATES_FILE)
+}
+
+pub fn load_cache(dir: &Path) -> Option<RateTable> {
+    let raw = std::fs::read_to_string(cache_path(dir)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn save_cache(dir: &Path, table: &RateTable) -> Result<(), VantaError> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(cache_path(dir), serde_json::to_string_pretty(table)?)?;
+    Ok(())
+}
+
+/// The table currently used for conversions.
+pub fn current() -> RateTable {
+    CURRENT
+        .read()
+        .map(|table| table.clone())
+        .unwrap_or_else(|_| bundled_table())
+}
+
+pub fn install(table: RateTable) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = table;
+    }
+}
+
+/// Load the cached rates file, if any, into the active table.
+pub fn init(dir: &Path) {
+    if let Some(table) = load_cache(dir) {
+        install(table);
+    }
+}
+
+/// Fetch from the provider and write the cache file without touching the active table.
+pub fn fetch_and_cache(dir: &Path, provider: &dyn RateProvider) -> Result<RateTable, VantaError> {
+    let table = provider.fetch()?;
+    save_cache(dir, &table)?;
+    Ok(table)
+}
+
+pub fn refresh(dir: &Path, config: &CurrencyConfig) -> Result<RateTable, VantaError> {
+    let table = fetch_and_cache(dir, provider_for(config).as_ref())?;
+    install(table.clone());
+    Ok(table)
+}
+
+pub fn needs_refresh(table: &RateTable, config: &CurrencyConfig, now_ms: i64) -> bool {
+    match table.fetched_at_ms {
+        Some(fetched) if table.provider != BUNDLED_PROVIDER => {
+            now_ms - fetched >= config.refresh_hours as i64 * 3_600_000
+        }
+        _ => true,
+    }
+}
+
+/// Background refresh loop. `config` is re-read every tick so settings changes apply
+/// without a restart; failures keep whatever table is active.
+pub fn spawn_refresh_worker<F>(dir: PathBuf, config: F)
+where
+    F: Fn() -> CurrencyConfig + Send + 'static,
+{
+    std::thread::spawn(move || loop {
+        let cfg = config();
+        if cfg.auto_refresh
+            && !cfg.rates_url.trim().is_empty()
+            && needs_refresh(&current(), &cfg, Utc::now().timestamp_millis())
+        {
+            match refresh(&dir, &cfg) {
+                Ok(table) => log::info!(
+                    "Currency rates refreshed from {} ({} currencies)",
+                    table.provider,
+                    table.rates.len()
+                ),
+                Err(e) => log::warn!("Currency rate refresh failed: {}", e),
+            }
+        }
+        std::thread::sleep(WORKER_TICK);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    const ECB_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+  <Cube>
+    <Cube time='2026-10-16'>
+      <Cube currency='USD' rate='1.1000'/>
+      <Cube currency='JPY' rate='165.00'/>
+      <Cube currency='GBP' rate='0.8500'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+
+    fn config(url: String, provider: &str) -> CurrencyConfig {
+        CurrencyConfig {
+            provider: provider.to_string(),
+            rates_url: url,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_ecb_xml_and_cross_converts() {
+        let table = parse_ecb_xml(ECB_XML).expect("ecb table");
+        assert_eq!(table.base, "EUR");
+        assert_eq!(table.date, NaiveDate::from_ymd_opt(2026, 10, 16));
+        assert!((table.convert(110.0, "USD", "EUR").unwrap() - 100.0).abs() < 1e-9);
+        assert!((table.convert(1.0, "USD", "JPY").unwrap() - 150.0).abs() < 1e-9);
+        assert!(table.convert(1.0, "USD", "XYZ").is_none());
+        assert!(parse_ecb_xml("<html>maintenance</html>").is_none());
+    }
+
+    #[test]
+    fn fetches_from_mock_server_and_caches() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(GET).path("/eurofxref-daily.xml");
+            then.status(200)
+                .header("content-type", "text/xml")
+                .body(ECB_XML);
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = config(server.url("/eurofxref-daily.xml"), "ecb");
+
+        let table = fetch_and_cache(dir.path(), provider_for(&cfg).as_ref()).expect("fetch");
+        mock.assert();
+        assert_eq!(table.rates.get("GBP"), Some(&0.85));
+        assert_eq!(load_cache(dir.path()), Some(table.clone()));
+        assert!(!needs_refresh(
+            &table,
+            &cfg,
+            table.fetched_at_ms.unwrap() + 1_000
+        ));
+        assert!(needs_refresh(&bundled_table(), &cfg, 0));
+    }
+
+    #[test]
+    fn json_provider_and_failures() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/latest");
+            then.status(200).json_body(serde_json::json!({
+                "base": "USD",
+                "date": "2026-10-15",
+                "rates": { "EUR": 0.9, "CHF": 0.8 }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/down");
+            then.status(503);
+        });
+        let dir = tempfile::tempdir().unwrap();
+
+        let cfg = config(server.url("/latest"), "json");
+        let table = fetch_and_cache(dir.path(), provider_for(&cfg).as_ref()).expect("json rates");
+        assert_eq!(table.base, "USD");
+        assert!((table.convert(9.0, "EUR", "USD").unwrap() - 10.0).abs() < 1e-9);
+
+        let down = config(server.url("/down"), "ecb");
+        assert!(fetch_and_cache(dir.path(), provider_for(&down).as_ref()).is_err());
+        // The earlier cache survives a failed refresh.
+        assert_eq!(
+            load_cache(dir.path()).map(|t| t.provider),
+            Some("json".to_string())
+        );
+    }
+
+    #[test]
+    fn describes_rate_age() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let fresh = RateStamp {
+            provider: "ecb".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 10, 16),
+        };
+        assert_eq!(fresh.describe(4, today), "ECB rates · 2026-10-16");
+        let old = RateStamp {
+            provider: "ecb".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 9, 1),
+        };
+        assert_eq!(
+            old.describe(4, today),
+            "⚠ ECB rates from 2026-09-01 (47 days old)"
+        );
+        assert!(bundled_table()
+            .stamp()
+            .describe(4, today)
+            .starts_with("⚠ Offline"));
+    }
+}
@@ -0,0 +1,788 @@
+//! Date and time arithmetic for the calculator.
+//!
+//! Handles `now + 3 weeks`, `2026-12-24 - today`, `days until christmas`,
+//! `unix 1700000000`, `to unix 2026-01-01 09:00`, `3pm PST in Tokyo`,
+//! `week number` and `next friday`. Every answer comes back as a human
+//! display string plus an ISO 8601 value for the clipboard.
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static OPERATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s+([+-])\s+").expect("date operator regex must be valid"));
+
+static ISO_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4})-(\d{1,2})-(\d{1,2})(?:t|\b)").expect("iso date regex must be valid")
+});
+
+static TIME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,2})(?::(\d{2}))?(?::(\d{2}))?\s*(am|pm)?$")
+        .expect("time regex must be valid")
+});
+
+static SPAN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+(?:\.\d+)?)\s*([a-z]+)").expect("span regex must be valid"));
+
+static UNIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:from\s+)?unix(?:\s+time)?\s+(-?\d+(?:\.\d+)?)$")
+        .expect("unix regex must be valid")
+});
+
+static TO_UNIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:to\s+unix\s+(.+)|(.+?)\s+(?:to|in)\s+unix)$")
+        .expect("to-unix regex must be valid")
+});
+
+static WEEK_NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:iso\s+)?week\s+(?:number|num|no\.?|of)(?:\s+(?:of\s+|for\s+)?(.+))?$")
+        .expect("week number regex must be valid")
+});
+
+static COUNT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(days|weeks|hours|minutes)\s+(until|till|to|since|from)\s+(.+)$")
+        .expect("count regex must be valid")
+});
+
+static RELATIVE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.+?)\s+(ago|from\s+(.+))$").expect("relative regex must be valid")
+});
+
+/// Named days that resolve to their next occurrence (today included).
+const HOLIDAYS: &[(&str, u32, u32)] = &[
+    ("christmas eve", 12, 24),
+    ("christmas", 12, 25),
+    ("xmas", 12, 25),
+    ("new year's eve", 12, 31),
+    ("new years eve", 12, 31),
+    ("new year's day", 1, 1),
+    ("new year's", 1, 1),
+    ("new years", 1, 1),
+    ("new year", 1, 1),
+    ("halloween", 10, 31),
+    ("valentine's day", 2, 14),
+    ("valentines day", 2, 14),
+    ("valentines", 2, 14),
+];
+
+static LOCAL_TZ: LazyLock<Tz> = LazyLock::new(detect_local_timezone);
+
+/// `$TZ`, then the `/etc/localtime` symlink, then `/etc/timezone`; UTC otherwise.
+fn detect_local_timezone() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|raw| raw.trim_start_matches(':').parse::<Tz>().ok())
+        .or_else(|| {
+            let target = std::fs::read_link("/etc/localtime").ok()?;
+            let target = target.to_string_lossy();
+            let (_, name) = target.split_once("zoneinfo/")?;
+            name.parse::<Tz>().ok()
+        })
+        .or_else(|| {
+            std::fs::read_to_string("/etc/timezone")
+                .ok()
+                .and_then(|raw| raw.trim().parse::<Tz>().ok())
+        })
+        .unwrap_or(Tz::UTC)
+}
+
+fn resolve_timezone_alias(raw: &str) -> Option<Tz> {
+    let key = raw.trim().to_ascii_lowercase();
+    let alias = match key.as_str() {
+        "utc" | "gmt" | "z" => "UTC",
+        "london" | "bst" => "Europe/London",
+        "new york" | "nyc" | "est" | "edt" => "America/New_York",
+        "los angeles" | "la" | "pst" | "pdt" => "America/Los_Angeles",
+        "san francisco" | "sf" => "America/Los_Angeles",
+        "chicago" | "cst" | "cdt" => "America/Chicago",
+        "denver" | "mst" | "mdt" => "America/Denver",
+        "tokyo" | "jst" => "Asia/Tokyo",
+        "seoul" | "kst" => "Asia/Seoul",
+        "singapore" => "Asia/Singapore",
+        "shanghai" => "Asia/Shanghai",
+        "hong kong" => "Asia/Hong_Kong",
+        "delhi" | "india" | "ist" => "Asia/Kolkata",
+        "dubai" => "Asia/Dubai",
+        "berlin" | "cet" | "cest" => "Europe/Berlin",
+        "paris" => "Europe/Paris",
+        "athens" | "eet" | "eest" => "Europe/Athens",
+        "sydney" | "aest" | "aedt" => "Australia/Sydney",
+        "auckland" | "nzst" | "nzdt" => "Pacific/Auckland",
+        _ => return None,
+    };
+    alias.parse::<Tz>().ok()
+}
+
+/// City aliases and common abbreviations first, then IANA names (`America/New York` works).
+pub fn resolve_timezone(raw: &str) -> Option<Tz> {
+    if let Some(tz) = resolve_timezone_alias(raw) {
+        return Some(tz);
+    }
+
+    let cleaned = raw.trim().replace(' ', "_");
+    cleaned
+        .parse::<Tz>()
+        .ok()
+        .or_else(|| title_case_zone(&cleaned).parse::<Tz>().ok())
+}
+
+/// `america/new_york` → `America/New_York`, since queries are matched lowercased.
+fn title_case_zone(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut upper = true;
+    for ch in raw.chars() {
+        if upper {
+            out.extend(ch.to_uppercase());
+        } else {
+            out.push(ch);
+        }
+        upper = matches!(ch, '/' | '_' | '-');
+    }
+    out
+}
+
+/// A point in time; `has_time` is false for plain dates (`next friday`, `2026-12-24`).
+#[derive(Clone, Copy, Debug)]
+struct Moment {
+    at: DateTime<Tz>,
+    has_time: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Span {
+    Seconds(f64),
+    Days(i64),
+    Months(i64),
+}
+
+impl Span {
+    fn is_calendar(self) -> bool {
+        !matches!(self, Span::Seconds(_))
+    }
+}
+
+enum Value {
+    Moment(Moment),
+    Difference(Moment, Moment),
+}
+
+/// Wall-clock time in `tz`; times skipped by a DST jump move forward an hour.
+fn localize(tz: Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&naive).earliest().or_else(|| {
+        tz.from_local_datetime(&(naive + Duration::hours(1)))
+            .earliest()
+    })
+}
+
+fn parse_time(raw: &str) -> Option<NaiveTime> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix("at ").unwrap_or(raw).trim();
+    match raw {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+    let caps = TIME_RE.captures(raw)?;
+    let meridiem = caps.get(4).map(|m| m.as_str());
+    // A bare number is arithmetic, not a time of day.
+    if caps.get(2).is_none() && meridiem.is_none() {
+        return None;
+    }
+    let mut hour = caps.get(1)?.as_str().parse::<u32>().ok()?;
+    let minute = caps
+        .get(2)
+        .map_or(Some(0), |m| m.as_str().parse::<u32>().ok())?;
+    let second = caps
+        .get(3)
+        .map_or(Some(0), |m| m.as_str().parse::<u32>().ok())?;
+    match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") if hour == 12 => hour = 0,
+        Some("pm") if hour != 12 => hour += 12,
+        _ => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+fn strip_word<'a>(raw: &'a str, word: &str) -> Option<&'a str> {
+    let rest = raw.strip_prefix(word)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+fn next_annual(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if this_year >= today {
+        Some(this_year)
+    } else {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    }
+}
+
+fn weekday_relative(today: NaiveDate, weekday: Weekday, mode: &str) -> NaiveDate {
+    let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    let ahead = ahead as u64;
+    match mode {
+        "next" => today + Days::new(if ahead == 0 { 7 } else { ahead }),
+        "last" => today - Days::new(if ahead == 0 { 7 } else { 7 - ahead }),
+        _ => today + Days::new(ahead),
+    }
+}
+
+/// Splits a leading day expression off `raw`, returning the date and the remainder.
+fn parse_day(raw: &str, today: NaiveDate) -> Option<(NaiveDate, &str)> {
+    if let Some(caps) = ISO_DATE_RE.captures(raw) {
+        let date = NaiveDate::from_ymd_opt(
+            caps.get(1)?.as_str().parse().ok()?,
+            caps.get(2)?.as_str().parse().ok()?,
+            caps.get(3)?.as_str().parse().ok()?,
+        )?;
+        return Some((date, &raw[caps.get(0)?.end()..]));
+    }
+
+    for (word, offset) in [("today", 0i64), ("tomorrow", 1), ("yesterday", -1)] {
+        if let Some(rest) = strip_word(raw, word) {
+            return Some((today + Duration::days(offset), rest));
+        }
+    }
+
+    for (name, month, day) in HOLIDAYS {
+        if let Some(rest) = strip_word(raw, name) {
+            return Some((next_annual(today, *month, *day)?, rest));
+        }
+    }
+
+    let (mode, rest) = ["next", "last", "this"]
+        .iter()
+        .find_map(|mode| strip_word(raw, mode).map(|rest| (*mode, rest.trim_start())))
+        .unwrap_or(("", raw));
+    let (word, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let weekday = word.parse::<Weekday>().ok()?;
+    Some((weekday_relative(today, weekday, mode), rest))
+}
+
+/// Parses a moment whose wall-clock fields are in `tz`.
+fn parse_moment_in(raw: &str, tz: Tz, now: DateTime<Utc>) -> Option<Moment> {
+    let raw = raw.trim();
+    let now_local = now.with_timezone(&tz);
+    if raw == "now" {
+        return Some(Moment {
+            at: now_local,
+            has_time: true,
+        });
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(&raw.to_ascii_uppercase()) {
+        return Some(Moment {
+            at: parsed.with_timezone(&tz),
+            has_time: true,
+        });
+    }
+
+    let (date, rest) = match parse_day(raw, now_local.date_naive()) {
+        Some((date, rest)) => (date, rest.trim()),
+        None => (now_local.date_naive(), raw),
+    };
+    if rest.is_empty() {
+        if raw.is_empty() {
+            return None;
+        }
+        return Some(Moment {
+            at: localize(tz, date.and_time(NaiveTime::MIN))?,
+            has_time: false,
+        });
+    }
+    let time = parse_time(rest)?;
+    Some(Moment {
+        at: localize(tz, date.and_time(time))?,
+        has_time: true,
+    })
+}
+
+/// Parses a moment in the local zone, or in a trailing zone (`3pm pst`, `9:00 new york`).
+fn parse_moment(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<Moment> {
+    if let Some(moment) = parse_moment_in(raw, local, now) {
+        return Some(moment);
+    }
+    let words: Vec<&str> = raw.split_whitespace().collect();
+    (1..words.len()).rev().find_map(|split| {
+        let tz = resolve_timezone(&words[split..].join(" "))?;
+        let moment = parse_moment_in(&words[..split].join(" "), tz, now)?;
+        Some(Moment {
+            at: moment.at.with_timezone(&local),
+            ..moment
+        })
+    })
+}
+
+/// `3 weeks`, `1 week 2 days`, `90 min`.
+fn parse_spans(raw: &str) -> Option<Vec<Span>> {
+    let mut spans = Vec::new();
+    let mut consumed = 0;
+    for caps in SPAN_RE.captures_iter(raw) {
+        let whole = caps.get(0)?;
+        if !raw[consumed..whole.start()].trim().is_empty() {
+            return None;
+        }
+        consumed = whole.end();
+        let amount = caps.get(1)?.as_str().parse::<f64>().ok()?;
+        let whole_amount = (amount.fract() == 0.0).then_some(amount as i64);
+        let span = match caps.get(2)?.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => Span::Seconds(amount),
+            "min" | "mins" | "minute" | "minutes" => Span::Seconds(amount * 60.0),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Span::Seconds(amount * 3_600.0),
+            "d" | "day" | "days" => whole_amount
+                .map(Span::Days)
+                .unwrap_or(Span::Seconds(amount * 86_400.0)),
+            "w" | "wk" | "wks" | "week" | "weeks" => match whole_amount {
+                Some(n) => Span::Days(n.checked_mul(7)?),
+                None => Span::Seconds(amount * 604_800.0),
+            },
+            "mo" | "month" | "months" => Span::Months(whole_amount?),
+            "y" | "yr" | "yrs" | "year" | "years" => Span::Months(whole_amount?.checked_mul(12)?),
+            _ => return None,
+        };
+        spans.push(span);
+    }
+    (!spans.is_empty() && raw[consumed..].trim().is_empty()).then_some(spans)
+}
+
+/// Calendar spans keep the wall-clock time across DST changes; seconds are exact.
+fn shift(moment: Moment, span: Span, negative: bool) -> Option<Moment> {
+    let tz = moment.at.timezone();
+    let naive = moment.at.naive_local();
+    let shifted = match span {
+        Span::Days(days) => {
+            let days = if negative { days.checked_neg()? } else { days };
+            localize(tz, naive.checked_add_signed(Duration::try_days(days)?)?)?
+        }
+        Span::Months(months) => {
+            let amount = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+            let shifted = if (months < 0) != negative {
+                naive.checked_sub_months(amount)?
+            } else {
+                naive.checked_add_months(amount)?
+            };
+            localize(tz, shifted)?
+        }
+        Span::Seconds(seconds) => {
+            let millis = (seconds * 1_000.0).round() as i64;
+            let delta = Duration::try_milliseconds(if negative { -millis } else { millis })?;
+            moment.at.checked_add_signed(delta)?
+        }
+    };
+    Some(Moment {
+        at: shifted,
+        has_time: moment.has_time || !span.is_calendar(),
+    })
+}
+
+/// `<moment> (± <span>)*`, optionally followed by `- <moment>` for a difference.
+fn evaluate_expression(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<Value> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    let mut pending_op = '+';
+    for caps in OPERATOR_RE.captures_iter(raw) {
+        let whole = caps.get(0)?;
+        segments.push((pending_op, &raw[last..whole.start()]));
+        pending_op = if caps.get(1)?.as_str() == "-" {
+            '-'
+        } else {
+            '+'
+        };
+        last = whole.end();
+    }
+    segments.push((pending_op, &raw[last..]));
+
+    let mut moment = parse_moment(segments[0].1, local, now)?;
+    for (index, (op, text)) in segments.iter().enumerate().skip(1) {
+        if let Some(spans) = parse_spans(text) {
+            for span in spans {
+                moment = shift(moment, span, *op == '-')?;
+            }
+            continue;
+        }
+        if *op == '-' && index == segments.len() - 1 {
+            let other = parse_moment(text, local, now)?;
+            return Some(Value::Difference(moment, other));
+        }
+        return None;
+    }
+    Some(Value::Moment(moment))
+}
+
+fn plural(count: i64, unit: &str) -> String {
+    if count.abs() == 1 {
+        format!("{} {}", count, unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
+
+fn trim_decimal(value: f64) -> String {
+    format!("{:.1}", value)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn relative_days(days: i64) -> String {
+    match days {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "yesterday".to_string(),
+        n if n > 0 => format!("in {}", plural(n, "day")),
+        n => format!("{} ago", plural(-n, "day")),
+    }
+}
+
+fn describe_moment(moment: &Moment) -> String {
+    if moment.has_time {
+        moment.at.format("%a, %-d %b %Y %H:%M %Z").to_string()
+    } else {
+        moment.at.format("%a, %-d %b %Y").to_string()
+    }
+}
+
+fn format_moment(moment: &Moment, today: NaiveDate) -> (String, String) {
+    if moment.has_time {
+        (
+            moment.at.format("%a, %-d %b %Y %H:%M %Z").to_string(),
+            moment.at.to_rfc3339_opts(SecondsFormat::Secs, false),
+        )
+    } else {
+        let date = moment.at.date_naive();
+        let days = (date - today).num_days();
+        (
+            format!("{} ({})", date.format("%a, %-d %b %Y"), relative_days(days)),
+            date.format("%Y-%m-%d").to_string(),
+        )
+    }
+}
+
+/// `2 days 3 hours 15 minutes` plus the ISO 8601 duration (`P2DT3H15M`).
+fn format_duration(delta: Duration) -> (String, String) {
+    let sign = if delta < Duration::zero() { "-" } else { "" };
+    let total = delta.num_seconds().unsigned_abs();
+    let (days, hours, minutes, seconds) = (
+        total / 86_400,
+        total % 86_400 / 3_600,
+        total % 3_600 / 60,
+        total % 60,
+    );
+    let mut words = Vec::new();
+    let mut iso = format!("{}P", sign);
+    if days > 0 {
+        words.push(plural(days as i64, "day"));
+        iso.push_str(&format!("{}D", days));
+    }
+    if hours + minutes + seconds > 0 {
+        iso.push('T');
+    }
+    for (amount, unit, suffix) in [
+        (hours, "hour", 'H'),
+        (minutes, "minute", 'M'),
+        (seconds, "second", 'S'),
+    ] {
+        if amount > 0 {
+            words.push(plural(amount as i64, unit));
+            iso.push_str(&format!("{}{}", amount, suffix));
+        }
+    }
+    if words.is_empty() {
+        return ("0 days".to_string(), "P0D".to_string());
+    }
+    (format!("{}{}", sign, words.join(" ")), iso)
+}
+
+fn format_difference(a: Moment, b: Moment) -> (String, String) {
+    if a.has_time || b.has_time {
+        return format_duration(a.at - b.at);
+    }
+    let days = (a.at.date_naive() - b.at.date_naive()).num_days();
+    let mut display = plural(days, "day");
+    if days.abs() >= 7 {
+        let weeks = days.abs() / 7;
+        let rest = days.abs() % 7;
+        let breakdown = if rest == 0 {
+            plural(weeks, "week")
+        } else {
+            format!("{} {}", plural(weeks, "week"), plural(rest, "day"))
+        };
+        display = format!("{} ({})", display, breakdown);
+    }
+    (display, format!("P{}D", days).replacen("P-", "-P", 1))
+}
+
+fn evaluate_unix(raw: &str, local: Tz) -> Option<(String, String)> {
+    let caps = UNIX_RE.captures(raw)?;
+    let value = caps.get(1)?.as_str().parse::<f64>().ok()?;
+    // Thirteen-digit values are milliseconds (JavaScript, Java, most logs).
+    let millis = if value.abs() >= 1e11 {
+        value
+    } else {
+        value * 1_000.0
+    };
+    let at = Utc.timestamp_millis_opt(millis.round() as i64).single()?;
+    let local_at = at.with_timezone(&local);
+    let display = format!(
+        "{} ({} UTC)",
+        local_at.format("%a, %-d %b %Y %H:%M:%S %Z"),
+        at.format("%H:%M:%S")
+    );
+    Some((display, at.to_rfc3339_opts(SecondsFormat::Secs, true)))
+}
+
+fn evaluate_to_unix(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<(String, String)> {
+    let caps = TO_UNIX_RE.captures(raw)?;
+    let target = caps.get(1).or_else(|| caps.get(2))?.as_str();
+    let Value::Moment(moment) = evaluate_expression(target, local, now)? else {
+        return None;
+    };
+    let timestamp = moment.at.timestamp();
+    let display = format!(
+        "{} ({})",
+        timestamp,
+        moment.at.format("%a, %-d %b %Y %H:%M %Z")
+    );
+    Some((display, timestamp.to_string()))
+}
+
+fn evaluate_week_number(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<(String, String)> {
+    let caps = WEEK_NUMBER_RE.captures(raw)?;
+    let date = match caps.get(1) {
+        Some(target) => match evaluate_expression(target.as_str(), local, now)? {
+            Value::Moment(moment) => moment.at.date_naive(),
+            Value::Difference(..) => return None,
+        },
+        None => now.with_timezone(&local).date_naive(),
+    };
+    let week = date.iso_week();
+    let monday = NaiveDate::from_isoywd_opt(week.year(), week.week(), Weekday::Mon)?;
+    let sunday = monday + Days::new(6);
+    let display = format!(
+        "Week {} of {} ({} – {})",
+        week.week(),
+        week.year(),
+        monday.format("%a %-d %b"),
+        sunday.format("%a %-d %b")
+    );
+    Some((display, format!("{}-W{:02}", week.year(), week.week())))
+}
+
+fn evaluate_count(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<(String, String)> {
+    let caps = COUNT_RE.captures(raw)?;
+    let unit = caps.get(1)?.as_str();
+    let since = matches!(caps.get(2)?.as_str(), "since" | "from");
+    let Value::Moment(target) = evaluate_expression(caps.get(3)?.as_str(), local, now)? else {
+        return None;
+    };
+    let today = now.with_timezone(&local).date_naive();
+    let seconds = if target.has_time {
+        target.at.signed_duration_since(now).num_seconds() as f64
+    } else {
+        (target.at.date_naive() - today).num_days() as f64 * 86_400.0
+    };
+    let seconds = if since { -seconds } else { seconds };
+    let amount = match unit {
+        "days" => seconds / 86_400.0,
+        "weeks" => seconds / 604_800.0,
+        "hours" => seconds / 3_600.0,
+        _ => seconds / 60.0,
+    };
+    let amount = trim_decimal(amount);
+    let when = describe_moment(&target);
+    let joiner = if since { "since" } else { "until" };
+    let label = if amount == "1" {
+        unit.trim_end_matches('s')
+    } else {
+        unit
+    };
+    Some((format!("{} {} {} {}", amount, label, joiner, when), amount))
+}
+
+/// `3 days ago`, `2 weeks from now`, `90 min from 2026-01-01 09:00`.
+fn evaluate_relative(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<Value> {
+    let caps = RELATIVE_RE.captures(raw)?;
+    let spans = parse_spans(caps.get(1)?.as_str())?;
+    let ago = caps.get(3).is_none();
+    let base = match caps.get(3) {
+        Some(base) => match evaluate_expression(base.as_str(), local, now)? {
+            Value::Moment(moment) => moment,
+            Value::Difference(..) => return None,
+        },
+        None if spans.iter().all(|span| span.is_calendar()) => parse_moment("today", local, now)?,
+        None => parse_moment("now", local, now)?,
+    };
+    let mut moment = base;
+    for span in spans {
+        moment = shift(moment, span, ago)?;
+    }
+    Some(Value::Moment(moment))
+}
+
+/// `<moment> in <zone>`: the same instant on another zone's wall clock.
+fn evaluate_zone_conversion(raw: &str, local: Tz, now: DateTime<Utc>) -> Option<(String, String)> {
+    let split = [" in ", " to "]
+        .iter()
+        .filter_map(|sep| raw.rfind(sep).map(|at| (at, sep.len())))
+        .max_by_key(|(at, _)| *at)?;
+    let (source, target) = (&raw[..split.0], &raw[split.0 + split.1..]);
+    let tz = resolve_timezone(target)?;
+    let Value::Moment(moment) = evaluate_expression(source, local, now)? else {
+        return None;
+    };
+    let converted = moment.at.with_timezone(&tz);
+    let display = format!(
+        "{} ({})",
+        converted.format("%a, %-d %b %Y %H:%M %Z"),
+        tz.name()
+    );
+    Some((
+        display,
+        converted.to_rfc3339_opts(SecondsFormat::Secs, false),
+    ))
+}
+
+/// Evaluates `query` against the system clock and local time zone.
+pub fn evaluate_display(query: &str) -> Option<(String, String)> {
+    evaluate_at(query, Utc::now(), *LOCAL_TZ)
+}
+
+/// Evaluates `query` as if it were `now` in the `local` zone.
+pub fn evaluate_at(query: &str, now: DateTime<Utc>, local: Tz) -> Option<(String, String)> {
+    let raw = query.trim().to_ascii_lowercase();
+    let raw = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if raw.is_empty() {
+        return None;
+    }
+
+    if let Some(out) = evaluate_unix(&raw, local) {
+        return Some(out);
+    }
+    if let Some(out) = evaluate_to_unix(&raw, local, now) {
+        return Some(out);
+    }
+    if let Some(out) = evaluate_week_number(&raw, local, now) {
+        return Some(out);
+    }
+    if let Some(out) = evaluate_count(&raw, local, now) {
+        return Some(out);
+    }
+    if let Some(out) = evaluate_zone_conversion(&raw, local, now) {
+        return Some(out);
+    }
+
+    let today = now.with_timezone(&local).date_naive();
+    let value = match evaluate_relative(&raw, local, now) {
+        Some(value) => value,
+        None => {
+            let value = evaluate_expression(&raw, local, now)?;
+            // A lone word like `today` or `friday` is more likely a search than a question.
+            let bare = !OPERATOR_RE.is_match(&raw);
+            let explicit = raw.chars().any(|c| c.is_ascii_digit())
+                || ["next ", "last ", "this "]
+                    .iter()
+                    .any(|p| raw.starts_with(p));
+            if bare && !explicit {
+                return None;
+            }
+            value
+        }
+    };
+    match value {
+        Value::Moment(moment) => Some(format_moment(&moment, today)),
+        Value::Difference(a, b) => Some(format_difference(a, b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_at, resolve_timezone};
+    use chrono::{DateTime, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    // Sunday 18 October 2026, 10:30 in Berlin (CEST, UTC+2).
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 18, 8, 30, 0).unwrap()
+    }
+
+    fn eval(query: &str) -> (String, String) {
+        evaluate_at(query, now(), Tz::Europe__Berlin).expect(query)
+    }
+
+    #[test]
+    fn adds_and_subtracts_calendar_spans() {
+        assert_eq!(eval("now + 3 weeks").1, "2026-11-08T10:30:00+01:00");
+        assert_eq!(eval("today - 1 month").1, "2026-09-18");
+        assert_eq!(eval("2026-01-31 + 1 month").1, "2026-02-28");
+        assert_eq!(eval("next friday").1, "2026-10-23");
+        assert_eq!(eval("last sunday").1, "2026-10-11");
+        assert_eq!(eval("3 days ago").1, "2026-10-15");
+        assert!(eval("next friday").0.contains("in 5 days"));
+    }
+
+    #[test]
+    fn measures_differences_and_countdowns() {
+        let (display, iso) = eval("2026-12-24 - today");
+        assert_eq!(iso, "P67D");
+        assert!(display.starts_with("67 days (9 weeks 4 days)"));
+        assert_eq!(eval("tomorrow 9am - now").1, "PT22H30M");
+        assert_eq!(eval("days until christmas").1, "68");
+        assert_eq!(eval("days since 2026-10-01").1, "17");
+    }
+
+    #[test]
+    fn converts_unix_timestamps_and_zones() {
+        assert_eq!(eval("unix 1700000000").1, "2023-11-14T22:13:20Z");
+        assert_eq!(eval("unix 1700000000000").1, "2023-11-14T22:13:20Z");
+        assert_eq!(eval("to unix 2026-01-01 09:00").1, "1767254400");
+        let (display, iso) = eval("3pm PST in Tokyo");
+        assert_eq!(iso, "2026-10-19T07:00:00+09:00");
+        assert!(display.contains("Asia/Tokyo"));
+        assert_eq!(eval("week number").1, "2026-W42");
+        assert_eq!(eval("week number 2027-01-01").1, "2026-W53");
+    }
+
+    #[test]
+    fn huge_spans_fail_instead_of_panicking() {
+        for query in [
+            "now + 99999999999999 days",
+            "99999999999999 days ago",
+            "today + 9999999999999999999 weeks",
+            "today + 999999999999999999 years",
+            "now + 99999999999999999999 hours",
+            "now - 1e300 seconds",
+        ] {
+            assert!(
+                evaluate_at(query, now(), Tz::Europe__Berlin).is_none(),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn leaves_plain_words_and_arithmetic_alone() {
+        for query in [
+            "today",
+            "friday",
+            "christmas",
+            "12 - 4",
+            "10 km in mi",
+            "firefox",
+        ] {
+            assert!(
+                evaluate_at(query, now(), Tz::Europe__Berlin).is_none(),
+                "{}",
+                query
+            );
+        }
+        assert_eq!(
+            resolve_timezone("pst").map(|tz| tz.name()),
+            Some("America/Los_Angeles")
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds (ms) of the latency buckets. Anything slower lands in the overflow bucket.
+const BUCKET_BOUNDS_MS: [u64; 14] = [1, 2, 4, 8, 16, 25, 50, 75, 100, 150, 250, 500, 1000, 2500];
+const SLOW_QUERY_CAPACITY: usize = 25;
+const SLOW_QUERY_THRESHOLD_MS: u64 = 75;
+
+pub const SOURCE_APPS: &str = "apps";
+pub const SOURCE_WINDOWS: &str = "windows";
+pub const SOURCE_FILES: &str = "files";
+pub const SOURCE_CLIPBOARD: &str = "clipboard";
+pub const SOURCE_EXTENSIONS: &str = "extensions";
+pub const SOURCE_CALCULATOR: &str = "calculator";
+pub const SOURCE_WEB: &str = "web";
+pub const SOURCE_RECENT: &str = "recent";
+pub const SOURCE_SSH: &str = "ssh";
+pub const SOURCE_SYSTEMD: &str = "systemd";
+pub const SOURCE_REPOS: &str = "repos";
+pub const SOURCE_EMOJI: &str = "emoji";
+
+#[derive(Clone, Debug, Default)]
+struct LatencyHistogram {
+    counts: [u64; BUCKET_BOUNDS_MS.len() + 1],
+    total: u64,
+    sum_ms: f64,
+    max_ms: f64,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let idx = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| ms <= *bound as f64)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[idx] += 1;
+        self.total += 1;
+        self.sum_ms += ms;
+        if ms > self.max_ms {
+            self.max_ms = ms;
+        }
+    }
+
+    /// Estimate a percentile from bucket counts. Returns the bucket's upper bound,
+    /// capped at the slowest observed sample so small samples don't over-report.
+    fn percentile(&self, pct: f64) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let rank = ((pct / 100.0) * self.total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0u64;
+        for (idx, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let bound = BUCKET_BOUNDS_MS
+                    .get(idx)
+                    .map(|b| *b as f64)
+                    .unwrap_or(self.max_ms);
+                return bound.min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+
+    fn summary(&self, name: &str) -> LatencySummary {
+        let buckets = BUCKET_BOUNDS_MS
+            .iter()
+            .map(|b| format!("<={}ms", b))
+            .chain(std::iter::once(format!(
+                ">{}ms",
+                BUCKET_BOUNDS_MS[BUCKET_BOUNDS_MS.len() - 1]
+            )))
+            .zip(self.counts.iter())
+            .filter(|(_, count)| **count > 0)
+            .map(|(label, count)| LatencyBucket {
+                le: label,
+                count: *count,
+            })
+            .collect();
+
+        LatencySummary {
+            name: name.to_string(),
+            count: self.total,
+            avg_ms: if self.total > 0 {
+                self.sum_ms / self.total as f64
+            } else {
+                0.0
+            },
+            p50_ms: self.percentile(50.0),
+            p95_ms: self.percentile(95.0),
+            p99_ms: self.percentile(99.0),
+            max_ms: self.max_ms,
+            buckets,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LatencyBucket {
+    pub le: String,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LatencySummary {
+    pub name: String,
+    pub count: u64,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceTiming {
+    pub source: String,
+    pub ms: f64,
+    pub results: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SlowQueryRecord {
+    /// Length of the query in characters. The text itself is never kept, since
+    /// these records end up in support bundles.
+    pub query_chars: usize,
+    pub total_ms: f64,
+    pub timestamp: i64,
+    pub sources: Vec<SourceTiming>,
+}
+
+#[derive(Default)]
+struct DiagnosticsStore {
+    operations: BTreeMap<String, LatencyHistogram>,
+    sources: BTreeMap<String, LatencyHistogram>,
+    slow_queries: VecDeque<SlowQueryRecord>,
+}
+
+static STORE: OnceLock<Mutex<DiagnosticsStore>> = OnceLock::new();
+
+fn store() -> &'static Mutex<DiagnosticsStore> {
+    STORE.get_or_init(|| Mutex::new(DiagnosticsStore::default()))
+}
+
+fn duration_ms(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Record one sample for a top-level operation (`search`, `suggestions`, `launch`).
+pub fn record_operation(name: &str, elapsed: Duration) {
+    if let Ok(mut guard) = store().lock() {
+        guard
+            .operations
+            .entry(name.to_string())
+            .or_default()
+            .record(elapsed);
+    }
+}
+
+/// Record the per-source timings for one search. Each source feeds its own histogram;
+/// queries slower than the threshold are pushed into the slow-query ring buffer.
+pub fn record_search(query: &str, total: Duration, sources: &[(&str, Duration, usize)]) {
+    let Ok(mut guard) = store().lock() else {
+        return;
+    };
+
+    for (source, elapsed, _) in sources {
+        guard
+            .sources
+            .entry((*source).to_string())
+            .or_default()
+            .record(*elapsed);
+    }
+
+    let total_ms = duration_ms(total);
+    if total_ms < SLOW_QUERY_THRESHOLD_MS as f64 {
+        return;
+    }
+
+    if guard.slow_queries.len() >= SLOW_QUERY_CAPACITY {
+        guard.slow_queries.pop_front();
+    }
+    guard.slow_queries.push_back(SlowQueryRecord {
+        query_chars: query.trim().chars().count(),
+        total_ms,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        sources: sources
+            .iter()
+            .map(|(source, elapsed, results)| SourceTiming {
+                source: (*source).to_string(),
+                ms: duration_ms(*elapsed),
+                results: *results,
+            })
+            .collect(),
+    });
+}
+
+pub fn operation_summaries() -> Vec<LatencySummary> {
+    store()
+        .lock()
+        .map(|guard| {
+            guard
+                .operations
+                .iter()
+                .map(|(name, hist)| hist.summary(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn source_summaries() -> Vec<LatencySummary> {
+    store()
+        .lock()
+        .map(|guard| {
+            guard
+                .sources
+                .iter()
+                .map(|(name, hist)| hist.summary(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Slow queries currently held in the ring buffer, slowest first.
+pub fn slow_queries() -> Vec<SlowQueryRecord> {
+    let mut records: Vec<SlowQueryRecord> = store()
+        .lock()
+        .map(|guard| guard.slow_queries.iter().cloned().collect())
+        .unwrap_or_default();
+    records.sort_by(|a, b| {
+        b.total_ms
+            .partial_cmp(&a.total_ms)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_percentiles_follow_bucket_bounds() {
+        let mut hist = LatencyHistogram::default();
+        for _ in 0..90 {
+            hist.record(Duration::from_millis(3));
+        }
+        for _ in 0..9 {
+            hist.record(Duration::from_millis(40));
+        }
+        hist.record(Duration::from_millis(700));
+
+        assert_eq!(hist.percentile(50.0), 4.0);
+        assert_eq!(hist.percentile(95.0), 50.0);
+        assert_eq!(hist.percentile(99.0), 50.0);
+        assert_eq!(hist.percentile(100.0), 700.0);
+    }
+
+    #[test]
+    fn histogram_percentile_is_capped_by_max_sample() {
+        let mut hist = LatencyHistogram::default();
+        hist.record(Duration::from_micros(5_500));
+        assert!((hist.percentile(50.0) - 5.5).abs() < 0.01);
+        assert_eq!(LatencyHistogram::default().percentile(99.0), 0.0);
+    }
+
+    #[test]
+    fn summary_only_lists_populated_buckets() {
+        let mut hist = LatencyHistogram::default();
+        hist.record(Duration::from_millis(1));
+        hist.record(Duration::from_millis(5_000));
+        let summary = hist.summary("search");
+        assert_eq!(summary.count, 2);
+        let labels: Vec<&str> = summary.buckets.iter().map(|b| b.le.as_str()).collect();
+        assert_eq!(labels, vec!["<=1ms", ">2500ms"]);
+    }
+
+    #[test]
+    fn slow_query_buffer_is_bounded_and_sorted() {
+        for i in 0..(SLOW_QUERY_CAPACITY + 5) {
+            record_search(
+                &format!("query-{}", i),
+                Duration::from_millis(SLOW_QUERY_THRESHOLD_MS + i as u64),
+                &[(SOURCE_FILES, Duration::from_millis(60), 3)],
+            );
+        }
+        record_search("fast", Duration::from_millis(1), &[]);
+
+        let records = slow_queries();
+        assert!(records.len() <= SLOW_QUERY_CAPACITY);
+        assert!(records.iter().all(|r| r.total_ms >= SLOW_QUERY_THRESHOLD_MS as f64));
+        assert!(records.iter().all(|r| (7..=8).contains(&r.query_chars)));
+        assert!(records.windows(2).all(|w| w[0].total_ms >= w[1].total_ms));
+        assert!(source_summaries().iter().any(|s| s.name == SOURCE_FILES));
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use emojis::SkinTone;
+
+use crate::matcher::{ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+/// Exec prefix that copies a character and records it in `History`.
+pub const EXEC_COPY_PREFIX: &str = "emoji:";
+/// Exec prefix that types a character into the previously focused window.
+pub const EXEC_TYPE_PREFIX: &str = "emoji-type:";
+
+const SKIN_TONES: [(SkinTone, &str); 5] = [
+    (SkinTone::Light, "Light"),
+    (SkinTone::MediumLight, "Medium-Light"),
+    (SkinTone::Medium, "Medium"),
+    (SkinTone::MediumDark, "Medium-Dark"),
+    (SkinTone::Dark, "Dark"),
+];
+
+/// CLDR short names and keywords, one `emoji<TAB>short name<TAB>keyword | keyword`
+/// line per character, written by `scripts/gen-emoji-annotations.mjs`.
+const ANNOTATIONS_TSV: &str = include_str!("emoji_annotations.tsv");
+
+/// Search keywords by character with U+FE0F dropped, as CLDR keys it.
+static ANNOTATIONS: LazyLock<HashMap<String, Vec<&'static str>>> = LazyLock::new(|| {
+    ANNOTATIONS_TSV
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let (value, short_name) = (fields.next()?, fields.next()?);
+            let mut keywords = vec![short_name];
+            keywords.extend(fields.next().unwrap_or("").split(" | ").filter(|k| !k.is_empty()));
+            Some((value.to_string(), keywords))
+        })
+        .collect()
+});
+
+fn annotation_keywords(value: &str) -> Vec<&'static str> {
+    ANNOTATIONS
+        .get(&value.replace('\u{fe0f}', ""))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Non-emoji Unicode characters: (character, name, keywords).
+const SYMBOLS: &[(&str, &str, &[&str])] = &[
+    ("→", "rightwards arrow", &["arrow", "right"]),
+    ("←", "leftwards arrow", &["arrow", "left"]),
+    ("↑", "upwards arrow", &["arrow", "up"]),
+    ("↓", "downwards arrow", &["arrow", "down"]),
+    ("↔", "left right arrow", &["arrow", "both"]),
+    ("↕", "up down arrow", &["arrow", "vertical"]),
+    ("⇒", "rightwards double arrow", &["arrow", "right", "implies"]),
+    ("⇐", "leftwards double arrow", &["arrow", "left"]),
+    ("⇔", "left right double arrow", &["arrow", "iff", "equivalent"]),
+    ("↩", "leftwards arrow with hook", &["arrow", "return", "back"]),
+    ("↪", "rightwards arrow with hook", &["arrow", "redirect"]),
+    ("⟶", "long rightwards arrow", &["arrow", "right"]),
+    ("↗", "north east arrow", &["arrow", "up", "right"]),
+    ("↘", "south east arrow", &["arrow", "down", "right"]),
+    ("⏎", "return symbol", &["enter", "key"]),
+    ("⌘", "place of interest sign", &["command", "cmd", "mac", "key"]),
+    ("⌥", "option key", &["alt", "mac", "key"]),
+    ("⇧", "upwards white arrow", &["shift", "key"]),
+    ("⌃", "up arrowhead", &["control", "ctrl", "key"]),
+    ("⌫", "erase to the left", &["backspace", "delete", "key"]),
+    ("⎋", "broken circle with northwest arrow", &["escape", "esc", "key"]),
+    ("⇥", "rightwards arrow to bar", &["tab", "key"]),
+    ("×", "multiplication sign", &["times", "multiply", "math"]),
+    ("÷", "division sign", &["divide", "math"]),
+    ("±", "plus minus sign", &["math", "tolerance"]),
+    ("∓", "minus or plus sign", &["math"]),
+    ("−", "minus sign", &["math", "subtract"]),
+    ("≈", "almost equal to", &["approx", "math"]),
+    ("≠", "not equal to", &["math", "neq"]),
+    ("≤", "less than or equal to", &["math", "leq"]),
+    ("≥", "greater than or equal to", &["math", "geq"]),
+    ("≡", "identical to", &["math", "equivalent"]),
+    ("∞", "infinity", &["math", "forever"]),
+    ("√", "square root", &["math", "sqrt"]),
+    ("∑", "n-ary summation", &["sum", "sigma", "math"]),
+    ("∏", "n-ary product", &["product", "math"]),
+    ("∫", "integral", &["math", "calculus"]),
+    ("∂", "partial differential", &["math", "derivative"]),
+    ("∆", "increment", &["delta", "change", "math"]),
+    ("∇", "nabla", &["del", "gradient", "math"]),
+    ("∈", "element of", &["math", "set", "in"]),
+    ("∉", "not an element of", &["math", "set"]),
+    ("∅", "empty set", &["math", "set", "null"]),
+    ("∩", "intersection", &["math", "set"]),
+    ("∪", "union", &["math", "set"]),
+    ("⊂", "subset of", &["math", "set"]),
+    ("∀", "for all", &["math", "logic", "forall"]),
+    ("∃", "there exists", &["math", "logic", "exists"]),
+    ("¬", "not sign", &["logic", "negation"]),
+    ("∧", "logical and", &["logic", "and"]),
+    ("∨", "logical or", &["logic", "or"]),
+    ("°", "degree sign", &["degrees", "temperature", "angle"]),
+    ("‰", "per mille sign", &["permille", "thousand"]),
+    ("½", "vulgar fraction one half", &["half", "fraction"]),
+    ("¼", "vulgar fraction one quarter", &["quarter", "fraction"]),
+    ("¾", "vulgar fraction three quarters", &["fraction"]),
+    ("²", "superscript two", &["squared", "power"]),
+    ("³", "superscript three", &["cubed", "power"]),
+    ("µ", "micro sign", &["micro", "mu"]),
+    ("π", "greek small letter pi", &["pi", "math", "greek"]),
+    ("α", "greek small letter alpha", &["alpha", "greek"]),
+    ("β", "greek small letter beta", &["beta", "greek"]),
+    ("γ", "greek small letter gamma", &["gamma", "greek"]),
+    ("δ", "greek small letter delta", &["delta", "greek"]),
+    ("ε", "greek small letter epsilon", &["epsilon", "greek"]),
+    ("θ", "greek small letter theta", &["theta", "greek", "angle"]),
+    ("λ", "greek small letter lambda", &["lambda", "greek"]),
+    ("σ", "greek small letter sigma", &["sigma", "greek"]),
+    ("φ", "greek small letter phi", &["phi", "greek"]),
+    ("ω", "greek small letter omega", &["omega", "greek"]),
+    ("Ω", "greek capital letter omega", &["ohm", "omega", "greek"]),
+    ("€", "euro sign", &["currency", "money", "eur"]),
+    ("£", "pound sign", &["currency", "money", "gbp"]),
+    ("¥", "yen sign", &["currency", "money", "jpy", "yuan"]),
+    ("₹", "indian rupee sign", &["currency", "money", "inr"]),
+    ("₽", "ruble sign", &["currency", "money", "rub"]),
+    ("₩", "won sign", &["currency", "money", "krw"]),
+    ("₿", "bitcoin sign", &["currency", "crypto", "btc"]),
+    ("¢", "cent sign", &["currency", "money"]),
+    ("—", "em dash", &["dash", "punctuation"]),
+    ("–", "en dash", &["dash", "range", "punctuation"]),
+    ("…", "horizontal ellipsis", &["dots", "ellipsis", "punctuation"]),
+    ("•", "bullet", &["dot", "list"]),
+    ("·", "middle dot", &["dot", "interpunct"]),
+    ("“", "left double quotation mark", &["quote", "smart"]),
+    ("”", "right double quotation mark", &["quote", "smart"]),
+    ("‘", "left single quotation mark", &["quote", "apostrophe"]),
+    ("’", "right single quotation mark", &["quote", "apostrophe"]),
+    ("«", "left pointing double angle quotation mark", &["guillemet", "quote"]),
+    ("»", "right pointing double angle quotation mark", &["guillemet", "quote"]),
+    ("§", "section sign", &["section", "legal", "paragraph"]),
+    ("¶", "pilcrow sign", &["paragraph"]),
+    ("†", "dagger", &["footnote"]),
+    ("©", "copyright sign", &["copyright", "legal"]),
+    ("®", "registered sign", &["registered", "trademark"]),
+    ("™", "trade mark sign", &["trademark", "tm"]),
+    ("✓", "check mark", &["tick", "done", "yes"]),
+    ("✗", "ballot x", &["cross", "no", "wrong"]),
+    ("★", "black star", &["star", "favorite", "rating"]),
+    ("☆", "white star", &["star", "outline", "rating"]),
+    ("♥", "black heart suit", &["heart", "love", "card"]),
+    ("♪", "eighth note", &["music", "note"]),
+    ("☐", "ballot box", &["checkbox", "todo"]),
+    ("☑", "ballot box with check", &["checkbox", "done"]),
+    ("█", "full block", &["block", "box"]),
+    ("░", "light shade", &["shade", "block"]),
+    ("─", "box drawings light horizontal", &["line", "box"]),
+    ("│", "box drawings light vertical", &["line", "box"]),
+    ("¿", "inverted question mark", &["question", "spanish"]),
+    ("¡", "inverted exclamation mark", &["exclamation", "spanish"]),
+    ("\u{00a0}", "no-break space", &["nbsp", "space", "whitespace"]),
+    ("\u{200b}", "zero width space", &["zwsp", "space", "invisible"]),
+];
+
+#[derive(Debug, Clone)]
+pub struct CharEntry {
+    pub value: &'static str,
+    pub name: &'static str,
+    pub shortcodes: Vec<&'static str>,
+    pub keywords: Vec<&'static str>,
+    pub group: &'static str,
+    pub emoji: Option<&'static emojis::Emoji>,
+}
+
+fn group_label(group: emojis::Group) -> &'static str {
+    match group {
+        emojis::Group::SmileysAndEmotion => "Smileys & Emotion",
+        emojis::Group::PeopleAndBody => "People & Body",
+        emojis::Group::AnimalsAndNature => "Animals & Nature",
+        emojis::Group::FoodAndDrink => "Food & Drink",
+        emojis::Group::TravelAndPlaces => "Travel & Places",
+        emojis::Group::Activities => "Activities",
+        emojis::Group::Objects => "Objects",
+        emojis::Group::Symbols => "Symbols",
+        emojis::Group::Flags => "Flags",
+    }
+}
+
+static CATALOG: LazyLock<Vec<CharEntry>> = LazyLock::new(|| {
+    let mut out: Vec<CharEntry> = emojis::iter()
+        .map(|e| CharEntry {
+            value: e.as_str(),
+            name: e.name(),
+            shortcodes: e.shortcodes().collect(),
+            keywords: annotation_keywords(e.as_str()),
+            group: group_label(e.group()),
+            emoji: Some(e),
+        })
+        .collect();
+    out.extend(SYMBOLS.iter().map(|(value, name, kw)| CharEntry {
+        value,
+        name,
+        shortcodes: Vec::new(),
+        keywords: kw.iter().copied().chain(annotation_keywords(value)).collect(),
+        group: "Unicode Symbols",
+        emoji: None,
+    }));
+    out
+});
+
+/// Extract the emoji search text from `:grin`, `emoji: arrow right` or `emoji grin`.
+/// Returns `None` when the query isn't aimed at the picker.
+pub fn parse_emoji_query(query: &str) -> Option<&str> {
+    let trimmed = query.trim_start();
+    if let Some(rest) = trimmed.strip_prefix(':') {
+        // `:` alone lists recents; `: foo` (space after colon) is not a shortcode.
+        if rest.is_empty() || !rest.starts_with(char::is_whitespace) {
+            return Some(rest.trim().trim_end_matches(':'));
+        }
+        return None;
+    }
+    let lower = trimmed.get(..6).map(|p| p.to_ascii_lowercase());
+    match lower.as_deref() {
+        Some("emoji:") => Some(trimmed[6..].trim()),
+        Some("emoji ") => Some(trimmed[6..].trim()),
+        _ if trimmed.eq_ignore_ascii_case("emoji") => Some(""),
+        _ => None,
+    }
+}
+
+fn words(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-' || c == ':' || c == ',')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+}
+
+/// Base score for one entry, or `None` if not every query token matches.
+fn entry_score(entry: &CharEntry, needle: &str, tokens: &[String]) -> Option<u32> {
+    if entry.shortcodes.contains(&needle) {
+        return Some(ranking_config::EMOJI_SHORTCODE_EXACT);
+    }
+    if !needle.contains(' ') && entry.shortcodes.iter().any(|s| s.starts_with(needle)) {
+        return Some(ranking_config::EMOJI_SHORTCODE_PREFIX);
+    }
+
+    let name_words: Vec<String> = words(entry.name).collect();
+    let extra_words: Vec<String> = entry
+        .shortcodes
+        .iter()
+        .chain(entry.keywords.iter())
+        .flat_map(|s| words(s))
+        .collect();
+
+    let mut in_name = 0u32;
+    for token in tokens {
+        if name_words.iter().any(|w| w.starts_with(token.as_str())) {
+            in_name += 1;
+        } else if !extra_words.iter().any(|w| w.starts_with(token.as_str())) {
+            return None;
+        }
+    }
+
+    let mut score = ranking_config::EMOJI_WORD_MATCH
+        + in_name * ranking_config::EMOJI_NAME_WORD_BONUS;
+    if entry.name == needle {
+        score += ranking_config::EMOJI_NAME_EXACT_BONUS;
+    }
+    // Prefer "grinning face" over "grinning face with sweat" for the same tokens.
+    let extra = (name_words.len() as u32).saturating_sub(tokens.len() as u32);
+    Some(score.saturating_sub(extra * ranking_config::EMOJI_EXTRA_WORD_PENALTY))
+}
+
+fn title_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn entry_result(entry: &CharEntry, score: u32) -> SearchResult {
+    let mut subtitle = entry.group.to_string();
+    if let Some(code) = entry.shortcodes.first() {
+        subtitle = format!(":{}: · {}", code, subtitle);
+    } else if entry.emoji.is_none() {
+        let codepoints: Vec<String> = entry
+            .value
+            .chars()
+            .map(|c| format!("U+{:04X}", c as u32))
+            .collect();
+        subtitle = format!("{} · {}", codepoints.join(" "), subtitle);
+    }
+
+    let mut actions = vec![ActionHint {
+        label: "Type into Window".to_string(),
+        exec: format!("{}{}", EXEC_TYPE_PREFIX, entry.value),
+        shortcut: Some("Shift+Enter".to_string()),
+    }];
+    if let Some(emoji) = entry.emoji {
+        for (tone, label) in SKIN_TONES {
+            if let Some(variant) = emoji.with_skin_tone(tone) {
+                actions.push(ActionHint {
+                    label: format!("Copy {} ({} Skin Tone)", variant.as_str(), label),
+                    exec: format!("{}{}", EXEC_COPY_PREFIX, variant.as_str()),
+                    shortcut: None,
+                });
+            }
+        }
+    }
+
+    SearchResult {
+        title: title_case(entry.name),
+        subtitle: Some(subtitle),
+        icon: Some(entry.value.to_string()),
+        exec: format!("{}{}", EXEC_COPY_PREFIX, entry.value),
+        score,
+        match_indices: vec![],
+        source: ResultSource::Emoji,
+        actions: Some(actions),
+        id: Some(format!("emoji:{}", entry.value)),
+        group: Some(entry.group.to_string()),
+        section: Some("Emoji & Symbols".to_string()),
+    }
+}
+
+fn usage_bonus(usage: &HashMap<String, u32>, value: &str) -> u32 {
+    usage
+        .get(&format!("{}{}", EXEC_COPY_PREFIX, value))
+        .map(|u| (u / ranking_config::EMOJI_USAGE_DIVISOR).min(ranking_config::EMOJI_USAGE_CAP))
+        .unwrap_or(0)
+}
+
+/// Recently used characters, most frecent first. Skin-tone variants are looked up
+/// directly so a picked 👍🏽 shows up as itself.
+fn recent_results(usage: &HashMap<String, u32>, limit: usize, weight: u32) -> Vec<SearchResult> {
+    let mut used: Vec<(&str, u32)> = usage
+        .iter()
+        .filter_map(|(exec, score)| Some((exec.strip_prefix(EXEC_COPY_PREFIX)?, *score)))
+        .filter(|(_, score)| *score > 0)
+        .collect();
+    used.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    used.into_iter()
+        .filter_map(|(value, score)| {
+            let entry = CATALOG.iter().find(|e| e.value == value).cloned().or_else(|| {
+                let emoji = emojis::get(value)?;
+                Some(CharEntry {
+                    value: emoji.as_str(),
+                    name: emoji.name(),
+                    shortcodes: emoji.shortcodes().collect(),
+                    keywords: Vec::new(),
+                    group: group_label(emoji.group()),
+                    emoji: None,
+                })
+            })?;
+            let base = ranking_config::EMOJI_RECENT_BASE
+                .saturating_add(score.min(ranking_config::EMOJI_USAGE_CAP));
+            let mut result = entry_result(&entry, crate::weighted_score(base, weight));
+            result.section = Some("Recently Used".to_string());
+            Some(result)
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Emoji/symbol results for an already-extracted needle (see `parse_emoji_query`).
+/// An empty needle lists recently used characters.
+pub fn search_emoji(
+    needle: &str,
+    usage: &HashMap<String, u32>,
+    limit: usize,
+    weight: u32,
+) -> Vec<SearchResult> {
+    let needle = needle.trim().to_lowercase();
+    if needle.is_empty() {
+        return recent_results(usage, limit, weight);
+    }
+    let tokens: Vec<String> = words(&needle).collect();
+    if tokens.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(u32, &CharEntry)> = CATALOG
+        .iter()
+        .filter_map(|entry| {
+            let base = entry_score(entry, &needle, &tokens)?;
+            Some((base.saturating_add(usage_bonus(usage, entry.value)), entry))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.len().cmp(&b.1.name.len())));
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(base, entry)| entry_result(entry, crate::weighted_score(base, weight)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_picker_prefixes() {
+        assert_eq!(parse_emoji_query(":grin"), Some("grin"));
+        assert_eq!(parse_emoji_query(":thumbsup:"), Some("thumbsup"));
+        assert_eq!(parse_emoji_query(":"), Some(""));
+        assert_eq!(parse_emoji_query("emoji: arrow right"), Some("arrow right"));
+        assert_eq!(parse_emoji_query("Emoji heart"), Some("heart"));
+        assert_eq!(parse_emoji_query(": not a shortcode"), None);
+        assert_eq!(parse_emoji_query("emojify"), None);
+        assert_eq!(parse_emoji_query("firefox"), None);
+    }
+
+    #[test]
+    fn shortcode_prefix_ranks_first() {
+        let results = search_emoji("grin", &HashMap::new(), 5, 100);
+        assert!(!results.is_empty());
+        assert_eq!(results[0].icon.as_deref(), Some("😁"));
+        assert_eq!(results[0].exec, "emoji:😁");
+        assert!(matches!(results[0].source, ResultSource::Emoji));
+    }
+
+    #[test]
+    fn multi_word_queries_match_names_in_any_order() {
+        let results = search_emoji("arrow right", &HashMap::new(), 10, 100);
+        let values: Vec<&str> = results.iter().filter_map(|r| r.icon.as_deref()).collect();
+        assert!(values.contains(&"➡️"));
+        assert!(values.contains(&"→"));
+        assert!(search_emoji("zzzz qqqq", &HashMap::new(), 10, 100).is_empty());
+    }
+
+    #[test]
+    fn keywords_and_skin_tone_actions() {
+        let results = search_emoji("agreement", &HashMap::new(), 5, 100);
+        assert_eq!(results[0].icon.as_deref(), Some("🤝"));
+        assert!(annotation_keywords("❤️").contains(&"red heart"));
+
+        let results = search_emoji("thumbs up", &HashMap::new(), 5, 100);
+        assert_eq!(results[0].icon.as_deref(), Some("👍"));
+        let actions = results[0].actions.as_ref().unwrap();
+        assert_eq!(actions[0].exec, "emoji-type:👍");
+        assert_eq!(actions.len(), 1 + SKIN_TONES.len());
+        assert!(actions.iter().any(|a| a.exec == "emoji:👍🏽"));
+    }
+
+    #[test]
+    fn recents_come_from_usage_and_boost_matches() {
+        let mut usage = HashMap::new();
+        usage.insert("emoji:🎉".to_string(), 3_000);
+        usage.insert("emoji:👍🏽".to_string(), 1_000);
+        usage.insert("/usr/bin/firefox".to_string(), 9_000);
+
+        let recents = search_emoji("", &usage, 5, 100);
+        let values: Vec<&str> = recents.iter().filter_map(|r| r.icon.as_deref()).collect();
+        assert_eq!(values, vec!["🎉", "👍🏽"]);
+        assert_eq!(recents[0].section.as_deref(), Some("Recently Used"));
+
+        let plain = search_emoji("party", &HashMap::new(), 5, 100);
+        let boosted = search_emoji("party", &usage, 5, 100);
+        let score_of = |rs: &[SearchResult]| rs.iter().find(|r| r.exec == "emoji:🎉").map(|r| r.score);
+        assert!(score_of(&boosted) > score_of(&plain));
+    }
+}
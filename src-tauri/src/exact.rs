@@ -0,0 +1,504 @@
+//! Exact arithmetic for the calculator.
+//!
+//! Basic arithmetic runs on big rationals, so `0.1 + 0.2` is `0.3` and `30!` keeps
+//! every digit. Percentages follow the usual calculator rules: `15% of 80` is 12
+//! and `80 + 15%` is 92. Anything transcendental (`sqrt`, `sin`, `pi`, fractional
+//! powers) returns `None` and is left to the `f64` path in `math`.
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+
+use crate::config::{CalculatorConfig, RoundingMode};
+
+const MAX_FACTORIAL: u64 = 5_000;
+const MAX_EXPONENT: u64 = 10_000;
+/// Largest numerator or denominator a power may produce, in bits (about 315k digits).
+const MAX_POWER_BITS: u64 = 1 << 20;
+/// Integers longer than this are displayed in scientific notation; the copy keeps every digit.
+const MAX_DISPLAY_DIGITS: usize = 40;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(BigRational),
+    Ident(String),
+    Op(char),
+    Of,
+    Mod,
+    Open,
+    Close,
+}
+
+fn pow10(exponent: u32) -> BigInt {
+    Pow::pow(BigInt::from(10u32), exponent)
+}
+
+/// `1_000.25`, `.5`, `1.5e3` as an exact rational.
+fn parse_decimal(literal: &str) -> Option<BigRational> {
+    let cleaned = literal.replace('_', "");
+    let (mantissa, exponent) = match cleaned.find(['e', 'E']) {
+        Some(at) => (&cleaned[..at], cleaned[at + 1..].parse::<i32>().ok()?),
+        None => (cleaned.as_str(), 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", whole, fraction);
+    if digits.is_empty() || exponent.unsigned_abs() > MAX_EXPONENT as u32 {
+        return None;
+    }
+    let numerator: BigInt = digits.parse().ok()?;
+    let scale = exponent - fraction.len() as i32;
+    Some(if scale >= 0 {
+        BigRational::from_integer(numerator * pow10(scale as u32))
+    } else {
+        BigRational::new(numerator, pow10(scale.unsigned_abs()))
+    })
+}
+
+fn tokenize(expression: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            // Exponent only when digits follow, so `2e` stays `2 * e` for the float path.
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && matches!(chars[j], '+' | '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            tokens.push(Token::Num(parse_decimal(&literal)?));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(match word.as_str() {
+                "of" => Token::Of,
+                "mod" => Token::Mod,
+                _ => Token::Ident(word),
+            });
+            continue;
+        }
+        tokens.push(match c {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                i += 1;
+                Token::Op('^')
+            }
+            '×' => Token::Op('*'),
+            '÷' => Token::Op('/'),
+            '+' | '-' | '*' | '/' | '^' | '%' | '!' => Token::Op(c),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => return None,
+        });
+        i += 1;
+    }
+    Some(tokens)
+}
+
+/// A value plus whether it was written as a percentage, which changes how
+/// `+` and `-` treat it (`80 + 15%` adds 15% of 80).
+struct Value {
+    q: BigRational,
+    percent: bool,
+}
+
+impl From<BigRational> for Value {
+    fn from(q: BigRational) -> Self {
+        Value { q, percent: false }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    lookup: &'a dyn Fn(&str) -> Option<BigRational>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expression(&mut self) -> Option<Value> {
+        let mut lhs = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            let delta = if rhs.percent { &lhs.q * &rhs.q } else { rhs.q };
+            lhs = Value::from(if op == '+' {
+                lhs.q + delta
+            } else {
+                lhs.q - delta
+            });
+        }
+        Some(lhs)
+    }
+
+    fn term(&mut self) -> Option<Value> {
+        let mut lhs = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Op('*')) | Some(Token::Of) => {
+                    self.pos += 1;
+                    let rhs = self.unary()?;
+                    lhs = Value::from(lhs.q * rhs.q);
+                }
+                Some(Token::Op('/')) => {
+                    self.pos += 1;
+                    let rhs = self.unary()?;
+                    if rhs.q.is_zero() {
+                        return None;
+                    }
+                    lhs = Value::from(lhs.q / rhs.q);
+                }
+                // `%` only reaches here when an operand follows, i.e. as modulo.
+                Some(Token::Op('%')) | Some(Token::Mod) => {
+                    self.pos += 1;
+                    let rhs = self.unary()?;
+                    if rhs.q.is_zero() {
+                        return None;
+                    }
+                    let quotient = (&lhs.q / &rhs.q).trunc();
+                    lhs = Value::from(&lhs.q - rhs.q * quotient);
+                }
+                _ => break,
+            }
+        }
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<Value> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                let value = self.unary()?;
+                Some(Value {
+                    q: -value.q,
+                    percent: value.percent,
+                })
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Option<Value> {
+        let base = self.postfix()?;
+        if self.peek() != Some(&Token::Op('^')) {
+            return Some(base);
+        }
+        self.pos += 1;
+        let exponent = self.unary()?.q;
+        // Fractional powers are irrational in general; leave them to f64.
+        if !exponent.is_integer() {
+            return None;
+        }
+        let exponent = exponent.to_integer();
+        let magnitude = exponent.abs().to_u64().filter(|e| *e <= MAX_EXPONENT)? as u32;
+        if base.q.is_zero() && exponent.is_negative() {
+            return None;
+        }
+        // The exponent cap alone doesn't bound `(9^9999)^9999`, so estimate the size.
+        let base_bits = base.q.numer().bits().max(base.q.denom().bits());
+        if base_bits.saturating_mul(magnitude as u64) > MAX_POWER_BITS {
+            return None;
+        }
+        let raised = Pow::pow(&base.q, magnitude);
+        Some(Value::from(if exponent.is_negative() {
+            raised.recip()
+        } else {
+            raised
+        }))
+    }
+
+    fn postfix(&mut self) -> Option<Value> {
+        let mut value = self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Op('!')) => {
+                    self.pos += 1;
+                    value = Value::from(factorial(&value.q)?);
+                }
+                Some(Token::Op('%')) => {
+                    let operand_follows = matches!(
+                        self.tokens.get(self.pos + 1),
+                        Some(Token::Num(_) | Token::Ident(_) | Token::Open)
+                    );
+                    if operand_follows {
+                        break;
+                    }
+                    self.pos += 1;
+                    value = Value {
+                        q: value.q / BigRational::from_integer(BigInt::from(100)),
+                        percent: true,
+                    };
+                }
+                _ => break,
+            }
+        }
+        Some(value)
+    }
+
+    fn primary(&mut self) -> Option<Value> {
+        match self.next()? {
+            Token::Num(q) => Some(Value::from(q)),
+            Token::Ident(name) => (self.lookup)(&name).map(Value::from),
+            Token::Open => {
+                let inner = self.expression()?;
+                (self.next()? == Token::Close).then_some(inner)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn factorial(q: &BigRational) -> Option<BigRational> {
+    if !q.is_integer() || q.is_negative() {
+        return None;
+    }
+    let n = q.to_integer().to_u64().filter(|n| *n <= MAX_FACTORIAL)?;
+    let product = (2..=n).fold(BigInt::one(), |acc, k| acc * k);
+    Some(BigRational::from_integer(product))
+}
+
+/// Evaluates `expression` exactly. `lookup` resolves variables; returning `None`
+/// for a name (e.g. `pi`) hands the whole expression to the float path.
+pub fn evaluate(
+    expression: &str,
+    lookup: &dyn Fn(&str) -> Option<BigRational>,
+) -> Option<BigRational> {
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return None;
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        lookup,
+    };
+    let value = parser.expression()?;
+    (parser.pos == parser.tokens.len()).then_some(value.q)
+}
+
+/// The shortest decimal that round-trips `value` (`0.19` stays `19/100`).
+pub fn from_f64(value: f64) -> Option<BigRational> {
+    if !value.is_finite() {
+        return None;
+    }
+    parse_decimal(&format!("{:e}", value))
+}
+
+/// Rounds `q` to an integer according to `mode`.
+fn round(q: &BigRational, mode: RoundingMode) -> BigInt {
+    let truncated = q.trunc();
+    let remainder = (q - &truncated).abs();
+    let truncated = truncated.to_integer();
+    let away = &truncated
+        + if q.is_negative() {
+            -BigInt::one()
+        } else {
+            BigInt::one()
+        };
+    let half = BigRational::new(BigInt::one(), BigInt::from(2));
+    if remainder.is_zero() {
+        return truncated;
+    }
+    match mode {
+        RoundingMode::Down => truncated,
+        RoundingMode::Up => away,
+        RoundingMode::Floor => q.floor().to_integer(),
+        RoundingMode::Ceiling => q.ceil().to_integer(),
+        RoundingMode::HalfUp if remainder >= half => away,
+        RoundingMode::HalfDown if remainder > half => away,
+        RoundingMode::HalfEven if remainder > half => away,
+        RoundingMode::HalfEven if remainder == half && (&truncated % 2u32) != BigInt::zero() => {
+            away
+        }
+        _ => truncated,
+    }
+}
+
+/// `12345` with two places → `123.45`; trailing zeros are dropped.
+fn scaled_to_string(scaled: &BigInt, places: u32) -> String {
+    let digits = scaled.abs().to_string();
+    let places = places as usize;
+    let sign = if scaled.is_negative() { "-" } else { "" };
+    if places == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let padded = format!("{:0>width$}", digits, width = places + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - places);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+/// `4.0238726007709e2567` for integers too long to read.
+fn scientific(integer: &BigInt) -> String {
+    let digits = integer.abs().to_string();
+    let sign = if integer.is_negative() { "-" } else { "" };
+    let mantissa = format!("{}.{}", &digits[..1], &digits[1..15.min(digits.len())]);
+    let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}e{}", sign, mantissa, digits.len() - 1)
+}
+
+/// A formatted exact result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Formatted {
+    pub display: String,
+    pub copy_value: String,
+    /// `p/q` when the decimal had to be rounded.
+    pub fraction: Option<String>,
+}
+
+pub fn format(q: &BigRational, config: &CalculatorConfig) -> Formatted {
+    if q.is_integer() {
+        let integer = q.to_integer();
+        let full = integer.to_string();
+        let display = if full.trim_start_matches('-').len() > MAX_DISPLAY_DIGITS {
+            scientific(&integer)
+        } else {
+            full.clone()
+        };
+        return Formatted {
+            display,
+            copy_value: full,
+            fraction: None,
+        };
+    }
+
+    let places = config.precision.min(100);
+    let scaled = q * BigRational::from_integer(pow10(places));
+    let rounded = round(&scaled, config.rounding);
+    let text = scaled_to_string(&rounded, places);
+    Formatted {
+        display: text.clone(),
+        copy_value: text,
+        fraction: (!scaled.is_integer()).then(|| format!("{}/{}", q.numer(), q.denom())),
+    }
+}
+
+/// Rounds an `f64` result with the same precision and mode as exact results.
+pub fn format_f64(value: f64, config: &CalculatorConfig) -> Option<String> {
+    let q = BigRational::from_float(value)?;
+    let places = config.precision.min(100);
+    let scaled = q * BigRational::from_integer(pow10(places));
+    Some(scaled_to_string(&round(&scaled, config.rounding), places))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, format, from_f64};
+    use crate::config::{CalculatorConfig, RoundingMode};
+    use num_rational::BigRational;
+
+    fn calc(expression: &str) -> String {
+        let q = evaluate(expression, &|_| None).expect(expression);
+        format(&q, &CalculatorConfig::default()).copy_value
+    }
+
+    #[test]
+    fn adds_decimals_exactly() {
+        assert_eq!(calc("0.1 + 0.2"), "0.3");
+        assert_eq!(calc("1.15 * 100"), "115");
+        assert_eq!(calc("(1 + 2) * 3 - 4 / 8"), "8.5");
+        assert_eq!(calc("2 ^ -2"), "0.25");
+        assert_eq!(calc("1.5e3 + .5"), "1500.5");
+        assert_eq!(calc("-7.5 mod 2"), "-1.5");
+        assert!(evaluate("2 ^ 0.5", &|_| None).is_none());
+        assert!(evaluate("sqrt(4)", &|_| None).is_none());
+        assert!(evaluate("1 / 0", &|_| None).is_none());
+    }
+
+    #[test]
+    fn keeps_every_digit_of_big_integers() {
+        assert_eq!(calc("25!"), "15511210043330985984000000");
+        let q = evaluate("100!", &|_| None).unwrap();
+        let formatted = format(&q, &CalculatorConfig::default());
+        assert_eq!(formatted.copy_value.len(), 158);
+        assert_eq!(formatted.display, "9.33262154439441e157");
+    }
+
+    #[test]
+    fn refuses_powers_that_would_be_too_large() {
+        assert!(evaluate("2 ^ 10000", &|_| None).is_some());
+        assert!(evaluate("1 ^ 10000", &|_| None).is_some());
+        assert!(evaluate("(9 ^ 9999) ^ 9999", &|_| None).is_none());
+        assert!(evaluate("5000! ^ 9999", &|_| None).is_none());
+        assert!(evaluate("(1 / 3 ^ 9999) ^ 9999", &|_| None).is_none());
+    }
+
+    #[test]
+    fn applies_percentage_operators() {
+        assert_eq!(calc("15% of 80"), "12");
+        assert_eq!(calc("80 + 15%"), "92");
+        assert_eq!(calc("80 - 15%"), "68");
+        assert_eq!(calc("200 * 5%"), "10");
+        assert_eq!(calc("10 % 4"), "2");
+        assert_eq!(calc("12.5%"), "0.125");
+    }
+
+    #[test]
+    fn rounds_with_configured_precision_and_mode() {
+        let third = evaluate("1 / 3", &|_| None).unwrap();
+        let formatted = format(&third, &CalculatorConfig::default());
+        assert_eq!(formatted.display, "0.3333333333");
+        assert_eq!(formatted.fraction.as_deref(), Some("1/3"));
+
+        let cents = |mode| CalculatorConfig {
+            precision: 2,
+            rounding: mode,
+        };
+        let q = evaluate("2.345", &|_| None).unwrap();
+        assert_eq!(format(&q, &cents(RoundingMode::HalfUp)).display, "2.35");
+        assert_eq!(format(&q, &cents(RoundingMode::HalfEven)).display, "2.34");
+        assert_eq!(format(&q, &cents(RoundingMode::Down)).display, "2.34");
+        let negative = evaluate("-2.341", &|_| None).unwrap();
+        assert_eq!(
+            format(&negative, &cents(RoundingMode::Floor)).display,
+            "-2.35"
+        );
+        assert_eq!(format(&negative, &cents(RoundingMode::Up)).display, "-2.35");
+
+        let rate = from_f64(0.19).unwrap();
+        assert_eq!(rate, BigRational::new(19.into(), 100.into()));
+        let lookup = |name: &str| (name == "rate").then(|| rate.clone());
+        let q = evaluate("1200 * rate", &lookup).unwrap();
+        assert_eq!(format(&q, &CalculatorConfig::default()).display, "228");
+    }
+}
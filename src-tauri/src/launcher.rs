@@ -3,7 +3,7 @@ use std::process::{Command, Stdio};
 use std::env;
 
 #[cfg(not(test))]
-fn spawn_cmd(cmd: &str, args: &[String]) -> Result<(), std::io::Error> {
+pub(crate) fn spawn_cmd(cmd: &str, args: &[String]) -> Result<(), std::io::Error> {
     Command::new(cmd)
         .args(args)
         .stdin(Stdio::null())
@@ -14,7 +14,7 @@ fn spawn_cmd(cmd: &str, args: &[String]) -> Result<(), std::io::Error> {
 }
 
 #[cfg(test)]
-fn spawn_cmd(_cmd: &str, _args: &[String]) -> Result<(), std::io::Error> {
+pub(crate) fn spawn_cmd(_cmd: &str, _args: &[String]) -> Result<(), std::io::Error> {
     Ok(())
 }
 
@@ -177,7 +177,7 @@ fn strip_field_codes(exec: &str) -> String {
     cleaned
 }
 
-fn focus_window(address: &str) -> Result<(), VantaError> {
+pub(crate) fn focus_window(address: &str) -> Result<(), VantaError> {
     let mut ok = false;
 
     if let Err(e) = spawn_cmd(
@@ -293,6 +293,48 @@ fn move_window_to_current_workspace(address: &str) -> Result<(), VantaError> {
     }
 }
 
+pub(crate) fn on_path(bin: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(bin).is_file()))
+        .unwrap_or(false)
+}
+
+/// Puts text on the system clipboard through the active clipboard backend.
+pub fn copy_to_clipboard(text: &str) -> Result<(), VantaError> {
+    crate::clipboard_backend::active().write(text.as_bytes(), None)
+}
+
+/// Puts `bytes` on the clipboard offered as `mime` (`image/png`, `text/html`, …).
+pub fn copy_bytes_to_clipboard(bytes: &[u8], mime: &str) -> Result<(), VantaError> {
+    crate::clipboard_backend::active().write(bytes, Some(mime))
+}
+
+/// Types text into the focused window after `delay`, giving the launcher time to
+/// hide and focus to return. Prefers wtype (Wayland), then ydotool, then xdotool.
+pub fn type_text_after(text: &str, delay: std::time::Duration) -> Result<(), VantaError> {
+    let text = text.to_string();
+    let (bin, args): (&str, Vec<String>) = if on_path("wtype") {
+        ("wtype", vec!["--".to_string(), text])
+    } else if on_path("ydotool") {
+        ("ydotool", vec!["type".to_string(), "--".to_string(), text])
+    } else if on_path("xdotool") {
+        (
+            "xdotool",
+            vec!["type".to_string(), "--clearmodifiers".to_string(), "--".to_string(), text],
+        )
+    } else {
+        return Err("No typing tool found (install wtype, ydotool or xdotool)".into());
+    };
+
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        if let Err(e) = spawn_cmd(bin, &args) {
+            log::warn!("Failed to type text with {}: {}", bin, e);
+        }
+    });
+    Ok(())
+}
+
 /// Launches a terminal emulator and runs the provided shell command.
 /// Tries common terminals plus $TERMINAL; falls back to an error if none spawn.
 pub fn launch_terminal_command(command: &str) -> Result<(), VantaError> {
@@ -351,7 +393,55 @@ pub fn launch_terminal_command(command: &str) -> Result<(), VantaError> {
         }
     }
 
-    Err("No terminal emulator found to run 'vanta doctor'".into())
+    Err(format!("No terminal emulator found to run '{}'", command).into())
+}
+
+/// Open an interactive terminal with `dir` as its working directory.
+pub fn open_terminal_at(dir: &std::path::Path) -> Result<(), VantaError> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()).into());
+    }
+
+    let mut candidates: Vec<(String, Vec<String>)> = Vec::new();
+    if let Ok(term_raw) = env::var("TERMINAL") {
+        if term_raw.len() <= 256 && !term_raw.contains('\0') {
+            let parsed =
+                shell_words::split(&term_raw).unwrap_or_else(|_| vec![term_raw.clone()]);
+            if let Some((bin, rest)) = parsed.split_first() {
+                candidates.push((bin.to_string(), rest.to_vec()));
+            }
+        }
+    }
+
+    let dir_arg = dir.to_string_lossy().to_string();
+    let presets: [(&str, Vec<String>); 7] = [
+        ("kitty", vec!["--directory".to_string(), dir_arg.clone()]),
+        ("alacritty", vec!["--working-directory".to_string(), dir_arg.clone()]),
+        ("wezterm", vec!["start".to_string(), "--cwd".to_string(), dir_arg.clone()]),
+        ("gnome-terminal", vec![format!("--working-directory={}", dir_arg)]),
+        ("konsole", vec!["--workdir".to_string(), dir_arg.clone()]),
+        ("foot", vec![format!("--working-directory={}", dir_arg)]),
+        ("xterm", Vec::new()),
+    ];
+    for (bin, args) in presets {
+        candidates.push((bin.to_string(), args));
+    }
+
+    for (bin, args) in candidates {
+        let spawn_result = Command::new(&bin)
+            .args(&args)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        if spawn_result.is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err(format!("No terminal emulator found to open '{}'", dir.display()).into())
 }
 
 #[cfg(test)]
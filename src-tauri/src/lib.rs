@@ -1,4 +1,8 @@
 pub mod clipboard;
+pub mod clipboard_backend;
+pub mod clipboard_crypto;
+pub mod clipboard_filter;
+pub mod clipboard_index;
 pub mod config;
 pub mod errors;
 pub mod extensions;
@@ -17,6 +21,24 @@ pub mod permissions;
 pub mod workflows;
 pub mod community;
 pub mod secrets;
+pub mod diagnostics;
+pub mod quicklinks;
+pub mod browser;
+pub mod recent;
+pub mod ssh;
+pub mod emoji;
+pub mod shell;
+pub mod systemd;
+pub mod repos;
+pub mod units;
+pub mod currency;
+pub mod datetime;
+pub mod programmer;
+pub mod calc_session;
+pub mod exact;
+pub mod transforms;
+pub mod paste;
+pub mod snippets;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -52,6 +74,8 @@ pub struct AppState {
     pub config: RwLock<VantaConfig>,
     pub extensions_cache: Mutex<Vec<ExtensionEntry>>,
     pub history: Mutex<History>,
+    pub shell_history: Mutex<shell::CommandHistory>,
+    pub calc_session: Mutex<calc_session::CalcSession>,
     pub file_index: FileIndex,
     macro_jobs: Mutex<Vec<MacroJobRecord>>,
     canceled_jobs: Mutex<HashSet<String>>,
@@ -415,6 +439,9 @@ struct SearchDiagnostics {
     search: PerfStats,
     suggestions: PerfStats,
     launch: PerfStats,
+    operations: Vec<diagnostics::LatencySummary>,
+    sources: Vec<diagnostics::LatencySummary>,
+    slow_queries: Vec<diagnostics::SlowQueryRecord>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -468,6 +495,8 @@ fn is_internal_exec_for_analytics(exec: &str) -> bool {
     exec.starts_with("open-")
         || exec.starts_with("system-action:")
         || exec.starts_with("copy:")
+        || exec.starts_with("calc:")
+        || exec.starts_with("clip-")
         || exec.starts_with("copy-path:")
         || exec.starts_with("reveal:")
         || exec.starts_with("open-with:")
@@ -483,6 +512,15 @@ fn is_internal_exec_for_analytics(exec: &str) -> bool {
         || exec.starts_with("ext-no-view:")
         || exec.starts_with("note-save:")
         || exec.starts_with("bookmark-")
+        || exec.starts_with("quicklink:")
+        || exec.starts_with("open-url:")
+        || exec.starts_with(ssh::EXEC_TERMINAL_PREFIX)
+        || exec.starts_with("systemd:")
+        || exec.starts_with("terminal-at:")
+        || exec.starts_with(repos::EXEC_OPEN_PREFIX)
+        || exec.starts_with("emoji")
+        || exec.starts_with("shell-")
+        || exec.starts_with("snippet")
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -571,6 +609,10 @@ enum FilterSource {
     Window,
     Clipboard,
     Extension,
+    Web,
+    Recent,
+    Emoji,
+    Calc,
 }
 
 #[derive(Debug, Clone)]
@@ -593,6 +635,12 @@ fn parse_search_filters(query: &str) -> SearchFilter {
                 "window" | "windows" => source = Some(FilterSource::Window),
                 "clipboard" | "clip" => source = Some(FilterSource::Clipboard),
                 "extension" | "ext" | "extensions" => source = Some(FilterSource::Extension),
+                "web" | "browser" | "bookmarks" | "history" => source = Some(FilterSource::Web),
+                "recent" | "recents" => source = Some(FilterSource::Recent),
+                "emoji" | "emojis" | "symbol" | "symbols" | "unicode" => {
+                    source = Some(FilterSource::Emoji)
+                }
+                "calc" | "calculator" | "math" => source = Some(FilterSource::Calc),
                 _ => remaining.push(token.to_string()),
             }
         } else if let Some(val) = token.strip_prefix("in:") {
@@ -601,6 +649,9 @@ fn parse_search_filters(query: &str) -> SearchFilter {
                 "apps" | "app" => source = Some(FilterSource::App),
                 "files" | "file" => source = Some(FilterSource::File),
                 "windows" | "window" => source = Some(FilterSource::Window),
+                "web" | "browser" => source = Some(FilterSource::Web),
+                "recent" | "recents" => source = Some(FilterSource::Recent),
+                "calc" | "calculator" => source = Some(FilterSource::Calc),
                 _ => remaining.push(token.to_string()),
             }
         } else if let Some(val) = token.strip_prefix("ext:") {
@@ -627,6 +678,10 @@ fn filter_matches_source(filter: &SearchFilter, source: &ResultSource) -> bool {
         FilterSource::File => matches!(source, ResultSource::File),
         FilterSource::Window => matches!(source, ResultSource::Window),
         FilterSource::Clipboard => matches!(source, ResultSource::Clipboard),
+        FilterSource::Web => matches!(source, ResultSource::Web),
+        FilterSource::Recent => matches!(source, ResultSource::Recent),
+        FilterSource::Emoji => matches!(source, ResultSource::Emoji),
+        FilterSource::Calc => matches!(source, ResultSource::Calculator),
         FilterSource::Extension => {
             if let ResultSource::Extension { ref ext_id } = source {
                 filter
@@ -857,13 +912,15 @@ fn build_intent_results(query: &str, weight: u32, apps: &[AppEntry]) -> Vec<Sear
 
 fn build_command_palette_results(query: &str, weight: u32) -> Vec<SearchResult> {
     let needle = query.trim().to_lowercase();
-    let commands: [(&str, &str, &str, &str); 6] = [
+    let commands: [(&str, &str, &str, &str); 8] = [
         ("Open Settings", "Configure Vanta preferences", "open-settings", "Commands"),
         ("Open Store", "Browse and install extensions", "open-store", "Commands"),
         ("Open Feature Hub", "Navigate to feature hub", "open-window:featureHub", "Commands"),
         ("Open Community Hub", "Open community workflows and snippets", "open-window:communityHub", "Commands"),
         ("Open Theme Studio", "Customize themes and profile visuals", "open-window:themeHub", "Commands"),
         ("Open Extensions Hub", "Manage extension templates and runtime", "open-window:extensionsHub", "Commands"),
+        ("Lock Clipboard History", "Forget the history key until unlocked", clipboard::EXEC_LOCK, "Commands"),
+        ("Unlock Clipboard History", "Reload the history key and resume recording", clipboard::EXEC_UNLOCK, "Commands"),
     ];
 
     let mut out = Vec::new();
@@ -1154,6 +1211,28 @@ fn source_intent_bonus(query: &str, result: &SearchResult) -> u32 {
                 0
             }
         }
+        ResultSource::Recent => {
+            if q.contains("recent") || q.contains("last") || q.contains("document") {
+                ranking_config::SI_RECENT
+            } else {
+                0
+            }
+        }
+        ResultSource::Emoji => {
+            if q.contains("emoji") || q.contains("symbol") || q.contains("unicode") {
+                ranking_config::SI_EMOJI
+            } else {
+                0
+            }
+        }
+        ResultSource::Transform => 0,
+        ResultSource::Web => {
+            if q.contains("http") || q.contains("www") || q.contains(".com") || q.contains("site") {
+                ranking_config::SI_WEB
+            } else {
+                0
+            }
+        }
     }
 }
 
@@ -1266,7 +1345,7 @@ fn exec_to_command(exec: &str, source: &matcher::ResultSource) -> CommandV1 {
         return CommandV1::ExtensionAction { ext_id, command };
     }
 
-    if matches!(source, matcher::ResultSource::File) {
+    if matches!(source, matcher::ResultSource::File | matcher::ResultSource::Recent) {
         return CommandV1::OpenFile {
             path: exec.to_string(),
         };
@@ -1393,6 +1472,7 @@ fn record_latency(
     total_ms: &AtomicU64,
     max_ms: &AtomicU64,
 ) {
+    diagnostics::record_operation(label, elapsed);
     let elapsed_ms = elapsed.as_millis().min(u64::MAX as u128) as u64;
     let current_calls = calls.fetch_add(1, Ordering::Relaxed) + 1;
     total_ms.fetch_add(elapsed_ms, Ordering::Relaxed);
@@ -1674,56 +1754,68 @@ fn build_extension_results(
     results
 }
 
+fn clipboard_paste_action(id: i64) -> matcher::ActionHint {
+    matcher::ActionHint {
+        label: "Paste".to_string(),
+        exec: format!("{}{}", clipboard::EXEC_PASTE_PREFIX, id),
+        shortcut: Some("Shift+Enter".to_string()),
+    }
+}
+
 fn build_clipboard_results(query: &str, weight: u32, max_results: usize) -> Vec<SearchResult> {
     let trimmed = query.trim();
     if trimmed.is_empty() {
         return Vec::new();
     }
 
-    let Ok(history) = clipboard::get_history() else {
+    let Ok(hits) = clipboard::search_history(trimmed, max_results) else {
         return Vec::new();
     };
 
-    let lower = trimmed.to_lowercase();
+    // Highlight the free-text part; `type:` filters never appear in the title.
+    let terms = clipboard_index::ClipQuery::parse(trimmed).terms.join(" ");
     let mut results = Vec::new();
 
-    for item in history.into_iter().take(ranking_config::CLIPBOARD_SCAN_LIMIT) {
+    for (item, relevance) in hits {
         let content = item.content.replace('\n', " ");
         let title = content.chars().take(100).collect::<String>();
-        let matched = title.to_lowercase().contains(&lower);
-        let fuzzy = matcher::fuzzy_score_text(trimmed, &title);
-
-        if !matched && fuzzy.is_none() {
-            continue;
-        }
+        let indices = matcher::fuzzy_score_text(&terms, &title)
+            .map(|(_, indices)| indices)
+            .unwrap_or_default();
+        let score = ranking_config::CLIPBOARD_BASE.saturating_add(relevance);
 
-        let mut score: u32 = if matched { ranking_config::CLIPBOARD_EXACT_BASE } else { ranking_config::CLIPBOARD_FUZZY_BASE };
-        let mut indices = Vec::new();
-        if let Some((raw, idxs)) = fuzzy {
-            score = score.saturating_add(raw.saturating_mul(ranking_config::CLIPBOARD_FUZZY_MULTIPLIER));
-            indices = idxs;
-        }
-        if item.pinned {
-            score = score.saturating_add(ranking_config::CLIPBOARD_PINNED_BONUS);
-        }
+        let (icon, exec, actions) = if item.is_image() {
+            (
+                item.thumbnail_path.clone().or_else(|| item.image_path.clone()),
+                format!("{}{}", clipboard::EXEC_RESTORE_PREFIX, item.id),
+                vec![clipboard_paste_action(item.id)],
+            )
+        } else {
+            let mut actions = transforms::clipboard_actions(&item.content, &item.content_type);
+            if item.html.is_some() {
+                actions.insert(0, matcher::ActionHint {
+                    label: "Copy as HTML".to_string(),
+                    exec: format!("{}{}", clipboard::EXEC_RESTORE_HTML_PREFIX, item.id),
+                    shortcut: None,
+                });
+            }
+            actions.insert(0, clipboard_paste_action(item.id));
+            (Some("clipboard".to_string()), format!("copy:{}", item.content), actions)
+        };
 
         results.push(SearchResult {
             title,
             subtitle: Some(item.timestamp.to_rfc3339()),
-            icon: Some("clipboard".to_string()),
-            exec: format!("copy:{}", item.content),
+            icon,
+            exec,
             score: weighted_score(score, weight),
             match_indices: indices,
             source: ResultSource::Clipboard,
-            actions: None,
+            actions: (!actions.is_empty()).then_some(actions),
             id: Some(format!("clip:{}", item.id)),
             group: None,
             section: Some("Clipboard".to_string()),
         });
-
-        if results.len() >= max_results {
-            break;
-        }
     }
 
     results
@@ -1910,7 +2002,7 @@ mod command_palette_tests {
         assert_eq!(settings[0].exec, "open-settings");
 
         let all = build_command_palette_results("", 100);
-        assert_eq!(all.len(), 6);
+        assert_eq!(all.len(), 8);
         assert!(all
             .iter()
             .all(|r| r.id.as_deref().unwrap_or_default().starts_with("cmd:")));
@@ -2248,6 +2340,88 @@ async fn import_profile(
     Ok(imported)
 }
 
+#[tauri::command]
+async fn save_snippet(
+    snippet: config::Snippet,
+    previous_keyword: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<config::SnippetsConfig, VantaError> {
+    let updated = {
+        let mut config = state
+            .config
+            .write()
+            .map_err(|_| "Failed to access config state".to_string())?;
+        if let Some(previous) = previous_keyword.filter(|k| !k.eq_ignore_ascii_case(&snippet.keyword)) {
+            config
+                .snippets
+                .entries
+                .retain(|s| !s.keyword.eq_ignore_ascii_case(&previous));
+        }
+        snippets::merge(&mut config.snippets, vec![snippet])?;
+        config.save_with_source("user")?;
+        config.clone()
+    };
+
+    let _ = app_handle.emit("config-updated", &updated);
+    Ok(updated.snippets)
+}
+
+#[tauri::command]
+async fn delete_snippet(
+    keyword: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<config::SnippetsConfig, VantaError> {
+    let updated = {
+        let mut config = state
+            .config
+            .write()
+            .map_err(|_| "Failed to access config state".to_string())?;
+        config
+            .snippets
+            .entries
+            .retain(|s| !s.keyword.eq_ignore_ascii_case(&keyword));
+        config.save_with_source("user")?;
+        config.clone()
+    };
+
+    let _ = app_handle.emit("config-updated", &updated);
+    Ok(updated.snippets)
+}
+
+#[tauri::command]
+async fn export_snippets(
+    output_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), VantaError> {
+    let config = state
+        .config
+        .read()
+        .map_err(|_| "Failed to access config state".to_string())?;
+    snippets::export_to_path(&config.snippets, &output_path)
+}
+
+#[tauri::command]
+async fn import_snippets(
+    input_path: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<usize, VantaError> {
+    let (imported, updated) = {
+        let mut config = state
+            .config
+            .write()
+            .map_err(|_| "Failed to access config state".to_string())?;
+        let imported = snippets::import_from_path(&mut config.snippets, &input_path)?;
+        config.save_with_source("user")?;
+        (imported, config.clone())
+    };
+
+    let _ = app_handle.emit("config-updated", &updated);
+    Ok(imported)
+}
+
 #[tauri::command]
 async fn rebuild_file_index(
     state: tauri::State<'_, AppState>,
@@ -2261,6 +2435,7 @@ async fn rebuild_file_index(
     };
 
     let index_state = files::build_index(&files_config);
+    repos::invalidate_index();
 
     {
         let mut guard = state
@@ -2333,7 +2508,7 @@ async fn search(
     let generation = SEARCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
 
     if let Some(command_query) = query.trim().strip_prefix('>') {
-        let (templates, macros) = {
+        let (templates, macros, quicklinks) = {
             let config = state
                 .config
                 .read()
@@ -2341,6 +2516,7 @@ async fn search(
             (
                 config.workflows.command_templates.clone(),
                 config.workflows.macros.clone(),
+                config.search.quicklinks.clone(),
             )
         };
         let mut command_results = build_command_palette_results(command_query, 100);
@@ -2350,6 +2526,11 @@ async fn search(
             &macros,
             100,
         ));
+        command_results.extend(quicklinks::build_quicklink_palette_results(
+            command_query,
+            &quicklinks,
+            100,
+        ));
         command_results.sort_by(|a, b| b.score.cmp(&a.score));
         record_latency(
             "search",
@@ -2361,6 +2542,34 @@ async fn search(
         return Ok(command_results);
     }
 
+    if let Some(shell_input) = query.trim_start().strip_prefix('!') {
+        let (max_results, commands) = {
+            let config = state
+                .config
+                .read()
+                .map_err(|_| "Failed to access config".to_string())?;
+            let history = state
+                .shell_history
+                .lock()
+                .map_err(|_| "Failed to access shell history".to_string())?;
+            (config.general.max_results, history.commands().to_vec())
+        };
+        let input = shell_input.to_string();
+        let shell_results = tokio::task::spawn_blocking(move || {
+            shell::build_shell_results(&input, &commands, &shell::executables(), max_results)
+        })
+        .await
+        .unwrap_or_default();
+        record_latency(
+            "search",
+            search_start.elapsed(),
+            &SEARCH_CALLS,
+            &SEARCH_TOTAL_MS,
+            &SEARCH_MAX_MS,
+        );
+        return Ok(shell_results);
+    }
+
     if let Some(note_query) = query.trim().strip_prefix("note:") {
         let config = state
             .config
@@ -2413,6 +2622,11 @@ async fn search(
     let usage_map;
     let ext_snapshot;
     let file_index_snapshot;
+    let files_config;
+    let browser_config;
+    let currency_config;
+    let calculator_config;
+    let snippets_config;
     {
         apps_snapshot = state
             .apps
@@ -2426,6 +2640,11 @@ async fn search(
         max_results = config.general.max_results;
         search_config = config.search.clone();
         profiles_config = config.profiles.clone();
+        files_config = config.files.clone();
+        browser_config = config.browser.clone();
+        currency_config = config.currency.clone();
+        calculator_config = config.calculator;
+        snippets_config = config.snippets.clone();
         drop(config);
 
         usage_map = state
@@ -2480,6 +2699,11 @@ async fn search(
     let q3 = effective_query.clone();
     let q4 = effective_query.clone();
     let q5 = effective_query.clone();
+    let q6 = effective_query.clone();
+    let q7 = effective_query.clone();
+    let q8 = effective_query.clone();
+    let q9 = effective_query.clone();
+    let q10 = effective_query.clone();
     let ql1 = effective_lower.clone();
     let apps1 = apps_snapshot.clone();
     let apps2 = apps_snapshot.clone();
@@ -2494,78 +2718,197 @@ async fn search(
     let skip_windows = has_filter && !matches!(filter.source, Some(FilterSource::Window));
     let skip_files = has_filter && !matches!(filter.source, Some(FilterSource::File));
     let skip_clipboard = has_filter && !matches!(filter.source, Some(FilterSource::Clipboard));
+    let skip_web = has_filter && !matches!(filter.source, Some(FilterSource::Web));
+    let skip_recent = has_filter && !matches!(filter.source, Some(FilterSource::Recent));
+    let skip_ssh = has_filter;
+    let skip_systemd = has_filter;
+    let skip_repos = has_filter && !matches!(filter.source, Some(FilterSource::File));
     let skip_misc = has_filter && !matches!(filter.source, Some(FilterSource::App | FilterSource::Extension));
 
     // Task 1: Fuzzy app search
     let app_task = tokio::task::spawn_blocking({
         let sc = sc.clone();
-        move || -> Vec<SearchResult> {
+        move || -> (Vec<SearchResult>, Option<Duration>) {
             if skip_apps || !sc.applications.enabled {
-                return Vec::new();
+                return (Vec::new(), None);
             }
-            matcher::fuzzy_search(&q1, &apps1, app_limit, &um1, sc.applications.weight)
+            let started = Instant::now();
+            let results = matcher::fuzzy_search(&q1, &apps1, app_limit, &um1, sc.applications.weight);
+            (results, Some(started.elapsed()))
         }
     });
 
     // Task 2: Window search
     let window_task = tokio::task::spawn_blocking({
         let sc = sc.clone();
-        move || -> Vec<SearchResult> {
+        move || -> (Vec<SearchResult>, Option<Duration>) {
             if skip_windows || !sc.windows.enabled {
-                return Vec::new();
+                return (Vec::new(), None);
             }
-            build_window_results(&q2, &ql1, window_cap, &apps2, sc.windows.weight, |cap| {
+            let started = Instant::now();
+            let results = build_window_results(&q2, &ql1, window_cap, &apps2, sc.windows.weight, |cap| {
                 list_windows_grouped(cap)
-            })
+            });
+            (results, Some(started.elapsed()))
         }
     });
 
     // Task 3: File search
     let file_task = tokio::task::spawn_blocking({
         let sc = sc.clone();
-        move || -> Vec<SearchResult> {
+        move || -> (Vec<SearchResult>, Option<Duration>) {
             if skip_files || !sc.files.enabled {
-                return Vec::new();
+                return (Vec::new(), None);
             }
+            let started = Instant::now();
             let mut file_results = files::search_index(&file_index_snapshot, &q3, max_results);
             for fr in &mut file_results {
                 fr.score = weighted_score(fr.score, sc.files.weight);
             }
-            file_results
+            (file_results, Some(started.elapsed()))
         }
     });
 
     // Task 4: Clipboard search
     let clipboard_task = tokio::task::spawn_blocking({
         let sc = sc.clone();
-        move || -> Vec<SearchResult> {
+        move || -> (Vec<SearchResult>, Option<Duration>) {
             if skip_clipboard || q4.trim().is_empty() {
-                return Vec::new();
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            let results = build_clipboard_results(&q4, sc.files.weight, max_results / 2);
+            (results, Some(started.elapsed()))
+        }
+    });
+
+    // Task 5: Browser bookmarks and history
+    let web_task = tokio::task::spawn_blocking({
+        let sc = sc.clone();
+        move || -> (Vec<SearchResult>, Option<Duration>) {
+            if skip_web || !sc.web.enabled || q6.trim().is_empty() {
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            let entries = browser::cached_entries(&browser_config);
+            let results = browser::search_entries(
+                &q6,
+                &entries,
+                ranking_config::WEB_RESULT_LIMIT.min(max_results.max(1)),
+                sc.web.weight,
+            );
+            (results, Some(started.elapsed()))
+        }
+    });
+
+    // Task 6: Recently used files (empty query lists the newest when filtered)
+    let recent_task = tokio::task::spawn_blocking({
+        let sc = sc.clone();
+        move || -> (Vec<SearchResult>, Option<Duration>) {
+            if skip_recent || !sc.recent.enabled || (q7.trim().is_empty() && !has_filter) {
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            let limit = if has_filter {
+                max_results
+            } else {
+                ranking_config::RECENT_RESULT_LIMIT.min(max_results.max(1))
+            };
+            let results = recent::search_recent(&q7, &recent::recent_entries(), limit, sc.recent.weight);
+            (results, Some(started.elapsed()))
+        }
+    });
+
+    // Task 7: SSH hosts from ~/.ssh/config and known_hosts
+    let ssh_task = tokio::task::spawn_blocking({
+        let sc = sc.clone();
+        move || -> (Vec<SearchResult>, Option<Duration>) {
+            if skip_ssh || !sc.ssh.enabled || q8.trim().is_empty() {
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            let results = ssh::search_hosts(
+                &q8,
+                &ssh::cached_inventory(),
+                ranking_config::SSH_RESULT_LIMIT.min(max_results.max(1)),
+                sc.ssh.weight,
+            );
+            (results, Some(started.elapsed()))
+        }
+    });
+
+    // Task 8: systemd user services and timers (listing needs Shell permission)
+    let systemd_task = tokio::task::spawn_blocking({
+        let sc = sc.clone();
+        move || -> (Vec<SearchResult>, Option<Duration>) {
+            if skip_systemd || !sc.systemd.enabled || q9.trim().is_empty() {
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            if !systemd::is_authorized() {
+                let hint = systemd::authorize_result(&q9).into_iter().collect();
+                return (hint, Some(started.elapsed()));
             }
-            build_clipboard_results(&q4, sc.files.weight, max_results / 2)
+            let limit = if systemd::is_explicit_query(&q9) {
+                ranking_config::SYSTEMD_LIST_LIMIT
+            } else {
+                ranking_config::SYSTEMD_RESULT_LIMIT
+            };
+            let results = systemd::search_units(&q9, &systemd::cached_units(), limit, sc.systemd.weight);
+            (results, Some(started.elapsed()))
         }
     });
 
-    // Task 5: Extensions, profiles, intents (lightweight — group together)
+    // Task 9: Git repositories found under the file index roots
+    let repos_task = tokio::task::spawn_blocking({
+        let sc = sc.clone();
+        move || -> (Vec<SearchResult>, Option<Duration>) {
+            if skip_repos || !sc.repos.enabled || q10.trim().is_empty() {
+                return (Vec::new(), None);
+            }
+            let started = Instant::now();
+            let repos_snapshot = repos::cached_repos(&files_config);
+            let limit = if repos::is_explicit_query(&q10) {
+                ranking_config::REPO_LIST_LIMIT
+            } else {
+                ranking_config::REPO_RESULT_LIMIT
+            };
+            let results = repos::search_repos(&q10, &repos_snapshot, limit, sc.repos.weight);
+            (results, Some(started.elapsed()))
+        }
+    });
+
+    // Task 10: Extensions, profiles, intents (lightweight — group together)
     let misc_task = tokio::task::spawn_blocking({
         let sc = sc.clone();
-        move || -> Vec<SearchResult> {
+        move || -> (Vec<SearchResult>, Option<Duration>) {
             if skip_misc {
-                return Vec::new();
+                return (Vec::new(), None);
             }
+            let started = Instant::now();
             let mut results = Vec::new();
             if sc.applications.enabled {
                 results.extend(build_profile_results(&q5, &pc, sc.applications.weight));
                 results.extend(build_intent_results(&q5, sc.applications.weight, &apps3));
                 results.extend(build_extension_results(&q5, &ext_snapshot, sc.applications.weight));
             }
-            results
+            (results, Some(started.elapsed()))
         }
     });
 
     // Await all concurrently.
-    let (app_res, win_res, file_res, clip_res, misc_res) =
-        tokio::join!(app_task, window_task, file_task, clipboard_task, misc_task);
+    let (app_res, win_res, file_res, clip_res, web_res, recent_res, ssh_res, systemd_res, repos_res, misc_res) = tokio::join!(
+        app_task,
+        window_task,
+        file_task,
+        clipboard_task,
+        web_task,
+        recent_task,
+        ssh_task,
+        systemd_task,
+        repos_task,
+        misc_task
+    );
 
     // Check cancellation after parallel work completes.
     if SEARCH_GENERATION.load(Ordering::SeqCst) != generation {
@@ -2574,21 +2917,55 @@ async fn search(
 
     // ── Merge results ────────────────────────────────────────────────
     let mut results: Vec<SearchResult> = Vec::new();
+    let mut source_timings: Vec<(&str, Duration, usize)> = Vec::new();
+    let mut merge_source = |label: &'static str,
+                            res: Result<(Vec<SearchResult>, Option<Duration>), tokio::task::JoinError>|
+     -> Vec<SearchResult> {
+        let (items, elapsed) = res.unwrap_or_default();
+        if let Some(elapsed) = elapsed {
+            source_timings.push((label, elapsed, items.len()));
+        }
+        items
+    };
+
+    let app_results = merge_source(diagnostics::SOURCE_APPS, app_res);
+    let win_results = merge_source(diagnostics::SOURCE_WINDOWS, win_res);
+    let file_results = merge_source(diagnostics::SOURCE_FILES, file_res);
+    let clip_results = merge_source(diagnostics::SOURCE_CLIPBOARD, clip_res);
+    let web_results = merge_source(diagnostics::SOURCE_WEB, web_res);
+    let recent_results = merge_source(diagnostics::SOURCE_RECENT, recent_res);
+    let ssh_results = merge_source(diagnostics::SOURCE_SSH, ssh_res);
+    let systemd_results = merge_source(diagnostics::SOURCE_SYSTEMD, systemd_res);
+    let repos_results = merge_source(diagnostics::SOURCE_REPOS, repos_res);
+    let misc_results = merge_source(diagnostics::SOURCE_EXTENSIONS, misc_res);
 
-    let app_results = app_res.unwrap_or_default();
     if !app_results.is_empty() {
         let _ = app_handle_stream.emit("search-partial", &app_results.iter().map(|r| to_v3_result(r.clone())).collect::<Vec<_>>());
     }
     results.extend(app_results);
 
-    results.extend(win_res.unwrap_or_default());
-    results.extend(file_res.unwrap_or_default());
-    results.extend(clip_res.unwrap_or_default());
-    results.extend(misc_res.unwrap_or_default());
-
-    // Calculator (fast, synchronous)
-    if search_config.calculator.enabled && !has_filter {
-        if let Some((display, copy_value)) = math::evaluate_timezone_display(&effective_query) {
+    results.extend(win_results);
+    results.extend(file_results);
+    results.extend(clip_results);
+    results.extend(web_results);
+    results.extend(recent_results);
+    results.extend(ssh_results);
+    results.extend(systemd_results);
+    results.extend(repos_results);
+    results.extend(misc_results);
+
+    // Calculator (fast, synchronous); `type:calc` adds the session and its history.
+    let calc_filtered = matches!(filter.source, Some(FilterSource::Calc));
+    if search_config.calculator.enabled && (!has_filter || calc_filtered) {
+        let calc_start = Instant::now();
+        let calc_len_before = results.len();
+        let session = state
+            .calc_session
+            .lock()
+            .map_err(|_| "Failed to access calculator session".to_string())?;
+        if let Some((display, copy_value)) = math::evaluate_timezone_display(&effective_query)
+            .or_else(|| datetime::evaluate_display(&effective_query))
+        {
             results.push(SearchResult {
                 title: format!("= {}", display),
                 subtitle: Some("Click to Copy".to_string()),
@@ -2602,21 +2979,107 @@ async fn search(
                 group: None,
                 section: Some("Calculator".to_string()),
             });
-        } else if let Some((display, copy_value)) = math::evaluate_display(&effective_query) {
+        } else if let Some(evaluation) = session.evaluate(&effective_query, &calculator_config) {
+            let output = evaluation.output;
+            let subtitle = match (&evaluation.statement, &output.rates) {
+                (calc_session::Statement::Assignment(name), _) => format!("Enter to Store as {}", name),
+                (calc_session::Statement::Function(name), _) => format!("Enter to Define {}()", name),
+                (_, Some(stamp)) => format!(
+                    "{} · Click to Copy",
+                    stamp.describe(currency_config.stale_after_days, Local::now().date_naive())
+                ),
+                (_, None) => "Click to Copy".to_string(),
+            };
+            let title = match evaluation.statement {
+                calc_session::Statement::Expression => format!("= {}", output.display),
+                _ => output.display.clone(),
+            };
+            let actions = (!output.alternates.is_empty()).then(|| {
+                output
+                    .alternates
+                    .iter()
+                    .map(|(label, value)| matcher::ActionHint {
+                        label: label.to_string(),
+                        exec: format!("copy:{}", value),
+                        shortcut: None,
+                    })
+                    .collect::<Vec<_>>()
+            });
             results.push(SearchResult {
-                title: format!("= {}", display),
-                subtitle: Some("Click to Copy".to_string()),
+                title,
+                subtitle: Some(subtitle),
                 icon: Some("calculator".to_string()),
-                exec: format!("copy:{}", copy_value),
+                exec: format!("{}{}", calc_session::EXEC_PREFIX, effective_query.trim()),
                 score: weighted_score(ranking_config::CALCULATOR_BASE_SCORE, search_config.calculator.weight),
                 match_indices: vec![],
                 source: matcher::ResultSource::Calculator,
-                actions: None,
+                actions,
                 id: None,
                 group: None,
                 section: Some("Calculator".to_string()),
             });
         }
+        if calc_filtered {
+            results.extend(session.search(&effective_query, ranking_config::CALC_HISTORY_LIMIT));
+        }
+        drop(session);
+        source_timings.push((
+            diagnostics::SOURCE_CALCULATOR,
+            calc_start.elapsed(),
+            results.len() - calc_len_before,
+        ));
+    }
+
+    // Emoji & Unicode picker (`:grin`, `emoji: arrow right`, `type:emoji`)
+    let emoji_filtered = matches!(filter.source, Some(FilterSource::Emoji));
+    let emoji_needle = if emoji_filtered {
+        Some(emoji::parse_emoji_query(&effective_query).unwrap_or(effective_query.trim()))
+    } else if !has_filter {
+        emoji::parse_emoji_query(&effective_query)
+    } else {
+        None
+    };
+    if let Some(needle) = emoji_needle.filter(|_| search_config.emoji.enabled) {
+        let emoji_start = Instant::now();
+        let mut emoji_results = emoji::search_emoji(
+            needle,
+            &usage_map,
+            ranking_config::EMOJI_RESULT_LIMIT.min(max_results.max(1)),
+            search_config.emoji.weight,
+        );
+        if !emoji_filtered {
+            for result in &mut emoji_results {
+                result.score = result.score.saturating_add(ranking_config::EMOJI_PICKER_BASE);
+            }
+        }
+        source_timings.push((diagnostics::SOURCE_EMOJI, emoji_start.elapsed(), emoji_results.len()));
+        results.extend(emoji_results);
+    }
+
+    // Text transforms (`b64 …`, `sha256 …`, `uuid`, `json …`, `#ff8800 to hsl`)
+    if !has_filter && search_config.transforms.enabled {
+        results.extend(transforms::search_transforms(
+            &effective_query,
+            search_config.transforms.weight,
+        ));
+    }
+
+    // Quicklinks (`gh <repo>`, `ddg <terms>`, …)
+    if !has_filter {
+        results.extend(quicklinks::build_quicklink_results(
+            &effective_query,
+            &search_config.quicklinks,
+            search_config.applications.weight,
+        ));
+    }
+
+    // Snippets (`sig`, `reply Alice; #4512`, …)
+    if !has_filter {
+        results.extend(snippets::build_snippet_results(
+            &effective_query,
+            &snippets_config,
+            search_config.applications.weight,
+        ));
     }
 
     // Store / Settings injection
@@ -2693,6 +3156,15 @@ async fn search(
         results.retain(|r| r.score >= ranking_config::NEGATIVE_SCORE_THRESHOLD);
     }
 
+    // ── Web search fallback when nothing matched ─────────────────────
+    if results.is_empty() && !has_filter {
+        if let Some(fallback) =
+            quicklinks::build_web_fallback_result(&effective_query, &search_config.quicklinks)
+        {
+            results.push(fallback);
+        }
+    }
+
     // ── Typo suggestions when results are sparse ─────────────────────
     if !effective_query.trim().is_empty() && results.len() <= 2 {
         let suggestions = typo_suggestions(&effective_query, &apps_snapshot, 3);
@@ -2715,9 +3187,11 @@ async fn search(
 
     results.sort_by(|a, b| b.score.cmp(&a.score));
 
+    let search_elapsed = search_start.elapsed();
+    diagnostics::record_search(&query, search_elapsed, &source_timings);
     record_latency(
         "search",
-        search_start.elapsed(),
+        search_elapsed,
         &SEARCH_CALLS,
         &SEARCH_TOTAL_MS,
         &SEARCH_MAX_MS,
@@ -2759,6 +3233,31 @@ async fn get_query_history(
     Ok(history.get_recent_queries().to_vec())
 }
 
+/// Hides the launcher so focus can go back to the window being pasted into.
+fn hide_main_window(app_handle: &tauri::AppHandle) {
+    if let Some(win) = app_handle.get_webview_window("main") {
+        if let Err(e) = window::hide_window(&win) {
+            log::warn!("Failed to hide before pasting: {}", e);
+        }
+    }
+}
+
+/// Current clipboard text, for the `{clipboard}` snippet placeholder.
+fn read_clipboard_text() -> Option<String> {
+    clipboard_backend::active()
+        .read("text/plain")
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn paste_config(state: &AppState) -> Result<config::PasteConfig, VantaError> {
+    Ok(state
+        .config
+        .read()
+        .map_err(|_| "Failed to access config".to_string())?
+        .paste
+        .clone())
+}
+
 #[tauri::command]
 async fn launch_app(
     exec: String,
@@ -2871,6 +3370,214 @@ async fn launch_app(
         return Ok(());
     }
 
+    if let Some(url) = exec.strip_prefix("open-url:") {
+        if let Ok(mut history) = state.history.lock() {
+            history.increment(&exec);
+        }
+        open_external_link(url.to_string()).await?;
+        record_latency(
+            "launch",
+            launch_start.elapsed(),
+            &LAUNCH_CALLS,
+            &LAUNCH_TOTAL_MS,
+            &LAUNCH_MAX_MS,
+        );
+        return Ok(());
+    }
+
+    if let Some(command) = exec.strip_prefix(shell::EXEC_RUN_PREFIX) {
+        if let Ok(mut shell_history) = state.shell_history.lock() {
+            shell_history.push(command);
+        }
+        launcher::launch(&shell::detached_exec(command), Some(&app_handle))?;
+        record_latency(
+            "launch",
+            launch_start.elapsed(),
+            &LAUNCH_CALLS,
+            &LAUNCH_TOTAL_MS,
+            &LAUNCH_MAX_MS,
+        );
+        return Ok(());
+    }
+
+    if let Some(command) = exec.strip_prefix(shell::EXEC_TERMINAL_PREFIX) {
+        if let Ok(mut shell_history) = state.shell_history.lock() {
+            shell_history.push(command);
+        }
+        launcher::launch_terminal_command(command)?;
+        record_latency(
+            "launch",
+            launch_start.elapsed(),
+            &LAUNCH_CALLS,
+            &LAUNCH_TOTAL_MS,
+            &LAUNCH_MAX_MS,
+        );
+        return Ok(());
+    }
+
+    if let Some(command) = exec.strip_prefix(shell::EXEC_COPY_OUTPUT_PREFIX) {
+        if let Ok(mut shell_history) = state.shell_history.lock() {
+            shell_history.push(command);
+        }
+        let owned = command.to_string();
+        let output = tokio::task::spawn_blocking(move || {
+            shell::run_capture(&owned, shell::CAPTURE_TIMEOUT)
+        })
+        .await
+        .map_err(|e| format!("Command task failed: {}", e))??;
+        launcher::copy_to_clipboard(&output)?;
+        return Ok(());
+    }
+
+    if let Some(path) = exec.strip_prefix(repos::EXEC_TERMINAL_PREFIX) {
+        launcher::open_terminal_at(std::path::Path::new(path))?;
+        return Ok(());
+    }
+
+    if let Some(path) = exec.strip_prefix(repos::EXEC_OPEN_PREFIX) {
+        if !std::path::Path::new(path).join(".git").exists() {
+            return Err(format!("Not a git repository: {}", path).into());
+        }
+        open_in_editor(path, &state, &app_handle)?;
+        return Ok(());
+    }
+
+    if let Some(rest) = exec.strip_prefix(systemd::EXEC_PREFIX) {
+        let (verb, unit) = rest.split_once(':').unwrap_or((rest, ""));
+        if verb == "authorize" {
+            systemd::ensure_permission()?;
+            systemd::invalidate_cache();
+            return Ok(());
+        }
+        let verb = systemd::UnitVerb::parse(verb)
+            .ok_or_else(|| format!("Unknown systemd action: {}", verb))?;
+        let unit = unit.to_string();
+        tokio::task::spawn_blocking(move || systemd::run_action(verb, &unit))
+            .await
+            .map_err(|e| format!("systemctl task failed: {}", e))??;
+        return Ok(());
+    }
+
+    if exec == clipboard::EXEC_LOCK {
+        clipboard::lock_history();
+        return Ok(());
+    }
+
+    if exec == clipboard::EXEC_UNLOCK {
+        return clipboard_crypto::unlock();
+    }
+
+    if let Some(id) = exec.strip_prefix(clipboard::EXEC_PASTE_PREFIX) {
+        let id = id.parse::<i64>().map_err(|_| "Invalid clipboard entry".to_string())?;
+        clipboard::restore_item(id, false)?;
+        hide_main_window(&app_handle);
+        return paste::press_paste(&paste_config(&state)?, 0);
+    }
+
+    if let Some(value) = exec.strip_prefix(snippets::EXEC_PASTE_PREFIX) {
+        let (expansion, paste_config) = {
+            let cfg = state
+                .config
+                .read()
+                .map_err(|_| "Failed to access config".to_string())?;
+            (snippets::expand(&cfg.snippets, value, read_clipboard_text)?, cfg.paste.clone())
+        };
+        hide_main_window(&app_handle);
+        return paste::paste_text(&expansion.text, expansion.cursor_back, &paste_config);
+    }
+
+    if let Some(value) = exec.strip_prefix(snippets::EXEC_COPY_PREFIX) {
+        let expansion = {
+            let cfg = state
+                .config
+                .read()
+                .map_err(|_| "Failed to access config".to_string())?;
+            snippets::expand(&cfg.snippets, value, read_clipboard_text)?
+        };
+        return launcher::copy_to_clipboard(&expansion.text);
+    }
+
+    if let Some(id) = exec.strip_prefix(clipboard::EXEC_RESTORE_HTML_PREFIX) {
+        let id = id.parse::<i64>().map_err(|_| "Invalid clipboard entry".to_string())?;
+        return clipboard::restore_item(id, true);
+    }
+
+    if let Some(id) = exec.strip_prefix(clipboard::EXEC_RESTORE_PREFIX) {
+        let id = id.parse::<i64>().map_err(|_| "Invalid clipboard entry".to_string())?;
+        return clipboard::restore_item(id, false);
+    }
+
+    if let Some(expression) = exec.strip_prefix(calc_session::EXEC_PREFIX) {
+        let calculator_config = state
+            .config
+            .read()
+            .map_err(|_| "Failed to access config".to_string())?
+            .calculator;
+        let copy_value = state
+            .calc_session
+            .lock()
+            .map_err(|_| "Failed to access calculator session".to_string())?
+            .commit(expression, &calculator_config, now_millis())?;
+        if let Some(value) = copy_value {
+            launcher::copy_to_clipboard(&value)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(value) = exec.strip_prefix(emoji::EXEC_TYPE_PREFIX) {
+        if let Ok(mut history) = state.history.lock() {
+            history.increment(&format!("{}{}", emoji::EXEC_COPY_PREFIX, value));
+        }
+        launcher::type_text_after(value, Duration::from_millis(180))?;
+        return Ok(());
+    }
+
+    if let Some(value) = exec.strip_prefix(emoji::EXEC_COPY_PREFIX) {
+        if let Ok(mut history) = state.history.lock() {
+            history.increment(&exec);
+        }
+        launcher::copy_to_clipboard(value)?;
+        return Ok(());
+    }
+
+    if let Some(command) = exec.strip_prefix(ssh::EXEC_TERMINAL_PREFIX) {
+        if let Ok(mut history) = state.history.lock() {
+            history.increment(&exec);
+        }
+        launcher::launch_terminal_command(command)?;
+        record_latency(
+            "launch",
+            launch_start.elapsed(),
+            &LAUNCH_CALLS,
+            &LAUNCH_TOTAL_MS,
+            &LAUNCH_MAX_MS,
+        );
+        return Ok(());
+    }
+
+    if let Some(target) = exec
+        .strip_prefix("quicklink:")
+        .or_else(|| exec.strip_prefix(ssh::EXEC_OPEN_URI_PREFIX))
+    {
+        if let Ok(mut history) = state.history.lock() {
+            history.increment(&exec);
+        }
+        match quicklinks::classify_target(target) {
+            quicklinks::QuicklinkTarget::Web(url) => open_external_link(url).await?,
+            quicklinks::QuicklinkTarget::Local(path) => {
+                open::that(&path).map_err(|e| format!("Failed to open '{}': {}", path, e))?
+            }
+        }
+        record_latency(
+            "launch",
+            launch_start.elapsed(),
+            &LAUNCH_CALLS,
+            &LAUNCH_TOTAL_MS,
+            &LAUNCH_MAX_MS,
+        );
+        return Ok(());
+    }
+
     if let Ok(mut history) = state.history.lock() {
         history.increment(&exec);
     }
@@ -2948,6 +3655,14 @@ async fn get_suggestions(
     );
     results.extend(bookmark_results);
 
+    if search_config.recent.enabled {
+        results.extend(recent::build_recent_suggestions(
+            &recent::recent_entries(),
+            ranking_config::RECENT_SUGGESTION_LIMIT,
+            search_config.recent.weight,
+        ));
+    }
+
     let mut scored_apps: Vec<(&AppEntry, u32)> = apps
         .iter()
         .map(|app| (app, history.get_usage(&app.exec)))
@@ -3107,6 +3822,9 @@ async fn get_search_diagnostics() -> Result<SearchDiagnostics, VantaError> {
         search: snapshot_perf(&SEARCH_CALLS, &SEARCH_TOTAL_MS, &SEARCH_MAX_MS),
         suggestions: snapshot_perf(&SUGGEST_CALLS, &SUGGEST_TOTAL_MS, &SUGGEST_MAX_MS),
         launch: snapshot_perf(&LAUNCH_CALLS, &LAUNCH_TOTAL_MS, &LAUNCH_MAX_MS),
+        operations: diagnostics::operation_summaries(),
+        sources: diagnostics::source_summaries(),
+        slow_queries: diagnostics::slow_queries(),
     })
 }
 
@@ -3172,6 +3890,17 @@ async fn get_health_dashboard(
         status: "ok".to_string(),
         detail: format!("{} jobs recorded", macro_jobs_total),
     });
+    let clipboard_watcher = clipboard::watcher_status();
+    checks.push(HealthCheck {
+        name: "clipboard_watcher".to_string(),
+        status: match clipboard_watcher.state {
+            clipboard::WatcherState::Watching => "ok",
+            clipboard::WatcherState::Starting | clipboard::WatcherState::Restarting => "warn",
+            clipboard::WatcherState::BackendMissing => "error",
+        }
+        .to_string(),
+        detail: clipboard_watcher.detail,
+    });
 
     Ok(HealthDashboard {
         generated_at: now_millis(),
@@ -3848,7 +4577,23 @@ async fn retry_macro_job(
 
 #[tauri::command]
 async fn get_clipboard_history() -> Result<Vec<clipboard::ClipboardItem>, VantaError> {
-    Ok(clipboard::get_history().map_err(|e| format!("Failed to get history: {}", e))?)
+    clipboard::get_history()
+}
+
+#[tauri::command]
+async fn lock_clipboard_history() -> Result<(), VantaError> {
+    clipboard::lock_history();
+    Ok(())
+}
+
+#[tauri::command]
+async fn unlock_clipboard_history() -> Result<(), VantaError> {
+    clipboard_crypto::unlock()
+}
+
+#[tauri::command]
+async fn is_clipboard_history_locked() -> Result<bool, VantaError> {
+    Ok(clipboard_crypto::is_locked())
 }
 
 #[tauri::command]
@@ -3856,6 +4601,20 @@ async fn delete_clipboard_item(id: i64) -> Result<(), VantaError> {
     Ok(clipboard::delete_item(id).map_err(|e| format!("Failed to delete: {}", e))?)
 }
 
+#[tauri::command]
+async fn restore_clipboard_item(id: i64, as_html: Option<bool>) -> Result<(), VantaError> {
+    tokio::task::spawn_blocking(move || clipboard::restore_item(id, as_html.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Clipboard task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_clipboard_image(id: i64, thumbnail: Option<bool>) -> Result<String, VantaError> {
+    tokio::task::spawn_blocking(move || clipboard::image_data_url(id, thumbnail.unwrap_or(false)))
+        .await
+        .map_err(|e| format!("Clipboard task failed: {}", e))?
+}
+
 #[tauri::command]
 async fn toggle_clipboard_pin(id: i64) -> Result<bool, VantaError> {
     Ok(clipboard::toggle_pin(id).map_err(|e| format!("Failed to toggle pin: {}", e))?)
@@ -3928,6 +4687,50 @@ async fn open_external_link(url: String) -> Result<(), VantaError> {
     Ok(())
 }
 
+#[tauri::command]
+async fn list_browser_profiles(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<browser::BrowserProfile>, VantaError> {
+    let cfg = state
+        .config
+        .read()
+        .map_err(|_| "Failed to access config".to_string())?
+        .browser
+        .clone();
+    Ok(tokio::task::spawn_blocking(move || browser::discover_profiles(&cfg))
+        .await
+        .map_err(|e| format!("Browser profile discovery failed: {}", e))?)
+}
+
+#[derive(Serialize)]
+struct CurrencyRatesInfo {
+    provider: String,
+    base: String,
+    date: Option<String>,
+    currencies: usize,
+}
+
+#[tauri::command]
+async fn refresh_currency_rates(
+    state: tauri::State<'_, AppState>,
+) -> Result<CurrencyRatesInfo, VantaError> {
+    let cfg = state
+        .config
+        .read()
+        .map_err(|_| "Failed to access config".to_string())?
+        .currency
+        .clone();
+    let table = tokio::task::spawn_blocking(move || currency::refresh(&config::config_dir(), &cfg))
+        .await
+        .map_err(|e| format!("Currency refresh task failed: {}", e))??;
+    Ok(CurrencyRatesInfo {
+        provider: table.provider,
+        base: table.base,
+        date: table.date.map(|d| d.to_string()),
+        currencies: table.rates.len() + 1,
+    })
+}
+
 #[tauri::command]
 async fn reveal_in_file_manager(
     path: String,
@@ -4012,6 +4815,16 @@ async fn open_with_editor(
         return Err("Cannot open directory with editor".into());
     }
 
+    open_in_editor(&path, &state, &app_handle)
+}
+
+/// Opens `path` with the configured file editor. Callers check what kind of path
+/// they allow; repositories use this to open their working tree.
+fn open_in_editor(
+    path: &str,
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), VantaError> {
     let editor_id = {
         state
             .config
@@ -4023,7 +4836,7 @@ async fn open_with_editor(
     };
 
     if editor_id == "default" {
-        open::that(&path).map_err(|e| format!("Failed to open path: {}", e))?;
+        open::that(path).map_err(|e| format!("Failed to open path: {}", e))?;
         return Ok(());
     }
 
@@ -4050,11 +4863,11 @@ async fn open_with_editor(
             final_exec = format!("{} \"{}\"", final_exec, path);
         }
 
-        launcher::launch(&final_exec, Some(&app_handle))
+        launcher::launch(&final_exec, Some(app_handle))
             .map_err(|e| format!("Failed to launch editor: {}", e))?;
     } else {
         log::warn!("Custom editor '{}' not found, falling back to default.", editor_id);
-        open::that(&path).map_err(|e| format!("Failed to open path: {}", e))?;
+        open::that(path).map_err(|e| format!("Failed to open path: {}", e))?;
     }
 
     Ok(())
@@ -4189,7 +5002,6 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
     if let Err(e) = clipboard::init_db() {
         log::error!("Failed to init clipboard DB: {}", e);
     }
-    clipboard::start_watcher();
 
     let mut vanta_config = config::load_or_create_default();
 
@@ -4235,6 +5047,9 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
         vanta_config.window.height
     );
 
+    clipboard_backend::init(vanta_config.clipboard.backend);
+    clipboard::start_watcher(vanta_config.clipboard.clone());
+
     let hotkey_str = vanta_config.general.hotkey.clone();
 
     let apps: Vec<AppEntry> = Vec::new();
@@ -4249,6 +5064,8 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
     } else {
         History::new()
     };
+    let shell_history = shell::CommandHistory::load_or_create(&config::config_dir());
+    let calc_session = calc_session::CalcSession::load_or_create(&config::config_dir());
 
     let file_index: files::FileIndex = std::sync::Arc::new(Mutex::new(files::FileIndexState::default()));
     let macro_jobs = load_jobs_from_disk();
@@ -4258,6 +5075,8 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
         config: RwLock::new(vanta_config),
         extensions_cache: Mutex::new(discovered_extensions),
         history: Mutex::new(history),
+        shell_history: Mutex::new(shell_history),
+        calc_session: Mutex::new(calc_session),
         file_index: file_index.clone(),
         macro_jobs: Mutex::new(macro_jobs),
         canceled_jobs: Mutex::new(HashSet::new()),
@@ -4314,6 +5133,10 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
             switch_profile,
             export_profile,
             import_profile,
+            save_snippet,
+            delete_snippet,
+            export_snippets,
+            import_snippets,
             search,
             search_v3,
             save_query_history,
@@ -4366,13 +5189,20 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
             get_suggestions_v3,
             run_contract_migration,
             get_search_diagnostics,
+            list_browser_profiles,
+            refresh_currency_rates,
             get_health_dashboard,
             get_usage_analytics,
             create_support_bundle,
             get_recovery_hints,
             get_clipboard_history,
+            lock_clipboard_history,
+            unlock_clipboard_history,
+            is_clipboard_history_locked,
             delete_clipboard_item,
             toggle_clipboard_pin,
+            restore_clipboard_item,
+            get_clipboard_image,
             open_external_link,
             open_path,
             reveal_in_file_manager,
@@ -4545,6 +5375,16 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
                 extensions::watch_extensions(handle_for_extensions);
             });
 
+            let handle_for_recent = app_handle.clone();
+            std::thread::spawn(move || {
+                recent::watch_recent_files(handle_for_recent);
+            });
+
+            let handle_for_path = app_handle.clone();
+            std::thread::spawn(move || {
+                shell::watch_path_dirs(handle_for_path);
+            });
+
             // Initial app + extension scans in background
             {
                 let handle_for_initial_scan = app_handle.clone();
@@ -4592,6 +5432,7 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
                     log::info!("Building file index...");
                     let index_state = files::build_index(&files_config);
                     log::info!("File index ready: {} entries", index_state.entries.len());
+                    repos::cached_repos(&files_config);
                     if let Ok(mut guard) = index_clone.lock() {
                         *guard = index_state.clone();
                     }
@@ -4604,6 +5445,32 @@ pub fn run(start_hidden: bool, open_clipboard: bool) {
                 });
             }
 
+            // Warm the browser bookmarks/history cache so the first query stays fast.
+            {
+                let (web_enabled, browser_config) = {
+                    let state = app_handle.state::<AppState>();
+                    match state.config.read() {
+                        Ok(cfg) => (cfg.search.web.enabled, cfg.browser.clone()),
+                        Err(_) => (false, crate::config::BrowserSourceConfig::default()),
+                    }
+                };
+                if web_enabled {
+                    browser::cached_entries(&browser_config);
+                }
+            }
+
+            // Currency rates: cached file first, then refresh in the background when due.
+            {
+                currency::init(&config::config_dir());
+                let handle_for_rates = app_handle.clone();
+                currency::spawn_refresh_worker(config::config_dir(), move || {
+                    handle_for_rates
+                        .try_state::<AppState>()
+                        .and_then(|state| state.config.read().ok().map(|cfg| cfg.currency.clone()))
+                        .unwrap_or_default()
+                });
+            }
+
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -40,6 +40,10 @@ pub enum ResultSource {
     Window,
     File,
     Clipboard,
+    Web,
+    Recent,
+    Emoji,
+    Transform,
     Extension { ext_id: String },
 }
 
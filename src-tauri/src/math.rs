@@ -1,19 +1,22 @@
 use chrono::Utc;
-use chrono_tz::Tz;
 use meval;
+use num_traits::ToPrimitive;
 use regex::Regex;
 use std::sync::LazyLock;
 
+use crate::calc_session::Scope;
+use crate::config::CalculatorConfig;
+use crate::currency::{self, RateStamp};
+use crate::datetime::resolve_timezone;
+use crate::exact;
+use crate::programmer;
+use crate::units;
+
 static BASE_LITERAL_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)\b0x[0-9a-f_]+\b|\b0b[01_]+\b|\b0o[0-7_]+\b")
         .expect("base literal regex must be valid")
 });
 
-static UNIT_CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)^\s*([-+]?[0-9]*\.?[0-9]+)\s*(km|mi|kg|lb)\s*(?:to|in)?\s*(km|mi|kg|lb)\s*$")
-        .expect("unit conversion regex must be valid")
-});
-
 static CURRENCY_CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"(?i)^\s*([-+]?[0-9]*\.?[0-9]+)\s*([a-z]{3})\s*(?:to|in)?\s*([a-z]{3})\s*$")
         .expect("currency conversion regex must be valid")
@@ -24,42 +27,31 @@ static TIMEZONE_QUERY_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("timezone regex must be valid")
 });
 
-fn convert_units(value: f64, from: &str, to: &str) -> Option<f64> {
-    match (from, to) {
-        ("km", "mi") => Some(value * 0.621_371),
-        ("mi", "km") => Some(value / 0.621_371),
-        ("kg", "lb") => Some(value * 2.204_622_621_8),
-        ("lb", "kg") => Some(value / 2.204_622_621_8),
-        _ => None,
-    }
+/// A calculator answer plus, for currency conversions, where the rate came from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalcOutput {
+    pub display: String,
+    pub copy_value: String,
+    /// The numeric result, bound to `ans` when the calculation is committed.
+    pub value: Option<f64>,
+    pub rates: Option<RateStamp>,
+    /// Extra copy actions as `(label, value)`, e.g. hex/bin/oct in programmer mode.
+    pub alternates: Vec<(&'static str, String)>,
 }
 
-fn usd_per_unit(currency: &str) -> Option<f64> {
-    match currency {
-        "USD" => Some(1.0),
-        "EUR" => Some(1.09),
-        "GBP" => Some(1.28),
-        "JPY" => Some(0.0067),
-        "INR" => Some(0.012),
-        "CNY" => Some(0.14),
-        "CAD" => Some(0.74),
-        "AUD" => Some(0.66),
-        "CHF" => Some(1.11),
-        "SEK" => Some(0.096),
-        "NOK" => Some(0.094),
-        "SGD" => Some(0.74),
-        "AED" => Some(0.272),
-        _ => None,
+impl CalcOutput {
+    fn plain(display: String, value: f64) -> Self {
+        Self {
+            copy_value: display.clone(),
+            display,
+            value: Some(value),
+            rates: None,
+            alternates: Vec::new(),
+        }
     }
 }
 
-fn convert_currency(value: f64, from: &str, to: &str) -> Option<f64> {
-    let from_usd = usd_per_unit(from)?;
-    let to_usd = usd_per_unit(to)?;
-    Some(value * from_usd / to_usd)
-}
-
-fn format_number(value: f64) -> String {
+pub(crate) fn format_number(value: f64) -> String {
     if value.fract().abs() < f64::EPSILON {
         format!("{:.0}", value)
     } else {
@@ -70,37 +62,26 @@ fn format_number(value: f64) -> String {
     }
 }
 
-fn resolve_timezone_alias(raw: &str) -> Option<Tz> {
-    let key = raw.trim().to_ascii_lowercase();
-    let alias = match key.as_str() {
-        "utc" | "gmt" => "UTC",
-        "london" => "Europe/London",
-        "new york" | "nyc" => "America/New_York",
-        "los angeles" | "la" => "America/Los_Angeles",
-        "san francisco" | "sf" => "America/Los_Angeles",
-        "chicago" => "America/Chicago",
-        "tokyo" => "Asia/Tokyo",
-        "seoul" => "Asia/Seoul",
-        "singapore" => "Asia/Singapore",
-        "shanghai" => "Asia/Shanghai",
-        "hong kong" => "Asia/Hong_Kong",
-        "delhi" | "india" | "ist" => "Asia/Kolkata",
-        "dubai" => "Asia/Dubai",
-        "berlin" => "Europe/Berlin",
-        "paris" => "Europe/Paris",
-        "sydney" => "Australia/Sydney",
-        _ => return None,
-    };
-    alias.parse::<Tz>().ok()
+/// Like `format_number`, but switches to scientific notation for magnitudes that
+/// six decimals can't show (`1 nm to km`, `1 ly to mm`).
+fn format_quantity(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e15).contains(&magnitude) {
+        let formatted = format!("{:.6e}", value);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+    format_number(value)
 }
 
-fn resolve_timezone(raw: &str) -> Option<Tz> {
-    if let Some(tz) = resolve_timezone_alias(raw) {
-        return Some(tz);
+/// Float results (`sqrt(2)`, `sin(1)`) rounded like exact ones.
+fn format_float(value: f64, config: &CalculatorConfig) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-4..1e15).contains(&magnitude) {
+        return format_quantity(value);
     }
-
-    let cleaned = raw.trim().replace(' ', "_");
-    cleaned.parse::<Tz>().ok()
+    exact::format_f64(value, config).unwrap_or_else(|| format_number(value))
 }
 
 pub fn evaluate_timezone_display(query: &str) -> Option<(String, String)> {
@@ -115,35 +96,85 @@ pub fn evaluate_timezone_display(query: &str) -> Option<(String, String)> {
 }
 
 pub fn evaluate_display(expression: &str) -> Option<(String, String)> {
+    evaluate_detailed(expression).map(|out| (out.display, out.copy_value))
+}
+
+pub fn evaluate_detailed(expression: &str) -> Option<CalcOutput> {
+    evaluate_scoped(expression, &Scope::empty())
+}
+
+/// Like `evaluate_detailed`, with session variables, `ans` and user functions in scope.
+pub fn evaluate_scoped(expression: &str, scope: &Scope) -> Option<CalcOutput> {
     let trimmed = expression.trim();
     if trimmed.is_empty() {
         return None;
     }
 
-    if let Some(caps) = UNIT_CONVERSION_RE.captures(trimmed) {
-        let raw_value = caps.get(1)?.as_str().parse::<f64>().ok()?;
-        let from = caps.get(2)?.as_str().to_ascii_lowercase();
-        let to = caps.get(3)?.as_str().to_ascii_lowercase();
-        let converted = convert_units(raw_value, &from, &to)?;
-        let display = format!("{} {}", format_number(converted), to);
-        return Some((display.clone(), display));
+    if let Some(result) = programmer::evaluate(trimmed) {
+        return Some(CalcOutput {
+            display: result.display(),
+            copy_value: result.primary(),
+            value: Some(result.value as f64),
+            rates: None,
+            alternates: result.alternates(),
+        });
+    }
+
+    let operand = |raw: &str| evaluate_operand(raw, scope);
+    if let Some((converted, unit)) = units::evaluate_conversion(trimmed, operand) {
+        let display = format!("{} {}", format_quantity(converted), unit);
+        return Some(CalcOutput::plain(display, converted));
     }
 
     if let Some(caps) = CURRENCY_CONVERSION_RE.captures(trimmed) {
         let raw_value = caps.get(1)?.as_str().parse::<f64>().ok()?;
         let from = caps.get(2)?.as_str().to_ascii_uppercase();
         let to = caps.get(3)?.as_str().to_ascii_uppercase();
-        let converted = convert_currency(raw_value, &from, &to)?;
-        let display = format!("{} {}", format_number(converted), to);
-        return Some((display.clone(), display));
+        let rates = currency::current();
+        let converted = rates.convert(raw_value, &from, &to)?;
+        return Some(CalcOutput {
+            rates: Some(rates.stamp()),
+            ..CalcOutput::plain(format!("{} {}", format_number(converted), to), converted)
+        });
+    }
+
+    if let Some(output) = evaluate_exact(trimmed, scope) {
+        return Some(output);
     }
 
-    let val = evaluate(trimmed)?;
-    let out = format_number(val);
-    Some((out.clone(), out))
+    let val = evaluate_in(trimmed, scope)?;
+    Some(CalcOutput::plain(format_float(val, scope.config()), val))
 }
 
-fn parse_base_literal(token: &str) -> Option<u64> {
+/// Rational evaluation for plain arithmetic and percentages; `None` hands
+/// anything transcendental to `meval`.
+fn evaluate_exact(expression: &str, scope: &Scope) -> Option<CalcOutput> {
+    if !looks_like_math(expression, scope) {
+        return None;
+    }
+    let normalized = normalize_base_literals(expression)?;
+    let lookup = |name: &str| scope.session_value(name).and_then(exact::from_f64);
+    let q = exact::evaluate(&normalized, &lookup)?;
+    let formatted = exact::format(&q, scope.config());
+    Some(CalcOutput {
+        display: formatted.display,
+        copy_value: formatted.copy_value,
+        value: q.to_f64(),
+        rates: None,
+        alternates: formatted
+            .fraction
+            .map(|fraction| vec![("Copy Fraction", fraction)])
+            .unwrap_or_default(),
+    })
+}
+
+/// If the query has no digits, it's probably not a calculation we want to show,
+/// unless it uses a session variable (`rate * rate`, `ans`).
+fn looks_like_math(expression: &str, scope: &Scope) -> bool {
+    expression.chars().any(|c| c.is_ascii_digit()) || scope.mentions_session_names(expression)
+}
+
+pub(crate) fn parse_base_literal(token: &str) -> Option<u64> {
     let lower = token.to_ascii_lowercase();
     if let Some(rest) = lower.strip_prefix("0x") {
         let cleaned = rest.replace('_', "");
@@ -175,6 +206,15 @@ fn normalize_base_literals(expression: &str) -> Option<String> {
     Some(normalized)
 }
 
+/// Evaluates the numeric part of a unit conversion. Unlike `evaluate` it accepts
+/// digit-free input such as `pi`, since the unit already marks it as a calculation.
+fn evaluate_operand(expression: &str, scope: &Scope) -> Option<f64> {
+    let normalized = normalize_base_literals(expression.trim())?;
+    meval::eval_str_with_context(&normalized, scope)
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
 /// Evaluates a mathematical expression string.
 /// Returns Some(result) if the expression is valid and calculation succeeds.
 /// Returns None if the expression is invalid or empty.
@@ -182,6 +222,10 @@ fn normalize_base_literals(expression: &str) -> Option<String> {
 /// This function performs a pre-check to ensure the query looks like math
 /// to avoid false positives on normal text searches.
 pub fn evaluate(expression: &str) -> Option<f64> {
+    evaluate_in(expression, &Scope::empty())
+}
+
+fn evaluate_in(expression: &str, scope: &Scope) -> Option<f64> {
     let trimmed = expression.trim();
     if trimmed.is_empty() {
         return None;
@@ -202,14 +246,13 @@ pub fn evaluate(expression: &str) -> Option<f64> {
     // "pi" works.
 
     // Critical: Filter out queries that are obviously not math.
-    // If the query has no digits, it's probably not a calculation we want to show.
-    if !trimmed.chars().any(|c| c.is_ascii_digit()) {
+    if !looks_like_math(trimmed, scope) {
         return None;
     }
 
     let normalized = normalize_base_literals(trimmed)?;
 
-    match meval::eval_str(&normalized) {
+    match meval::eval_str_with_context(&normalized, scope) {
         Ok(val) => {
             // Filter out infinite or NaN results
             if val.is_infinite() || val.is_nan() {
@@ -225,7 +268,8 @@ pub fn evaluate(expression: &str) -> Option<f64> {
 #[cfg(test)]
 mod tests {
     use super::{
-        evaluate, evaluate_display, evaluate_timezone_display, resolve_timezone,
+        evaluate, evaluate_detailed, evaluate_display, evaluate_timezone_display,
+        resolve_timezone,
     };
 
     #[test]
@@ -248,10 +292,19 @@ mod tests {
         assert_eq!(evaluate("0b102"), None);
     }
 
+    #[test]
+    fn prefers_exact_arithmetic_over_floats() {
+        assert_eq!(evaluate_display("0.1 + 0.2").unwrap().0, "0.3");
+        assert_eq!(evaluate_display("15% of 80").unwrap().0, "12");
+        let third = evaluate_detailed("1 / 3").unwrap();
+        assert_eq!(third.alternates, vec![("Copy Fraction", "1/3".to_string())]);
+        assert_eq!(evaluate_display("sqrt(2)").unwrap().0, "1.4142135624");
+    }
+
     #[test]
     fn converts_km_to_mi() {
         let out = evaluate_display("10 km to mi").expect("conversion result");
-        assert_eq!(out.0, "6.21371 mi");
+        assert_eq!(out.0, "6.213712 mi");
     }
 
     #[test]
@@ -260,6 +313,16 @@ mod tests {
         assert_eq!(out.0, "4.535924 kg");
     }
 
+    #[test]
+    fn converts_expression_with_compound_units() {
+        let out = evaluate_display("(2+3) ft to cm").expect("conversion result");
+        assert_eq!(out.0, "152.4 cm");
+        let out = evaluate_display("3 GiB in MB").expect("conversion result");
+        assert_eq!(out.0, "3221.225472 MB");
+        let out = evaluate_display("1 nm to km").expect("conversion result");
+        assert_eq!(out.0, "1e-12 km");
+    }
+
     #[test]
     fn evaluate_display_falls_back_to_math() {
         let out = evaluate_display("2 + 2").expect("math result");
@@ -297,6 +360,13 @@ mod tests {
         assert_eq!(out.0, "16.75 USD");
     }
 
+    #[test]
+    fn currency_results_carry_rate_stamp() {
+        let out = evaluate_detailed("10 usd to eur").expect("currency output");
+        assert!(out.rates.is_some());
+        assert!(evaluate_detailed("2 + 2").unwrap().rates.is_none());
+    }
+
     #[test]
     fn rejects_unknown_currency() {
         assert_eq!(evaluate_display("10 aaa usd"), None);
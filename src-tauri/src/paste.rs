@@ -0,0 +1,359 @@
+//! The "Paste" action: once Vanta hides, focus goes back to the window that
+//! was active before it opened and the text is pasted there, either by
+//! pressing the paste chord or by typing it. Terminals get their own chord
+//! since most of them read Ctrl+V as a literal character.
+
+use std::time::Duration;
+
+use crate::config::{PasteConfig, PasteMode};
+use crate::errors::VantaError;
+use crate::launcher;
+use crate::windows;
+
+/// Time between asking the compositor for focus and sending keys.
+const FOCUS_SETTLE: Duration = Duration::from_millis(60);
+
+/// Linux input event code of the Left arrow key, for ydotool.
+const LEFT_EVDEV_CODE: u16 = 105;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ctrl" | "control" => Some(Self::Ctrl),
+            "shift" => Some(Self::Shift),
+            "alt" => Some(Self::Alt),
+            "super" | "logo" | "meta" | "win" => Some(Self::Super),
+            _ => None,
+        }
+    }
+
+    fn wtype_name(self) -> &'static str {
+        match self {
+            Self::Ctrl => "ctrl",
+            Self::Shift => "shift",
+            Self::Alt => "alt",
+            Self::Super => "logo",
+        }
+    }
+
+    fn xdotool_name(self) -> &'static str {
+        match self {
+            Self::Ctrl => "ctrl",
+            Self::Shift => "shift",
+            Self::Alt => "alt",
+            Self::Super => "super",
+        }
+    }
+
+    /// Linux input event code of the left-hand key, for ydotool.
+    fn evdev_code(self) -> u16 {
+        match self {
+            Self::Ctrl => 29,
+            Self::Shift => 42,
+            Self::Alt => 56,
+            Self::Super => 125,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    /// A letter or digit.
+    Char(char),
+    Insert,
+}
+
+impl Key {
+    fn parse(name: &str) -> Option<Self> {
+        if name == "insert" || name == "ins" {
+            return Some(Self::Insert);
+        }
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphanumeric() => Some(Self::Char(c)),
+            _ => None,
+        }
+    }
+
+    fn keysym(self) -> String {
+        match self {
+            Self::Char(c) => c.to_string(),
+            Self::Insert => "Insert".to_string(),
+        }
+    }
+
+    fn evdev_code(self) -> u16 {
+        const ROWS: [(&str, u16); 4] = [
+            ("1234567890", 2),
+            ("qwertyuiop", 16),
+            ("asdfghjkl", 30),
+            ("zxcvbnm", 44),
+        ];
+        match self {
+            Self::Insert => 110,
+            Self::Char(c) => ROWS
+                .iter()
+                .find_map(|(row, first)| row.find(c).map(|i| first + i as u16))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Tools that can press keys in the focused window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyTool {
+    Wtype,
+    Ydotool,
+    Xdotool,
+}
+
+impl KeyTool {
+    /// Same preference as `launcher::type_text_after`.
+    fn detect() -> Option<Self> {
+        [
+            (Self::Wtype, "wtype"),
+            (Self::Ydotool, "ydotool"),
+            (Self::Xdotool, "xdotool"),
+        ]
+        .into_iter()
+        .find(|(_, bin)| launcher::on_path(bin))
+        .map(|(tool, _)| tool)
+    }
+
+    fn bin(self) -> &'static str {
+        match self {
+            Self::Wtype => "wtype",
+            Self::Ydotool => "ydotool",
+            Self::Xdotool => "xdotool",
+        }
+    }
+}
+
+/// A key combination such as `ctrl+shift+v`.
+#[derive(Debug, PartialEq)]
+pub struct Chord {
+    modifiers: Vec<Modifier>,
+    key: Key,
+}
+
+impl Chord {
+    pub fn parse(chord: &str) -> Result<Self, VantaError> {
+        let invalid = || VantaError::from(format!("Invalid paste chord '{}'", chord));
+        let lower = chord.to_lowercase();
+        let mut parts = lower.split('+').map(str::trim).collect::<Vec<_>>();
+        let key = parts.pop().and_then(Key::parse).ok_or_else(invalid)?;
+        let modifiers = parts
+            .into_iter()
+            .map(Modifier::parse)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        Ok(Self { modifiers, key })
+    }
+
+    /// Arguments that make `tool` press the chord, then Left `left` times to
+    /// move the cursor back into the pasted text.
+    pub fn args(&self, tool: KeyTool, left: usize) -> Vec<String> {
+        match tool {
+            KeyTool::Wtype => {
+                let mut args = Vec::new();
+                for modifier in &self.modifiers {
+                    args.extend(["-M".to_string(), modifier.wtype_name().to_string()]);
+                }
+                args.extend(["-k".to_string(), self.key.keysym()]);
+                for modifier in self.modifiers.iter().rev() {
+                    args.extend(["-m".to_string(), modifier.wtype_name().to_string()]);
+                }
+                for _ in 0..left {
+                    args.extend(["-k".to_string(), "Left".to_string()]);
+                }
+                args
+            }
+            KeyTool::Ydotool => {
+                let codes = self
+                    .modifiers
+                    .iter()
+                    .map(|modifier| modifier.evdev_code())
+                    .chain([self.key.evdev_code()])
+                    .collect::<Vec<_>>();
+                let mut args = vec!["key".to_string()];
+                args.extend(codes.iter().map(|code| format!("{}:1", code)));
+                args.extend(codes.iter().rev().map(|code| format!("{}:0", code)));
+                for _ in 0..left {
+                    args.extend([
+                        format!("{}:1", LEFT_EVDEV_CODE),
+                        format!("{}:0", LEFT_EVDEV_CODE),
+                    ]);
+                }
+                args
+            }
+            KeyTool::Xdotool => {
+                let combo = self
+                    .modifiers
+                    .iter()
+                    .map(|modifier| modifier.xdotool_name().to_string())
+                    .chain([self.key.keysym()])
+                    .collect::<Vec<_>>()
+                    .join("+");
+                let mut args = vec!["key".to_string(), "--clearmodifiers".to_string(), combo];
+                args.extend(std::iter::repeat_n("Left".to_string(), left));
+                args
+            }
+        }
+    }
+}
+
+pub fn is_terminal(class: &str, config: &PasteConfig) -> bool {
+    config
+        .terminal_classes
+        .iter()
+        .any(|terminal| terminal.trim().eq_ignore_ascii_case(class))
+}
+
+/// The chord to paste into a window of `class`.
+pub fn chord_for<'a>(class: Option<&str>, config: &'a PasteConfig) -> &'a str {
+    match class {
+        Some(class) if is_terminal(class, config) => &config.terminal_chord,
+        _ => &config.chord,
+    }
+}
+
+/// Focuses the remembered window after `delay`, giving the launcher time to
+/// hide. Returns its class so the caller can pick a chord.
+fn refocus_previous_after(delay: Duration) -> Option<String> {
+    let target = windows::previous_window()?;
+    let class = target.class.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        if let Err(e) = launcher::focus_window(&target.address) {
+            log::warn!("Failed to refocus {} before pasting: {}", target.class, e);
+        }
+    });
+    Some(class)
+}
+
+/// Presses the paste chord in the previously focused window, pasting whatever
+/// is on the clipboard, then moves the cursor `cursor_back` characters left.
+/// Call after hiding the launcher.
+pub fn press_paste(config: &PasteConfig, cursor_back: usize) -> Result<(), VantaError> {
+    let tool = KeyTool::detect()
+        .ok_or("No key tool found to paste with (install wtype, ydotool or xdotool)")?;
+    let delay = Duration::from_millis(config.delay_ms);
+    let class = refocus_previous_after(delay);
+    let args = Chord::parse(chord_for(class.as_deref(), config))?.args(tool, cursor_back);
+
+    std::thread::spawn(move || {
+        std::thread::sleep(delay + FOCUS_SETTLE);
+        if let Err(e) = launcher::spawn_cmd(tool.bin(), &args) {
+            log::warn!("Failed to press paste chord with {}: {}", tool.bin(), e);
+        }
+    });
+    Ok(())
+}
+
+/// Pastes `text` into the previously focused window the way `config.mode` asks.
+/// The cursor is only moved back in keystroke mode, since typing has no
+/// known end time to wait for.
+pub fn paste_text(text: &str, cursor_back: usize, config: &PasteConfig) -> Result<(), VantaError> {
+    match config.mode {
+        PasteMode::Keystroke => {
+            launcher::copy_to_clipboard(text)?;
+            press_paste(config, cursor_back)
+        }
+        PasteMode::Type => {
+            let delay = Duration::from_millis(config.delay_ms);
+            refocus_previous_after(delay);
+            launcher::type_text_after(text, delay + FOCUS_SETTLE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chord_for, Chord, KeyTool};
+    use crate::config::PasteConfig;
+
+    fn args(chord: &str, tool: KeyTool) -> String {
+        Chord::parse(chord).unwrap().args(tool, 0).join(" ")
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert!(Chord::parse("Ctrl+Shift+V").is_ok());
+        assert!(Chord::parse(" shift + insert ").is_ok());
+        assert!(Chord::parse("v").is_ok());
+        for invalid in ["", "ctrl+", "hyper+v", "ctrl+f12", "ctrl+vv"] {
+            assert!(
+                Chord::parse(invalid).is_err(),
+                "{:?} should not parse",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn builds_arguments_for_each_tool() {
+        assert_eq!(
+            args("ctrl+shift+v", KeyTool::Wtype),
+            "-M ctrl -M shift -k v -m shift -m ctrl"
+        );
+        assert_eq!(
+            args("super+insert", KeyTool::Wtype),
+            "-M logo -k Insert -m logo"
+        );
+        assert_eq!(
+            args("ctrl+shift+v", KeyTool::Ydotool),
+            "key 29:1 42:1 47:1 47:0 42:0 29:0"
+        );
+        assert_eq!(
+            args("shift+insert", KeyTool::Ydotool),
+            "key 42:1 110:1 110:0 42:0"
+        );
+        assert_eq!(args("alt+1", KeyTool::Ydotool), "key 56:1 2:1 2:0 56:0");
+        assert_eq!(
+            args("ctrl+shift+v", KeyTool::Xdotool),
+            "key --clearmodifiers ctrl+shift+v"
+        );
+    }
+
+    #[test]
+    fn moves_the_cursor_back_after_pasting() {
+        let chord = Chord::parse("ctrl+v").unwrap();
+        assert_eq!(
+            chord.args(KeyTool::Wtype, 2).join(" "),
+            "-M ctrl -k v -m ctrl -k Left -k Left"
+        );
+        assert_eq!(
+            chord.args(KeyTool::Ydotool, 1).join(" "),
+            "key 29:1 47:1 47:0 29:0 105:1 105:0"
+        );
+        assert_eq!(
+            chord.args(KeyTool::Xdotool, 2).join(" "),
+            "key --clearmodifiers ctrl+v Left Left"
+        );
+    }
+
+    #[test]
+    fn terminals_get_their_own_chord() {
+        let config = PasteConfig::default();
+        assert_eq!(chord_for(Some("kitty"), &config), "ctrl+shift+v");
+        assert_eq!(chord_for(Some("ALACRITTY"), &config), "ctrl+shift+v");
+        assert_eq!(chord_for(Some("firefox"), &config), "ctrl+v");
+        assert_eq!(chord_for(None, &config), "ctrl+v");
+
+        let custom = PasteConfig {
+            chord: "shift+insert".to_string(),
+            terminal_classes: vec!["my-term".to_string()],
+            ..PasteConfig::default()
+        };
+        assert_eq!(chord_for(Some("kitty"), &custom), "shift+insert");
+        assert_eq!(chord_for(Some("my-term"), &custom), "ctrl+shift+v");
+    }
+}
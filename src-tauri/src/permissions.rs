@@ -5,12 +5,16 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Emitter;
 
 const MAX_BLOCK_EVENTS: usize = 200;
 const MAX_AUDIT_EVENTS: usize = 500;
 
+/// Bumped on every save so callers can cache decisions between writes.
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
 pub enum Capability {
     Network,
@@ -139,10 +143,16 @@ pub fn save_permissions(store: &PermissionsStore) -> Result<(), VantaError> {
 
     fs::rename(&tmp_path, &path)
         .map_err(|e| format!("Failed to replace permissions.json: {}", e))?;
+    REVISION.fetch_add(1, Ordering::Relaxed);
 
     Ok(())
 }
 
+/// Changes whenever this process saves the store.
+pub fn revision() -> u64 {
+    REVISION.load(Ordering::Relaxed)
+}
+
 /// Ensure new scripts have a seed decision so capability prompts work immediately.
 /// Only inserts when no decision exists; it never overwrites user choices.
 pub fn seed_missing_decisions(seeds: &[(String, Vec<Capability>)]) -> Result<(), VantaError> {
@@ -0,0 +1,471 @@
+//! Exact integer evaluation for the calculator.
+//!
+//! `meval` works on `f64`, which silently rounds anything above 2^53. Integer-only
+//! expressions are evaluated here on `i128` instead. Programmer mode kicks in when
+//! the query uses bitwise operators (`& | ^ ~ << >>`), `mod`/`xor`, a `0x`/`0b`/`0o`
+//! literal, a width (`as u8` … `as i64`) or a base (`in hex`); it then shows the
+//! result in every base. Outside programmer mode `^` stays exponentiation.
+
+use crate::math::parse_base_literal;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl Width {
+    fn parse(raw: &str) -> Option<Self> {
+        Some(match raw {
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+        }
+    }
+
+    fn bits(self) -> u32 {
+        match self {
+            Self::I8 | Self::U8 => 8,
+            Self::I16 | Self::U16 => 16,
+            Self::I32 | Self::U32 => 32,
+            Self::I64 | Self::U64 => 64,
+        }
+    }
+
+    fn signed(self) -> bool {
+        matches!(self, Self::I8 | Self::I16 | Self::I32 | Self::I64)
+    }
+
+    /// Two's-complement wrap of `value` into this width.
+    fn wrap(self, value: i128) -> i128 {
+        let bits = self.bits();
+        let masked = value & ((1i128 << bits) - 1);
+        if self.signed() && masked >> (bits - 1) == 1 {
+            masked - (1i128 << bits)
+        } else {
+            masked
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Radix {
+    Dec,
+    Hex,
+    Bin,
+    Oct,
+}
+
+impl Radix {
+    fn parse(raw: &str) -> Option<Self> {
+        Some(match raw {
+            "dec" | "decimal" => Self::Dec,
+            "hex" | "hexadecimal" => Self::Hex,
+            "bin" | "binary" => Self::Bin,
+            "oct" | "octal" => Self::Oct,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Num(i128),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+fn tokenize(expression: &str) -> Option<(Vec<Token>, bool)> {
+    const SYMBOLS: [&str; 12] = [
+        "**", "<<", ">>", "+", "-", "*", "/", "%", "^", "&", "|", "~",
+    ];
+    let bytes = expression.as_bytes();
+    let mut tokens = Vec::new();
+    let mut has_literal = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            let literal = &expression[start..i];
+            let value = if literal.len() > 2
+                && literal.as_bytes()[0] == b'0'
+                && literal.as_bytes()[1].is_ascii_alphabetic()
+            {
+                has_literal = true;
+                parse_base_literal(literal)? as i128
+            } else {
+                literal.replace('_', "").parse::<i128>().ok()?
+            };
+            tokens.push(Token::Num(value));
+            continue;
+        }
+        if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                i += 1;
+            }
+            tokens.push(match &expression[start..i] {
+                "mod" => Token::Op("mod"),
+                "xor" => Token::Op("xor"),
+                _ => return None,
+            });
+            continue;
+        }
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            _ => {
+                let symbol = SYMBOLS.iter().find(|s| expression[i..].starts_with(**s))?;
+                tokens.push(Token::Op(symbol));
+                i += symbol.len();
+                continue;
+            }
+        }
+        i += 1;
+    }
+    Some((tokens, has_literal))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    programmer: bool,
+    width: Option<Width>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn fit(&self, value: i128) -> i128 {
+        match self.width {
+            Some(width) => width.wrap(value),
+            None => value,
+        }
+    }
+
+    /// Binding powers, loosest first: `|`, `^`/`xor`, `&`, shifts, `+ -`, `* / %`, `**`.
+    fn infix_power(&self, op: &str) -> Option<(u8, u8)> {
+        Some(match op {
+            "|" => (1, 2),
+            "xor" => (3, 4),
+            "^" if self.programmer => (3, 4),
+            "&" => (5, 6),
+            "<<" | ">>" => (7, 8),
+            "+" | "-" => (9, 10),
+            "*" | "/" | "%" | "mod" => (11, 12),
+            "**" | "^" => (15, 14),
+            _ => return None,
+        })
+    }
+
+    fn expression(&mut self, min_power: u8) -> Option<i128> {
+        let mut lhs = match self.next()? {
+            Token::Num(value) => self.fit(value),
+            Token::Open => {
+                let inner = self.expression(0)?;
+                (self.next()? == Token::Close).then_some(inner)?
+            }
+            Token::Op("-") => {
+                let operand = self.expression(13)?;
+                self.fit(operand.checked_neg()?)
+            }
+            Token::Op("+") => self.expression(13)?,
+            Token::Op("~") => {
+                let operand = self.expression(13)?;
+                self.fit(!operand)
+            }
+            _ => return None,
+        };
+
+        while let Some(Token::Op(op)) = self.peek() {
+            let (left, right) = self.infix_power(op)?;
+            if left < min_power {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.expression(right)?;
+            lhs = self.fit(self.apply(op, lhs, rhs)?);
+        }
+        Some(lhs)
+    }
+
+    fn apply(&self, op: &str, lhs: i128, rhs: i128) -> Option<i128> {
+        let shift_limit = self.width.map_or(127, |width| width.bits() - 1);
+        match op {
+            "+" => lhs.checked_add(rhs),
+            "-" => lhs.checked_sub(rhs),
+            "*" => lhs.checked_mul(rhs),
+            // Integer division truncates in programmer mode; elsewhere it must be exact
+            // so `7 / 2` still falls through to the float path.
+            "/" if !self.programmer && lhs.checked_rem(rhs)? != 0 => None,
+            "/" => lhs.checked_div(rhs),
+            "%" | "mod" => lhs.checked_rem(rhs),
+            "**" => lhs.checked_pow(u32::try_from(rhs).ok()?),
+            "^" if !self.programmer => lhs.checked_pow(u32::try_from(rhs).ok()?),
+            "^" | "xor" => Some(lhs ^ rhs),
+            "&" => Some(lhs & rhs),
+            "|" => Some(lhs | rhs),
+            "<<" => {
+                let shift = u32::try_from(rhs).ok().filter(|s| *s <= shift_limit)?;
+                match self.width {
+                    Some(_) => Some(lhs << shift),
+                    None => lhs.checked_mul(1i128.checked_shl(shift)?),
+                }
+            }
+            ">>" => {
+                let shift = u32::try_from(rhs).ok().filter(|s| *s <= shift_limit)?;
+                Some(lhs >> shift)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An exact integer answer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntResult {
+    pub value: i128,
+    pub width: Option<Width>,
+    pub radix: Option<Radix>,
+    /// Whether to render every base (programmer mode) or just the decimal value.
+    pub programmer: bool,
+}
+
+impl IntResult {
+    /// The two's-complement bit pattern shown in hex/bin/oct; 64-bit when no width is given.
+    fn bit_pattern(&self) -> u128 {
+        let bits = self.width.map_or(64, Width::bits);
+        (self.value as u128) & ((1u128 << bits) - 1)
+    }
+
+    pub fn format(&self, radix: Radix) -> String {
+        match radix {
+            Radix::Dec => self.value.to_string(),
+            Radix::Hex => format!("0x{:x}", self.bit_pattern()),
+            Radix::Bin => format!("0b{:b}", self.bit_pattern()),
+            Radix::Oct => format!("0o{:o}", self.bit_pattern()),
+        }
+    }
+
+    /// The value copied on Enter: decimal unless a base was requested.
+    pub fn primary(&self) -> String {
+        self.format(self.radix.unwrap_or(Radix::Dec))
+    }
+
+    pub fn display(&self) -> String {
+        if !self.programmer {
+            return self.value.to_string();
+        }
+        let binary = group_binary(&format!("{:b}", self.bit_pattern()));
+        let mut display = format!(
+            "{} · {} · 0b{} · {}",
+            self.value,
+            self.format(Radix::Hex),
+            binary,
+            self.format(Radix::Oct)
+        );
+        if let Some(width) = self.width {
+            display.push_str(&format!(" ({})", width.as_str()));
+        }
+        display
+    }
+
+    /// Copy actions for each base, as `(label, value)` pairs.
+    pub fn alternates(&self) -> Vec<(&'static str, String)> {
+        if !self.programmer {
+            return Vec::new();
+        }
+        vec![
+            ("Copy Decimal", self.format(Radix::Dec)),
+            ("Copy Hex", self.format(Radix::Hex)),
+            ("Copy Binary", self.format(Radix::Bin)),
+            ("Copy Octal", self.format(Radix::Oct)),
+        ]
+    }
+}
+
+/// `11111111` → `1111_1111`, grouped in nibbles from the right.
+fn group_binary(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 4);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(4) {
+            grouped.push('_');
+        }
+        grouped.push(ch);
+    }
+    grouped
+}
+
+/// Splits a trailing `as u8`, `in hex`, `to i32 hex` style suffix off the expression.
+fn split_suffix(expression: &str) -> (&str, Option<Width>, Option<Radix>) {
+    let mut body = expression.trim_end();
+    let mut width = None;
+    let mut radix = None;
+    while let Some((head, last)) = body.rsplit_once(char::is_whitespace) {
+        let word = last.to_ascii_lowercase();
+        if let (None, Some(w)) = (width, Width::parse(&word)) {
+            width = Some(w);
+        } else if let (None, Some(r)) = (radix, Radix::parse(&word)) {
+            radix = Some(r);
+        } else if matches!(word.as_str(), "as" | "in" | "to")
+            && (width.is_some() || radix.is_some())
+        {
+            body = head.trim_end();
+            break;
+        } else {
+            break;
+        }
+        body = head.trim_end();
+    }
+    if width.is_none() && radix.is_none() {
+        return (expression, None, None);
+    }
+    (body, width, radix)
+}
+
+/// Evaluates an integer-only expression exactly, or returns `None` so the float
+/// path can take over (decimals, functions, inexact division, overflow).
+pub fn evaluate(expression: &str) -> Option<IntResult> {
+    let trimmed = expression.trim();
+    if !trimmed.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (body, width, radix) = split_suffix(trimmed);
+    let (tokens, has_literal) = tokenize(body)?;
+    let programmer = has_literal
+        || width.is_some()
+        || radix.is_some()
+        || tokens.iter().any(|token| {
+            matches!(
+                token,
+                Token::Op("&" | "|" | "~" | "<<" | ">>" | "xor" | "mod")
+            )
+        });
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        programmer,
+        width,
+    };
+    let value = parser.expression(0)?;
+    if parser.pos != parser.tokens.len() {
+        return None;
+    }
+    // Without a width, results must fit a 64-bit register (signed or unsigned).
+    if width.is_none() && (value < i64::MIN as i128 || value > u64::MAX as i128) {
+        return None;
+    }
+    Some(IntResult {
+        value,
+        width,
+        radix,
+        programmer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, Radix, Width};
+
+    #[test]
+    fn evaluates_bitwise_operators_and_shifts() {
+        assert_eq!(evaluate("0xff & 0x0f").unwrap().value, 0x0f);
+        assert_eq!(evaluate("0b1010 | 0b0101").unwrap().value, 0b1111);
+        assert_eq!(evaluate("0xff ^ 0x0f").unwrap().value, 0xf0);
+        assert_eq!(evaluate("1 << 4 | 1").unwrap().value, 17);
+        assert_eq!(evaluate("256 >> 2").unwrap().value, 64);
+        assert_eq!(evaluate("17 mod 5").unwrap().value, 2);
+        assert_eq!(evaluate("6 xor 3").unwrap().value, 5);
+        // Outside programmer mode `^` is still a power.
+        assert_eq!(evaluate("2 ^ 10").unwrap().value, 1024);
+        assert!(!evaluate("2 ^ 10").unwrap().programmer);
+    }
+
+    #[test]
+    fn wraps_to_twos_complement_widths() {
+        assert_eq!(evaluate("0xff + 1 as u8").unwrap().value, 0);
+        assert_eq!(evaluate("127 + 1 as i8").unwrap().value, -128);
+        assert_eq!(evaluate("~0 as u16").unwrap().value, 0xffff);
+        let neg = evaluate("-1 as i32").unwrap();
+        assert_eq!(neg.width, Some(Width::I32));
+        assert_eq!(neg.format(Radix::Hex), "0xffffffff");
+        assert_eq!(
+            evaluate("~0").unwrap().format(Radix::Hex),
+            "0xffffffffffffffff"
+        );
+    }
+
+    #[test]
+    fn keeps_precision_above_two_to_the_fifty_three() {
+        assert_eq!(evaluate("2^53 + 1").unwrap().value, 9_007_199_254_740_993);
+        assert_eq!(
+            evaluate("0xffffffffffffffff - 1").unwrap().value,
+            18_446_744_073_709_551_614
+        );
+        assert!(evaluate("0xffffffffffffffff + 1").is_none());
+        assert!(evaluate("7 / 2").is_none());
+        assert!(evaluate("1.5 + 1").is_none());
+        assert!(evaluate("sin(1)").is_none());
+    }
+
+    #[test]
+    fn renders_every_base_with_copy_actions() {
+        let result = evaluate("255 in hex").unwrap();
+        assert!(result.programmer);
+        assert_eq!(result.primary(), "0xff");
+        assert_eq!(result.display(), "255 · 0xff · 0b1111_1111 · 0o377");
+        let labels: Vec<_> = result
+            .alternates()
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(
+            labels,
+            ["Copy Decimal", "Copy Hex", "Copy Binary", "Copy Octal"]
+        );
+        assert_eq!(result.alternates()[2].1, "0b11111111");
+    }
+}
@@ -0,0 +1,305 @@
+use crate::config::{Quicklink, QuicklinksConfig};
+use crate::matcher::{ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+const DEFAULT_ICON: &str = "fa-solid fa-link";
+/// Shortest prefix of "quicklink" that lists every quicklink in the palette.
+const PALETTE_KEYWORD_MIN_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuicklinkTarget {
+    /// http(s) URL, opened through `open_external_link`.
+    Web(String),
+    /// File path or non-web URI (`file://`, `obsidian://`, …) handed to xdg-open.
+    Local(String),
+}
+
+fn has_placeholder(template: &str) -> bool {
+    template.contains("{query}") || template.contains("{raw}")
+}
+
+/// Substitute `{query}` (URL-encoded) and `{raw}` (verbatim) in a quicklink template.
+/// Local path templates get the raw value for `{query}` too, since encoding would
+/// break file names.
+pub fn expand_template(template: &str, args: &str) -> String {
+    let args = args.trim();
+    let encoded = if is_web_url(template) || has_uri_scheme(template) {
+        urlencoding::encode(args).into_owned()
+    } else {
+        args.to_string()
+    };
+    template.replace("{query}", &encoded).replace("{raw}", args)
+}
+
+fn is_web_url(value: &str) -> bool {
+    let lower = value.trim().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn has_uri_scheme(value: &str) -> bool {
+    let Some((scheme, _)) = value.split_once("://") else {
+        return false;
+    };
+    !scheme.is_empty()
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+}
+
+pub fn classify_target(expanded: &str) -> QuicklinkTarget {
+    let trimmed = expanded.trim();
+    if is_web_url(trimmed) {
+        return QuicklinkTarget::Web(trimmed.to_string());
+    }
+    if let Some(rest) = trimmed.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return QuicklinkTarget::Local(home.join(rest).to_string_lossy().to_string());
+        }
+    }
+    QuicklinkTarget::Local(trimmed.to_string())
+}
+
+fn find_keyword<'a>(cfg: &'a QuicklinksConfig, keyword: &str) -> Option<&'a Quicklink> {
+    cfg.entries
+        .iter()
+        .filter(|q| q.enabled)
+        .find(|q| q.keyword.eq_ignore_ascii_case(keyword))
+}
+
+fn quicklink_icon(link: &Quicklink) -> String {
+    link.icon
+        .clone()
+        .filter(|i| !i.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_ICON.to_string())
+}
+
+fn quicklink_result(link: &Quicklink, args: &str, score: u32) -> SearchResult {
+    let expanded = expand_template(&link.url, args);
+    let title = if args.trim().is_empty() {
+        link.name.clone()
+    } else {
+        format!("{}: {}", link.name, args.trim())
+    };
+    SearchResult {
+        title,
+        subtitle: Some(expanded.clone()),
+        icon: Some(quicklink_icon(link)),
+        exec: format!("quicklink:{}", expanded),
+        score,
+        match_indices: vec![],
+        source: ResultSource::Application,
+        actions: Some(vec![ActionHint {
+            label: "Copy Link".to_string(),
+            exec: format!("copy:{}", expanded),
+            shortcut: None,
+        }]),
+        id: Some(format!("quicklink:{}", link.keyword)),
+        group: None,
+        section: Some("Quicklinks".to_string()),
+    }
+}
+
+fn keyword_hint_result(link: &Quicklink, score: u32) -> SearchResult {
+    if !has_placeholder(&link.url) {
+        return quicklink_result(link, "", score);
+    }
+    SearchResult {
+        title: format!("{} ({})", link.name, link.keyword),
+        subtitle: Some(format!("Type \"{} <terms>\" to open {}", link.keyword, link.url)),
+        icon: Some(quicklink_icon(link)),
+        exec: format!("fill:{} ", link.keyword),
+        score,
+        match_indices: vec![],
+        source: ResultSource::Application,
+        actions: None,
+        id: Some(format!("quicklink-hint:{}", link.keyword)),
+        group: None,
+        section: Some("Quicklinks".to_string()),
+    }
+}
+
+/// Results for `<keyword> <args>` queries plus keyword/name hints while typing.
+pub fn build_quicklink_results(query: &str, cfg: &QuicklinksConfig, weight: u32) -> Vec<SearchResult> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+
+    let (keyword, args) = trimmed
+        .split_once(char::is_whitespace)
+        .map(|(k, a)| (k, a.trim()))
+        .unwrap_or((trimmed, ""));
+
+    if !args.is_empty() {
+        if let Some(link) = find_keyword(cfg, keyword) {
+            return vec![quicklink_result(
+                link,
+                args,
+                crate::weighted_score(ranking_config::QUICKLINK_MATCH_SCORE, weight),
+            )];
+        }
+    }
+
+    let needle = trimmed.to_lowercase();
+    cfg.entries
+        .iter()
+        .filter(|q| q.enabled)
+        .filter(|q| {
+            let keyword = q.keyword.to_lowercase();
+            keyword == needle
+                || (needle.len() >= 2 && keyword.starts_with(&needle))
+                || (needle.len() >= 3 && q.name.to_lowercase().contains(&needle))
+        })
+        .map(|q| {
+            let base = if q.keyword.eq_ignore_ascii_case(trimmed) {
+                ranking_config::QUICKLINK_KEYWORD_SCORE
+            } else {
+                ranking_config::QUICKLINK_HINT_SCORE
+            };
+            keyword_hint_result(q, crate::weighted_score(base, weight))
+        })
+        .collect()
+}
+
+/// "Search the web for …" result used when nothing else matched.
+pub fn build_web_fallback_result(query: &str, cfg: &QuicklinksConfig) -> Option<SearchResult> {
+    let trimmed = query.trim();
+    if !cfg.web_search_fallback || trimmed.is_empty() {
+        return None;
+    }
+    let engine = find_keyword(cfg, &cfg.fallback_keyword)
+        .or_else(|| cfg.entries.iter().find(|q| q.enabled && has_placeholder(&q.url)))?;
+    let mut result = quicklink_result(engine, trimmed, ranking_config::WEB_FALLBACK_SCORE);
+    result.title = format!("Search the web for \"{}\"", trimmed);
+    result.subtitle = Some(format!("{} · {}", engine.name, expand_template(&engine.url, trimmed)));
+    result.id = Some("quicklink:web-fallback".to_string());
+    Some(result)
+}
+
+/// Quicklinks listed in the `>` command palette.
+pub fn build_quicklink_palette_results(
+    query: &str,
+    cfg: &QuicklinksConfig,
+    weight: u32,
+) -> Vec<SearchResult> {
+    let needle = query.trim().to_lowercase();
+    cfg.entries
+        .iter()
+        .filter(|q| q.enabled)
+        .filter(|q| {
+            needle.is_empty()
+                || q.keyword.to_lowercase().contains(&needle)
+                || q.name.to_lowercase().contains(&needle)
+                || (needle.len() >= PALETTE_KEYWORD_MIN_LEN && "quicklink".starts_with(&needle))
+        })
+        .map(|q| {
+            let mut result = keyword_hint_result(
+                q,
+                crate::weighted_score(ranking_config::STORE_SEARCH_SCORE, weight),
+            );
+            result.title = format!("Quicklink: {} ({})", q.name, q.keyword);
+            result.id = Some(format!("cmd-quicklink:{}", q.keyword));
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(keyword: &str, url: &str) -> Quicklink {
+        Quicklink {
+            keyword: keyword.to_string(),
+            name: keyword.to_uppercase(),
+            url: url.to_string(),
+            icon: None,
+            enabled: true,
+        }
+    }
+
+    fn cfg(entries: Vec<Quicklink>) -> QuicklinksConfig {
+        QuicklinksConfig {
+            entries,
+            web_search_fallback: true,
+            fallback_keyword: "ddg".to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_query_url_encoded_and_raw_verbatim() {
+        assert_eq!(
+            expand_template("https://duckduckgo.com/?q={query}", "rust & tauri"),
+            "https://duckduckgo.com/?q=rust%20%26%20tauri"
+        );
+        assert_eq!(
+            expand_template("https://github.com/{raw}", "Misiix9/Vanta"),
+            "https://github.com/Misiix9/Vanta"
+        );
+        assert_eq!(
+            expand_template("~/Projects/{query}/README.md", "my app"),
+            "~/Projects/my app/README.md"
+        );
+    }
+
+    #[test]
+    fn classifies_web_and_local_targets() {
+        assert_eq!(
+            classify_target("https://example.com"),
+            QuicklinkTarget::Web("https://example.com".to_string())
+        );
+        assert_eq!(
+            classify_target("obsidian://open?file=x"),
+            QuicklinkTarget::Local("obsidian://open?file=x".to_string())
+        );
+        assert!(matches!(classify_target("/etc/hosts"), QuicklinkTarget::Local(_)));
+    }
+
+    #[test]
+    fn keyword_with_args_builds_single_ranked_result() {
+        let cfg = cfg(vec![link("jira", "https://jira.example.com/browse/{raw}")]);
+        let results = build_quicklink_results("jira ABC-123", &cfg, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exec, "quicklink:https://jira.example.com/browse/ABC-123");
+        assert_eq!(results[0].score, ranking_config::QUICKLINK_MATCH_SCORE);
+    }
+
+    #[test]
+    fn bare_keyword_offers_fill_hint() {
+        let cfg = cfg(vec![link("gh", "https://github.com/search?q={query}")]);
+        let results = build_quicklink_results("gh", &cfg, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exec, "fill:gh ");
+        assert!(build_quicklink_results("zz", &cfg, 100).is_empty());
+    }
+
+    #[test]
+    fn disabled_quicklinks_are_ignored() {
+        let mut entry = link("gh", "https://github.com/search?q={query}");
+        entry.enabled = false;
+        let cfg = cfg(vec![entry]);
+        assert!(build_quicklink_results("gh vanta", &cfg, 100).is_empty());
+    }
+
+    #[test]
+    fn palette_lists_all_only_for_the_quicklink_keyword() {
+        let cfg = cfg(vec![link("gh", "https://github.com/{raw}"), link("jira", "https://jira.example.com")]);
+        assert_eq!(build_quicklink_palette_results("quick", &cfg, 100).len(), 2);
+        assert_eq!(build_quicklink_palette_results("quicklink", &cfg, 100).len(), 2);
+        assert!(build_quicklink_palette_results("link", &cfg, 100).is_empty());
+        assert!(build_quicklink_palette_results("qu", &cfg, 100).is_empty());
+        assert_eq!(build_quicklink_palette_results("jir", &cfg, 100).len(), 1);
+    }
+
+    #[test]
+    fn web_fallback_uses_configured_engine() {
+        let cfg = cfg(vec![link("ddg", "https://duckduckgo.com/?q={query}")]);
+        let fallback = build_web_fallback_result("hello world", &cfg).expect("fallback");
+        assert_eq!(fallback.exec, "quicklink:https://duckduckgo.com/?q=hello%20world");
+        assert!(fallback.title.contains("Search the web"));
+
+        let mut disabled = cfg.clone();
+        disabled.web_search_fallback = false;
+        assert!(build_web_fallback_result("hello", &disabled).is_none());
+    }
+}
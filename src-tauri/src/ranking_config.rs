@@ -10,6 +10,20 @@ pub const WEIGHT_MAX: u32 = 300;
 // ── Calculator ───────────────────────────────────────────────────────
 pub const CALCULATOR_BASE_SCORE: u32 = 900_000;
 
+// ── Text transforms (`b64 …`, `json …`, `#ff8800 to hsl`) ─────────────
+/// Explicit keyword, so it sits just below the calculator's answer.
+pub const TRANSFORM_SCORE: u32 = 890_000;
+
+// ── Calculator session (`type:calc`) ────────────────────────────────
+/// Variables and functions are listed above the history they came from.
+pub const CALC_VARIABLE_SCORE: u32 = 3_000;
+pub const CALC_HISTORY_FUZZY_BASE: u32 = 900;
+pub const CALC_HISTORY_FUZZY_MULTIPLIER: u32 = 6;
+/// Bonus for the newest entry, reduced by `CALC_HISTORY_RECENCY_STEP` per position.
+pub const CALC_HISTORY_RECENCY_BONUS: u32 = 400;
+pub const CALC_HISTORY_RECENCY_STEP: u32 = 4;
+pub const CALC_HISTORY_LIMIT: usize = 20;
+
 // ── Store / Settings (search) ────────────────────────────────────────
 pub const STORE_SEARCH_SCORE: u32 = 2_600;
 pub const SETTINGS_BASE_SCORE: u32 = 1_100;
@@ -26,6 +40,21 @@ pub const INTENT_MIN_QUERY_LEN: usize = 8;
 pub const INTENT_MIN_STEPS: usize = 2;
 pub const INTENT_BASE_SCORE: u32 = 970_000;
 
+// ── Quicklinks / web search ──────────────────────────────────────────
+/// `<keyword> <terms>` — the user explicitly asked for this quicklink.
+pub const QUICKLINK_MATCH_SCORE: u32 = 950_000;
+pub const QUICKLINK_KEYWORD_SCORE: u32 = 2_400;
+pub const QUICKLINK_HINT_SCORE: u32 = 900;
+/// "Search the web for …" shown only when nothing else matched.
+pub const WEB_FALLBACK_SCORE: u32 = 100;
+
+// ── Snippets ─────────────────────────────────────────────────────────
+/// `<keyword>` or `<keyword> <inputs>` — the user typed the snippet's keyword.
+pub const SNIPPET_KEYWORD_SCORE: u32 = 950_000;
+/// Keyword prefix, tag or body matches while typing.
+pub const SNIPPET_HINT_SCORE: u32 = 900;
+pub const SNIPPET_RESULT_LIMIT: usize = 8;
+
 // ── Window results ───────────────────────────────────────────────────
 pub const WINDOW_NO_QUERY_BASE: u32 = 650;
 pub const WINDOW_FUZZY_TITLE_BASE: u32 = 850;
@@ -42,11 +71,88 @@ pub const EXTENSION_FUZZY_MULTIPLIER: u32 = 6;
 pub const EXTENSION_EXACT_SCORE: u32 = 1_050;
 
 // ── Clipboard results ────────────────────────────────────────────────
-pub const CLIPBOARD_EXACT_BASE: u32 = 900;
-pub const CLIPBOARD_FUZZY_BASE: u32 = 700;
-pub const CLIPBOARD_FUZZY_MULTIPLIER: u32 = 5;
+pub const CLIPBOARD_BASE: u32 = 700;
+/// Each unit of FTS5 BM25 relevance is worth this many points.
+pub const CLIPBOARD_BM25_MULTIPLIER: f64 = 60.0;
+/// Bonus for an entry copied just now; halved once it is a half-life old.
+pub const CLIPBOARD_RECENCY_BONUS: f64 = 200.0;
+pub const CLIPBOARD_RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
 pub const CLIPBOARD_PINNED_BONUS: u32 = 220;
-pub const CLIPBOARD_SCAN_LIMIT: usize = 80;
+
+// ── Browser bookmarks / history ──────────────────────────────────────
+pub const WEB_FUZZY_BASE: u32 = 620;
+pub const WEB_FUZZY_MULTIPLIER: u32 = 5;
+/// Frecency: ln(visits + 1) * multiplier, capped.
+pub const WEB_VISIT_LN_MULTIPLIER: f64 = 90.0;
+pub const WEB_VISIT_CAP: u32 = 600;
+pub const WEB_RECENT_VISIT_BONUS: u32 = 160;
+pub const WEB_BOOKMARK_BONUS: u32 = 240;
+pub const WEB_RESULT_LIMIT: usize = 6;
+
+// ── Recent files (recently-used.xbel) ────────────────────────────────
+pub const RECENT_FUZZY_BASE: u32 = 1_150;
+pub const RECENT_FUZZY_MULTIPLIER: u32 = 8;
+pub const RECENT_PATH_BASE: u32 = 820;
+pub const RECENT_PATH_MULTIPLIER: u32 = 5;
+/// Bonus for the newest entry, reduced by `RECENT_RECENCY_STEP` per position.
+pub const RECENT_RECENCY_BONUS: u32 = 300;
+pub const RECENT_RECENCY_STEP: u32 = 15;
+pub const RECENT_RESULT_LIMIT: usize = 5;
+pub const RECENT_SUGGESTION_SCORE: u32 = 700;
+pub const RECENT_SUGGESTION_LIMIT: usize = 6;
+
+// ── SSH hosts (~/.ssh/config, known_hosts) ──────────────────────────
+pub const SSH_FUZZY_BASE: u32 = 700;
+pub const SSH_FUZZY_MULTIPLIER: u32 = 6;
+/// Added when the query starts with `ssh `.
+pub const SSH_EXPLICIT_BONUS: u32 = 6_000;
+pub const SSH_RESULT_LIMIT: usize = 5;
+
+// ── Emoji & Unicode picker ───────────────────────────────────────────
+pub const EMOJI_SHORTCODE_EXACT: u32 = 4_000;
+pub const EMOJI_SHORTCODE_PREFIX: u32 = 3_000;
+pub const EMOJI_WORD_MATCH: u32 = 1_800;
+pub const EMOJI_NAME_WORD_BONUS: u32 = 150;
+pub const EMOJI_NAME_EXACT_BONUS: u32 = 600;
+pub const EMOJI_EXTRA_WORD_PENALTY: u32 = 40;
+/// History frecency (×1000) is divided down and capped before being added.
+pub const EMOJI_USAGE_DIVISOR: u32 = 4;
+pub const EMOJI_USAGE_CAP: u32 = 1_200;
+pub const EMOJI_RECENT_BASE: u32 = 2_000;
+/// Added to every result when the query uses `:` or `emoji:` so the picker wins.
+pub const EMOJI_PICKER_BASE: u32 = 880_000;
+pub const EMOJI_RESULT_LIMIT: usize = 24;
+
+// ── `!` shell runner ─────────────────────────────────────────────────
+pub const SHELL_RUN_SCORE: u32 = 10_000;
+pub const SHELL_HISTORY_BASE: u32 = 2_000;
+pub const SHELL_HISTORY_PREFIX_BONUS: u32 = 1_500;
+/// Bonus for the newest history entry, reduced by `SHELL_HISTORY_RECENCY_STEP` per position.
+pub const SHELL_HISTORY_RECENCY_BONUS: u32 = 600;
+pub const SHELL_HISTORY_RECENCY_STEP: u32 = 10;
+pub const SHELL_EXEC_BASE: u32 = 1_500;
+pub const SHELL_EXEC_PREFIX_BONUS: u32 = 1_200;
+
+// ── systemd user units ───────────────────────────────────────────────
+pub const SYSTEMD_FUZZY_BASE: u32 = 600;
+pub const SYSTEMD_FUZZY_MULTIPLIER: u32 = 5;
+/// Added when the query starts with `systemd`, `unit` or `service`.
+pub const SYSTEMD_EXPLICIT_BONUS: u32 = 6_000;
+pub const SYSTEMD_RESULT_LIMIT: usize = 6;
+/// Cap for explicit `systemd` queries that list every unit.
+pub const SYSTEMD_LIST_LIMIT: usize = 40;
+
+// ── Git repositories ─────────────────────────────────────────────────
+pub const REPO_FUZZY_BASE: u32 = 900;
+pub const REPO_FUZZY_MULTIPLIER: u32 = 7;
+/// Path-only matches keep a fraction of their fuzzy score.
+pub const REPO_PATH_DIVISOR: u32 = 3;
+pub const REPO_EXACT_BONUS: u32 = 2_500;
+/// Added when the query starts with `repo` or `git`.
+pub const REPO_EXPLICIT_BONUS: u32 = 6_000;
+pub const REPO_RESULT_LIMIT: usize = 5;
+/// Cap for explicit `repo` queries that list every repository.
+pub const REPO_LIST_LIMIT: usize = 20;
 
 // ── Profile results ──────────────────────────────────────────────────
 pub const PROFILE_FUZZY_BASE: u32 = 1_000;
@@ -80,6 +186,9 @@ pub const SI_WINDOW: u32 = 4_500;
 pub const SI_CALCULATOR: u32 = 4_000;
 pub const SI_EXTENSION: u32 = 3_500;
 pub const SI_CLIPBOARD: u32 = 3_200;
+pub const SI_WEB: u32 = 3_800;
+pub const SI_RECENT: u32 = 4_200;
+pub const SI_EMOJI: u32 = 4_000;
 
 // ── app_entity_bonus ─────────────────────────────────────────────────
 pub const AE_EXACT: u32 = 8_000;
@@ -0,0 +1,438 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+static RECENT_CACHE: OnceLock<Mutex<Option<Vec<RecentEntry>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RecentEntry {
+    pub path: String,
+    pub name: String,
+    pub mime_type: Option<String>,
+    pub modified_ms: i64,
+    pub applications: Vec<String>,
+}
+
+/// `$XDG_DATA_HOME/recently-used.xbel`, falling back to `~/.local/share`.
+pub fn xbel_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("recently-used.xbel"))
+}
+
+/// Attributes of `element` with entities (including numeric ones) decoded.
+fn attrs(element: &BytesStart) -> Vec<(String, String)> {
+    element
+        .attributes()
+        .flatten()
+        .filter_map(|a| {
+            let key = String::from_utf8_lossy(a.key.as_ref()).to_string();
+            Some((key, a.unescape_value().ok()?.into_owned()))
+        })
+        .collect()
+}
+
+fn attr<'a>(list: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    list.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+}
+
+fn parse_timestamp_ms(value: Option<&str>) -> i64 {
+    value
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(0)
+}
+
+/// A `<bookmark>` whose children are still being read.
+struct PendingBookmark {
+    attrs: Vec<(String, String)>,
+    mime_type: Option<String>,
+    applications: Vec<(i64, String)>,
+}
+
+impl PendingBookmark {
+    fn new(attrs: Vec<(String, String)>) -> Self {
+        Self {
+            attrs,
+            mime_type: None,
+            applications: Vec::new(),
+        }
+    }
+
+    fn finish(mut self) -> Option<RecentEntry> {
+        let href = attr(&self.attrs, "href")?;
+        let encoded = href.strip_prefix("file://")?;
+        let path = urlencoding::decode(encoded).ok()?.into_owned();
+        let name = Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let modified_ms = parse_timestamp_ms(attr(&self.attrs, "modified"))
+            .max(parse_timestamp_ms(attr(&self.attrs, "visited")))
+            .max(parse_timestamp_ms(attr(&self.attrs, "added")));
+        self.applications.sort_by(|a, b| b.0.cmp(&a.0));
+
+        Some(RecentEntry {
+            path,
+            name,
+            mime_type: self.mime_type,
+            modified_ms,
+            applications: self.applications.into_iter().map(|(_, n)| n).collect(),
+        })
+    }
+}
+
+/// Parse a freedesktop XBEL document into entries for local files, newest first.
+/// Only `file://` URIs are kept; remote/trash entries are skipped. A malformed
+/// document keeps the entries read before the error.
+pub fn parse_xbel(raw: &str) -> Vec<RecentEntry> {
+    let mut reader = Reader::from_str(raw);
+    let mut out: Vec<RecentEntry> = Vec::new();
+    let mut current: Option<PendingBookmark> = None;
+
+    loop {
+        let (element, self_closing) = match reader.read_event() {
+            Ok(Event::Start(element)) => (element, false),
+            Ok(Event::Empty(element)) => (element, true),
+            Ok(Event::End(end)) => {
+                if end.name().as_ref() == b"bookmark" {
+                    out.extend(current.take().and_then(PendingBookmark::finish));
+                }
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                log::warn!("Stopped reading recently-used.xbel: {}", e);
+                break;
+            }
+        };
+
+        match element.name().as_ref() {
+            b"bookmark" => {
+                let bookmark = PendingBookmark::new(attrs(&element));
+                if self_closing {
+                    out.extend(bookmark.finish());
+                } else {
+                    current = Some(bookmark);
+                }
+            }
+            b"mime:mime-type" => {
+                if let Some(bookmark) = current.as_mut() {
+                    bookmark.mime_type = attr(&attrs(&element), "type").map(str::to_string);
+                }
+            }
+            b"bookmark:application" => {
+                let app_attrs = attrs(&element);
+                if let (Some(bookmark), Some(name)) = (current.as_mut(), attr(&app_attrs, "name")) {
+                    let modified = parse_timestamp_ms(attr(&app_attrs, "modified"));
+                    bookmark.applications.push((modified, name.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out.sort_by(|a, b| b.modified_ms.cmp(&a.modified_ms));
+    out
+}
+
+pub fn load_entries() -> Vec<RecentEntry> {
+    let Some(path) = xbel_path() else {
+        return Vec::new();
+    };
+    let Ok(raw) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    parse_xbel(&raw)
+        .into_iter()
+        .filter(|entry| Path::new(&entry.path).exists())
+        .collect()
+}
+
+/// Cached recent entries; populated lazily and refreshed by `watch_recent_files`.
+pub fn recent_entries() -> Vec<RecentEntry> {
+    let cache = RECENT_CACHE.get_or_init(|| Mutex::new(None));
+    let Ok(mut guard) = cache.lock() else {
+        return load_entries();
+    };
+    guard.get_or_insert_with(load_entries).clone()
+}
+
+fn refresh_cache() -> usize {
+    let entries = load_entries();
+    let count = entries.len();
+    if let Ok(mut guard) = RECENT_CACHE.get_or_init(|| Mutex::new(None)).lock() {
+        *guard = Some(entries);
+    }
+    count
+}
+
+pub fn watch_recent_files(app_handle: tauri::AppHandle) {
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let Some(path) = xbel_path() else {
+        return;
+    };
+    let Some(dir) = path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = match Watcher::new(
+        tx,
+        notify::Config::default().with_poll_interval(Duration::from_secs(5)),
+    ) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Failed to create recent files watcher: {}", e);
+            return;
+        }
+    };
+
+    // GTK rewrites the file atomically, so watch the directory rather than the inode.
+    if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        log::warn!("Could not watch {}: {}", dir.display(), e);
+        return;
+    }
+
+    log::info!("Watching {} for recent file changes", path.display());
+    let mut last_reload = std::time::Instant::now() - Duration::from_millis(500);
+
+    for event in rx {
+        match event {
+            Ok(ev) => {
+                let touches_xbel = ev.paths.iter().any(|p| p.file_name() == path.file_name());
+                let is_modify = matches!(
+                    ev.kind,
+                    EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+                );
+                if touches_xbel && is_modify && last_reload.elapsed() > Duration::from_millis(500) {
+                    last_reload = std::time::Instant::now();
+                    let count = refresh_cache();
+                    log::debug!("Recent files reloaded: {} entries", count);
+                    let _ = app_handle.emit("recent-files-changed", count);
+                }
+            }
+            Err(e) => log::error!("Recent files watcher error: {}", e),
+        }
+    }
+}
+
+fn relative_age(modified_ms: i64, now_ms: i64) -> String {
+    let minutes = (now_ms - modified_ms).max(0) / 60_000;
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{}m ago", minutes),
+        60..=1439 => format!("{}h ago", minutes / 60),
+        _ => format!("{}d ago", minutes / 1440),
+    }
+}
+
+fn recent_result(entry: &RecentEntry, score: u32, match_indices: Vec<u32>, now_ms: i64) -> SearchResult {
+    let mut subtitle = format!("{} · {}", entry.path, relative_age(entry.modified_ms, now_ms));
+    if let Some(app) = entry.applications.first() {
+        subtitle.push_str(&format!(" · {}", app));
+    }
+    let icon = Path::new(&entry.path)
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|ext| format!("file:{}", ext.to_lowercase()))
+        .unwrap_or_else(|| "file".to_string());
+
+    SearchResult {
+        title: entry.name.clone(),
+        subtitle: Some(subtitle),
+        icon: Some(icon),
+        exec: entry.path.clone(),
+        score,
+        match_indices,
+        source: ResultSource::Recent,
+        actions: Some(vec![
+            ActionHint {
+                label: "Copy Path".to_string(),
+                exec: format!("copy-path:{}", entry.path),
+                shortcut: Some("Ctrl+Shift+C".to_string()),
+            },
+            ActionHint {
+                label: "Reveal".to_string(),
+                exec: format!("reveal:{}", entry.path),
+                shortcut: Some("Shift+Enter".to_string()),
+            },
+            ActionHint {
+                label: "Open with Editor".to_string(),
+                exec: format!("open-with:{}", entry.path),
+                shortcut: Some("Alt+Enter".to_string()),
+            },
+        ]),
+        id: Some(format!("recent:{}", entry.path)),
+        group: entry.mime_type.clone(),
+        section: Some("Recent".to_string()),
+    }
+}
+
+/// Recency bonus decays by position so the newest document wins close calls.
+fn recency_bonus(rank: usize) -> u32 {
+    ranking_config::RECENT_RECENCY_BONUS.saturating_sub(rank as u32 * ranking_config::RECENT_RECENCY_STEP)
+}
+
+pub fn search_recent(query: &str, entries: &[RecentEntry], limit: usize, weight: u32) -> Vec<SearchResult> {
+    let trimmed = query.trim();
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    if trimmed.is_empty() {
+        return build_recent_suggestions(entries, limit, weight);
+    }
+
+    let mut out: Vec<SearchResult> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(rank, entry)| {
+            let (score, indices) = match fuzzy_score_text(trimmed, &entry.name) {
+                Some((raw, indices)) => (
+                    ranking_config::RECENT_FUZZY_BASE
+                        .saturating_add(raw.saturating_mul(ranking_config::RECENT_FUZZY_MULTIPLIER)),
+                    indices,
+                ),
+                None => {
+                    let (raw, _) = fuzzy_score_text(trimmed, &entry.path)?;
+                    (
+                        ranking_config::RECENT_PATH_BASE
+                            .saturating_add(raw.saturating_mul(ranking_config::RECENT_PATH_MULTIPLIER)),
+                        Vec::new(),
+                    )
+                }
+            };
+            let score = crate::weighted_score(score.saturating_add(recency_bonus(rank)), weight);
+            Some(recent_result(entry, score, indices, now_ms))
+        })
+        .collect();
+
+    out.sort_by(|a, b| b.score.cmp(&a.score));
+    out.truncate(limit);
+    out
+}
+
+/// Newest documents for the empty-query suggestions list.
+pub fn build_recent_suggestions(entries: &[RecentEntry], limit: usize, weight: u32) -> Vec<SearchResult> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    entries
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(rank, entry)| {
+            let score = crate::weighted_score(
+                ranking_config::RECENT_SUGGESTION_SCORE.saturating_add(recency_bonus(rank)),
+                weight,
+            );
+            recent_result(entry, score, Vec::new(), now_ms)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0"
+      xmlns:bookmark="http://www.freedesktop.org/standards/desktop-bookmarks"
+      xmlns:mime="http://www.freedesktop.org/standards/shared-mime-info">
+  <bookmark href="file:///home/me/Documents/Q3%20Report.pdf" added="2026-01-02T10:00:00Z" modified="2026-01-05T09:30:00Z" visited="2026-01-05T09:30:00Z">
+    <info>
+      <metadata owner="http://freedesktop.org">
+        <mime:mime-type type="application/pdf"/>
+        <bookmark:applications>
+          <bookmark:application name="Evince" exec="&apos;evince %u&apos;" modified="2026-01-03T08:00:00Z" count="1"/>
+          <bookmark:application name="Okular" exec="&apos;okular %u&apos;" modified="2026-01-05T09:30:00Z" count="2"/>
+        </bookmark:applications>
+      </metadata>
+    </info>
+  </bookmark>
+  <bookmark href="file:///home/me/notes/todo.md" added="2026-01-06T12:00:00Z" modified="2026-01-06T12:00:00Z" visited="2026-01-06T12:00:00Z">
+    <info><metadata owner="http://freedesktop.org"><mime:mime-type type="text/markdown"/></metadata></info>
+  </bookmark>
+  <bookmark href="sftp://server/etc/hosts" added="2026-01-07T12:00:00Z" modified="2026-01-07T12:00:00Z" visited="2026-01-07T12:00:00Z">
+  </bookmark>
+</xbel>"#;
+
+    #[test]
+    fn parses_local_entries_newest_first() {
+        let entries = parse_xbel(SAMPLE);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/home/me/notes/todo.md");
+        assert_eq!(entries[0].mime_type.as_deref(), Some("text/markdown"));
+
+        let report = &entries[1];
+        assert_eq!(report.path, "/home/me/Documents/Q3 Report.pdf");
+        assert_eq!(report.name, "Q3 Report.pdf");
+        assert_eq!(report.mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(report.applications, vec!["Okular", "Evince"]);
+        assert_eq!(
+            report.modified_ms,
+            chrono::DateTime::parse_from_rfc3339("2026-01-05T09:30:00Z")
+                .unwrap()
+                .timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn handles_self_closing_bookmarks_and_numeric_entities() {
+        let raw = r#"<xbel version="1.0">
+  <bookmark href="file:///home/me/it&#39;s.txt" modified="2026-01-08T12:00:00Z"/>
+  <bookmark href="file:///home/me/a&amp;b.txt" modified="2026-01-07T12:00:00Z">
+    <info><metadata><bookmark:applications>
+      <bookmark:application name="Tom&#x27;s Editor" modified="2026-01-07T12:00:00Z"/>
+    </bookmark:applications></metadata></info>
+  </bookmark>
+</xbel>"#;
+        let entries = parse_xbel(raw);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "/home/me/it's.txt");
+        assert!(entries[0].applications.is_empty());
+        assert_eq!(entries[1].path, "/home/me/a&b.txt");
+        assert_eq!(entries[1].applications, vec!["Tom's Editor"]);
+    }
+
+    #[test]
+    fn search_matches_names_and_prefers_recent() {
+        let mut entries = parse_xbel(SAMPLE);
+        entries.push(RecentEntry {
+            path: "/home/me/old/report-draft.pdf".to_string(),
+            name: "report-draft.pdf".to_string(),
+            mime_type: None,
+            modified_ms: 0,
+            applications: Vec::new(),
+        });
+
+        let results = search_recent("report", &entries, 10, 100);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| matches!(r.source, ResultSource::Recent)));
+        assert_eq!(results[0].exec, "/home/me/Documents/Q3 Report.pdf");
+        assert_eq!(results[1].exec, "/home/me/old/report-draft.pdf");
+        assert!(search_recent("zzzz", &entries, 10, 100).is_empty());
+    }
+
+    #[test]
+    fn suggestions_follow_recency_order() {
+        let entries = parse_xbel(SAMPLE);
+        let results = build_recent_suggestions(&entries, 1, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "todo.md");
+        assert_eq!(results[0].section.as_deref(), Some("Recent"));
+    }
+
+    #[test]
+    fn relative_age_formats_buckets() {
+        let now = 10 * 86_400_000;
+        assert_eq!(relative_age(now, now), "just now");
+        assert_eq!(relative_age(now - 5 * 60_000, now), "5m ago");
+        assert_eq!(relative_age(now - 3 * 3_600_000, now), "3h ago");
+        assert_eq!(relative_age(now - 2 * 86_400_000, now), "2d ago");
+    }
+}
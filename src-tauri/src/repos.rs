@@ -0,0 +1,598 @@
+use crate::config::FilesConfig;
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+pub const EXEC_TERMINAL_PREFIX: &str = "terminal-at:";
+/// Opens the repository directory with the configured editor.
+pub const EXEC_OPEN_PREFIX: &str = "repo-open:";
+
+const STATUS_TTL: Duration = Duration::from_secs(10);
+const INDEX_TTL: Duration = Duration::from_secs(600);
+/// Directories that never contain repositories worth jumping to but are expensive to walk.
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "__pycache__", ".venv"];
+
+static STATUS_CACHE: OnceLock<Mutex<StatusCache>> = OnceLock::new();
+static REPO_INDEX: OnceLock<Mutex<RepoIndex>> = OnceLock::new();
+
+/// The `FilesConfig` fields that change what `discover_repos` finds.
+type ScanKey = (Vec<String>, usize, bool, Vec<String>);
+
+#[derive(Default)]
+struct StatusCache {
+    statuses: HashMap<String, (Instant, RepoStatus)>,
+    /// Paths a worker is currently running `git status` for.
+    pending: HashSet<String>,
+}
+
+#[derive(Default)]
+struct RepoIndex {
+    loaded_at: Option<Instant>,
+    key: Option<ScanKey>,
+    repos: Arc<Vec<RepoEntry>>,
+    refreshing: Option<ScanKey>,
+}
+
+/// A discovered working tree (the directory that contains `.git`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepoEntry {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepoStatus {
+    pub branch: Option<String>,
+    pub detached_at: Option<String>,
+    pub has_upstream: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub changed: usize,
+    pub untracked: usize,
+    pub remote_url: Option<String>,
+}
+
+impl RepoStatus {
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0 || self.untracked > 0
+    }
+}
+
+/// Walk the file index roots looking for `.git` entries. Uses the `files` settings for
+/// hidden dirs and exclude globs, but `repo_max_depth` instead of `max_depth` so projects
+/// nested a few levels down are still found. Repositories are not descended into, so
+/// submodules and vendored checkouts don't show up as separate results.
+pub fn discover_repos(config: &FilesConfig) -> Vec<RepoEntry> {
+    let exclude_matcher = build_globset(&config.exclude_globs);
+    let mut repos = Vec::new();
+
+    for root in repo_roots(config) {
+        let mut walker = WalkDir::new(&root)
+            .max_depth(config.repo_max_depth.max(config.max_depth))
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| {
+                if !e.file_type().is_dir() {
+                    return false;
+                }
+                if e.depth() == 0 {
+                    return true;
+                }
+                let name = e.file_name().to_string_lossy();
+                if SKIP_DIRS.contains(&name.as_ref()) {
+                    return false;
+                }
+                if !config.include_hidden && name.starts_with('.') {
+                    return false;
+                }
+                if let Some(exclude) = exclude_matcher.as_ref() {
+                    let target = e.path().strip_prefix(&root).unwrap_or_else(|_| e.path());
+                    if exclude.is_match(target) {
+                        return false;
+                    }
+                }
+                true
+            });
+
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.path().join(".git").exists() {
+                continue;
+            }
+            repos.push(RepoEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+            });
+            walker.skip_current_dir();
+        }
+    }
+
+    repos.sort_by_key(|a| a.name.to_lowercase());
+    repos.dedup_by(|a, b| a.path == b.path);
+    repos
+}
+
+fn scan_key(config: &FilesConfig) -> ScanKey {
+    (
+        config.repo_roots.clone(),
+        config.repo_max_depth.max(config.max_depth),
+        config.include_hidden,
+        config.exclude_globs.clone(),
+    )
+}
+
+fn repo_index() -> &'static Mutex<RepoIndex> {
+    REPO_INDEX.get_or_init(|| Mutex::new(RepoIndex::default()))
+}
+
+/// Snapshot of the discovered repositories. The walk is much deeper than the file
+/// index, so it is cached on its own and redone on a background thread when stale,
+/// when the scan settings change, or after `invalidate_index`.
+pub fn cached_repos(config: &FilesConfig) -> Arc<Vec<RepoEntry>> {
+    let Ok(mut guard) = repo_index().lock() else {
+        return Arc::new(Vec::new());
+    };
+    let key = scan_key(config);
+    if guard.key.as_ref() != Some(&key) {
+        guard.key = Some(key.clone());
+        guard.repos = Arc::new(Vec::new());
+        guard.loaded_at = None;
+    }
+    let stale = guard
+        .loaded_at
+        .map(|t| t.elapsed() >= INDEX_TTL)
+        .unwrap_or(true);
+    if stale && guard.refreshing.as_ref() != Some(&key) {
+        guard.refreshing = Some(key.clone());
+        let config = config.clone();
+        std::thread::spawn(move || {
+            let repos = Arc::new(discover_repos(&config));
+            let Ok(mut guard) = repo_index().lock() else {
+                return;
+            };
+            if guard.key.as_ref() == Some(&key) {
+                guard.repos = repos;
+                guard.loaded_at = Some(Instant::now());
+            }
+            if guard.refreshing.as_ref() == Some(&key) {
+                guard.refreshing = None;
+            }
+        });
+    }
+    Arc::clone(&guard.repos)
+}
+
+/// Marks the repository list stale so the next search rescans, e.g. after a reindex.
+pub fn invalidate_index() {
+    if let Ok(mut guard) = repo_index().lock() {
+        guard.loaded_at = None;
+    }
+}
+
+fn repo_roots(config: &FilesConfig) -> Vec<PathBuf> {
+    if !config.repo_roots.is_empty() {
+        let home = dirs::home_dir();
+        return config
+            .repo_roots
+            .iter()
+            .map(|root| match (root.strip_prefix("~/"), home.as_ref()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(root),
+            })
+            .filter(|root| root.is_dir())
+            .collect();
+    }
+
+    let default_home = dirs::home_dir().unwrap_or(PathBuf::from("/"));
+    vec![std::env::var("VANTA_FILE_INDEX_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or(default_home)]
+}
+
+fn build_globset(patterns: &[String]) -> Option<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    let mut added = 0;
+    for pat in patterns {
+        if let Ok(glob) = Glob::new(pat) {
+            builder.add(glob);
+            added += 1;
+        }
+    }
+    if added == 0 {
+        return None;
+    }
+    builder.build().ok()
+}
+
+/// Parse `git status --porcelain=v2 --branch` output.
+pub fn parse_status(output: &str) -> RepoStatus {
+    let mut status = RepoStatus::default();
+    let mut oid = None;
+
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" => oid = Some(value.to_string()),
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.has_upstream = true,
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if line.starts_with("? ") {
+            status.untracked += 1;
+        } else if line.starts_with("1 ") || line.starts_with("2 ") || line.starts_with("u ") {
+            status.changed += 1;
+        }
+    }
+
+    if status.branch.is_none() {
+        status.detached_at = oid
+            .filter(|oid| oid != "(initial)")
+            .map(|oid| oid.chars().take(7).collect());
+    }
+    status
+}
+
+/// Pick the push/fetch URL to copy: `origin` if present, otherwise the first remote.
+pub fn parse_remote_urls(output: &str) -> Option<String> {
+    let remotes: Vec<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, url) = line.split_once(char::is_whitespace)?;
+            let name = key.strip_prefix("remote.")?.strip_suffix(".url")?;
+            Some((name, url.trim()))
+        })
+        .collect();
+    remotes
+        .iter()
+        .find(|(name, _)| *name == "origin")
+        .or_else(|| remotes.first())
+        .map(|(_, url)| url.to_string())
+}
+
+fn git_output(path: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn load_status(path: &str) -> RepoStatus {
+    let mut status = git_output(path, &["status", "--porcelain=v2", "--branch"])
+        .map(|out| parse_status(&out))
+        .unwrap_or_default();
+    status.remote_url = git_output(path, &["config", "--get-regexp", r"^remote\..*\.url$"])
+        .and_then(|out| parse_remote_urls(&out));
+    status
+}
+
+fn status_cache() -> &'static Mutex<StatusCache> {
+    STATUS_CACHE.get_or_init(|| Mutex::new(StatusCache::default()))
+}
+
+/// Last known status for a repository. `git status` never runs on the search path:
+/// a missing or stale entry is refreshed by a worker thread, and the result shows
+/// up on a later keystroke.
+pub fn cached_status(path: &str) -> Option<RepoStatus> {
+    let mut guard = status_cache().lock().ok()?;
+    let cached = guard.statuses.get(path).cloned();
+    let fresh = cached
+        .as_ref()
+        .is_some_and(|(at, _)| at.elapsed() < STATUS_TTL);
+    if !fresh && guard.pending.insert(path.to_string()) {
+        let path = path.to_string();
+        std::thread::spawn(move || {
+            let status = load_status(&path);
+            if let Ok(mut guard) = status_cache().lock() {
+                guard.pending.remove(&path);
+                guard.statuses.insert(path, (Instant::now(), status));
+            }
+        });
+    }
+    cached.map(|(_, status)| status)
+}
+
+fn display_path(path: &str) -> String {
+    dirs::home_dir()
+        .and_then(|home| {
+            Path::new(path)
+                .strip_prefix(&home)
+                .ok()
+                .map(|rest| format!("~/{}", rest.display()))
+        })
+        .unwrap_or_else(|| path.to_string())
+}
+
+pub fn status_line(status: &RepoStatus, path: &str) -> String {
+    let mut head = match (&status.branch, &status.detached_at) {
+        (Some(branch), _) => branch.clone(),
+        (None, Some(oid)) => format!("detached @ {}", oid),
+        (None, None) => "no commits".to_string(),
+    };
+    if status.ahead > 0 {
+        head.push_str(&format!(" ↑{}", status.ahead));
+    }
+    if status.behind > 0 {
+        head.push_str(&format!(" ↓{}", status.behind));
+    }
+
+    let state = match (status.changed, status.untracked) {
+        (0, 0) => "clean".to_string(),
+        (changed, 0) => format!("{} changed", changed),
+        (0, untracked) => format!("{} untracked", untracked),
+        (changed, untracked) => format!("{} changed, {} untracked", changed, untracked),
+    };
+
+    format!("{} · {} · {}", head, state, display_path(path))
+}
+
+fn repo_result(
+    repo: &RepoEntry,
+    status: Option<&RepoStatus>,
+    score: u32,
+    match_indices: Vec<u32>,
+) -> SearchResult {
+    let mut actions = vec![
+        ActionHint {
+            label: "Open in File Manager".to_string(),
+            exec: format!("reveal:{}", repo.path),
+            shortcut: Some("Shift+Enter".to_string()),
+        },
+        ActionHint {
+            label: "Open Terminal Here".to_string(),
+            exec: format!("{}{}", EXEC_TERMINAL_PREFIX, repo.path),
+            shortcut: Some("Ctrl+T".to_string()),
+        },
+    ];
+    if let Some(url) = status.and_then(|s| s.remote_url.as_ref()) {
+        actions.push(ActionHint {
+            label: "Copy Remote URL".to_string(),
+            exec: format!("copy:{}", url),
+            shortcut: Some("Ctrl+Shift+C".to_string()),
+        });
+    }
+    actions.push(ActionHint {
+        label: "Copy Path".to_string(),
+        exec: format!("copy-path:{}", repo.path),
+        shortcut: None,
+    });
+
+    SearchResult {
+        title: repo.name.clone(),
+        subtitle: Some(match status {
+            Some(status) => status_line(status, &repo.path),
+            None => display_path(&repo.path),
+        }),
+        icon: Some("fa-brands fa-git-alt".to_string()),
+        exec: format!("{}{}", EXEC_OPEN_PREFIX, repo.path),
+        score,
+        match_indices,
+        source: ResultSource::Application,
+        actions: Some(actions),
+        id: Some(format!("repo:{}", repo.path)),
+        group: status.map(|s| if s.is_dirty() { "Dirty" } else { "Clean" }.to_string()),
+        section: Some("Repositories".to_string()),
+    }
+}
+
+fn explicit_query(query: &str) -> Option<&str> {
+    let trimmed = query.trim();
+    // Not `git`: that query belongs to gitk, git-cola and friends.
+    ["repo", "repos"].iter().find_map(|kw| {
+        let rest = trimmed.strip_prefix(kw)?;
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+    })
+}
+
+pub fn is_explicit_query(query: &str) -> bool {
+    explicit_query(query).is_some()
+}
+
+/// Rank repositories by name (falling back to path), then attach whatever status is cached.
+pub fn search_repos(
+    query: &str,
+    repos: &[RepoEntry],
+    limit: usize,
+    weight: u32,
+) -> Vec<SearchResult> {
+    search_repos_with(query, repos, limit, weight, cached_status)
+}
+
+fn search_repos_with(
+    query: &str,
+    repos: &[RepoEntry],
+    limit: usize,
+    weight: u32,
+    status_for: impl Fn(&str) -> Option<RepoStatus>,
+) -> Vec<SearchResult> {
+    let (needle, explicit) = match explicit_query(query) {
+        Some(rest) => (rest, true),
+        None => (query.trim(), false),
+    };
+    if !explicit && needle.chars().count() < 2 {
+        return Vec::new();
+    }
+    let bonus = if explicit {
+        ranking_config::REPO_EXPLICIT_BONUS
+    } else {
+        0
+    };
+
+    let mut ranked: Vec<(&RepoEntry, u32, Vec<u32>)> = repos
+        .iter()
+        .filter_map(|repo| {
+            if needle.is_empty() {
+                return Some((repo, ranking_config::REPO_FUZZY_BASE + bonus, Vec::new()));
+            }
+            let (raw, indices) = match fuzzy_score_text(needle, &repo.name) {
+                Some(hit) => hit,
+                None => {
+                    let (raw, _) = fuzzy_score_text(needle, &repo.path)?;
+                    (raw / ranking_config::REPO_PATH_DIVISOR, Vec::new())
+                }
+            };
+            let mut base = ranking_config::REPO_FUZZY_BASE
+                .saturating_add(raw.saturating_mul(ranking_config::REPO_FUZZY_MULTIPLIER))
+                .saturating_add(bonus);
+            if repo.name.eq_ignore_ascii_case(needle) {
+                base = base.saturating_add(ranking_config::REPO_EXACT_BONUS);
+            }
+            Some((repo, base, indices))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+    ranked.truncate(limit);
+
+    ranked
+        .into_iter()
+        .map(|(repo, base, indices)| {
+            let status = status_for(&repo.path);
+            repo_result(
+                repo,
+                status.as_ref(),
+                crate::weighted_score(base, weight),
+                indices,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn discovers_repos_below_file_depth_and_skips_nested() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("work/clients/acme/api/.git")).unwrap();
+        fs::create_dir_all(root.join("work/clients/acme/api/vendor/lib/.git")).unwrap();
+        fs::create_dir_all(root.join("code/vanta/.git")).unwrap();
+        fs::create_dir_all(root.join("code/site/node_modules/pkg/.git")).unwrap();
+        fs::create_dir_all(root.join("archive/old/.git")).unwrap();
+
+        let config = FilesConfig {
+            max_depth: 1,
+            repo_max_depth: 6,
+            repo_roots: vec![root.to_string_lossy().to_string()],
+            exclude_globs: vec!["archive/**".to_string(), "archive".to_string()],
+            ..Default::default()
+        };
+        let names: Vec<String> = discover_repos(&config)
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["api", "vanta"]);
+    }
+
+    #[test]
+    fn parses_porcelain_v2_branch_and_changes() {
+        let out = "# branch.oid 1a2b3c4d5e6f\n# branch.head main\n# branch.upstream origin/main\n# branch.ab +2 -1\n1 .M N... 100644 100644 100644 aaa bbb src/lib.rs\n2 R. N... 100644 100644 100644 aaa bbb R100 new.rs\told.rs\n? notes.txt\n";
+        let status = parse_status(out);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert!(status.has_upstream);
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!((status.changed, status.untracked), (2, 1));
+        assert_eq!(
+            status_line(&status, "/srv/vanta"),
+            "main ↑2 ↓1 · 2 changed, 1 untracked · /srv/vanta"
+        );
+
+        let detached = parse_status("# branch.oid 1a2b3c4d5e6f\n# branch.head (detached)\n");
+        assert_eq!(detached.detached_at.as_deref(), Some("1a2b3c4"));
+        assert!(!detached.is_dirty());
+    }
+
+    #[test]
+    fn prefers_origin_remote_url() {
+        let out = "remote.upstream.url git@github.com:up/vanta.git\nremote.origin.url https://github.com/me/vanta.git\n";
+        assert_eq!(
+            parse_remote_urls(out).as_deref(),
+            Some("https://github.com/me/vanta.git")
+        );
+        assert_eq!(parse_remote_urls("").as_deref(), None);
+    }
+
+    #[test]
+    fn search_ranks_names_and_attaches_actions() {
+        let repos = vec![
+            RepoEntry {
+                name: "vanta".into(),
+                path: "/code/vanta".into(),
+            },
+            RepoEntry {
+                name: "vanta-docs".into(),
+                path: "/code/vanta-docs".into(),
+            },
+            RepoEntry {
+                name: "dotfiles".into(),
+                path: "/code/dotfiles".into(),
+            },
+        ];
+        let status = |_: &str| {
+            Some(RepoStatus {
+                branch: Some("main".into()),
+                remote_url: Some("git@github.com:me/vanta.git".into()),
+                ..Default::default()
+            })
+        };
+        let results = search_repos_with("vanta", &repos, 5, 100, status);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "vanta");
+        assert_eq!(results[0].exec, "repo-open:/code/vanta");
+        let execs: Vec<&str> = results[0]
+            .actions
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|a| a.exec.as_str())
+            .collect();
+        assert!(execs.contains(&"terminal-at:/code/vanta"));
+        assert!(execs.contains(&"copy:git@github.com:me/vanta.git"));
+
+        assert_eq!(search_repos_with("repo", &repos, 5, 100, status).len(), 3);
+        assert!(search_repos_with("v", &repos, 5, 100, status).is_empty());
+        assert!(!is_explicit_query("git"));
+        assert!(!is_explicit_query("gitk"));
+    }
+
+    #[test]
+    fn results_without_cached_status_still_show() {
+        let repos = vec![RepoEntry {
+            name: "vanta".into(),
+            path: "/code/vanta".into(),
+        }];
+        let results = search_repos_with("vanta", &repos, 5, 100, |_| None);
+        assert_eq!(results[0].subtitle.as_deref(), Some("/code/vanta"));
+        assert_eq!(results[0].group, None);
+        let actions = results[0].actions.as_ref().unwrap();
+        assert!(actions.iter().all(|a| !a.exec.starts_with("copy:")));
+    }
+}
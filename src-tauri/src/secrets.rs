@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
 
 use aes_gcm::aead::{Aead, KeyInit};
 use aes_gcm::{Aes256Gcm, Nonce};
@@ -39,13 +41,15 @@ fn ensure_secret_name(name: &str) -> Result<(), VantaError> {
     Ok(())
 }
 
-fn read_or_create_key() -> Result<[u8; 32], VantaError> {
-    let path = key_path();
+/// Reads the 32-byte key at `path`, creating it with owner-only permissions on
+/// first use. Shared by the workflow secrets and the clipboard history; `label`
+/// names the key in errors.
+pub(crate) fn read_or_create_key_file(path: &Path, label: &str) -> Result<[u8; 32], VantaError> {
     if path.exists() {
-        let raw = std::fs::read(&path)
-            .map_err(|e| format!("Failed to read secrets key: {}", e))?;
+        let raw =
+            std::fs::read(path).map_err(|e| format!("Failed to read {} key: {}", label, e))?;
         if raw.len() != 32 {
-            return Err("Invalid secrets key length".into());
+            return Err(format!("Invalid {} key length", label).into());
         }
         let mut key = [0u8; 32];
         key.copy_from_slice(&raw);
@@ -54,13 +58,19 @@ fn read_or_create_key() -> Result<[u8; 32], VantaError> {
 
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create secrets directory: {}", e))?;
+            .map_err(|e| format!("Failed to create {} key directory: {}", label, e))?;
     }
 
     let mut key = [0u8; 32];
     rand::rngs::OsRng.fill_bytes(&mut key);
-    std::fs::write(&path, key)
-        .map_err(|e| format!("Failed to write secrets key: {}", e))?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(&key))
+        .map_err(|e| format!("Failed to write {} key: {}", label, e))?;
     Ok(key)
 }
 
@@ -79,7 +89,7 @@ fn load_plain_map() -> Result<HashMap<String, String>, VantaError> {
     let encrypted: EncryptedSecretsFile = serde_json::from_str(&raw)
         .map_err(|e| format!("Failed to parse secrets store: {}", e))?;
 
-    let key = read_or_create_key()?;
+    let key = read_or_create_key_file(&key_path(), "secrets")?;
     let cipher = Aes256Gcm::new_from_slice(&key)
         .map_err(|e| format!("Failed to initialize secrets cipher: {}", e))?;
 
@@ -105,7 +115,7 @@ fn load_plain_map() -> Result<HashMap<String, String>, VantaError> {
 }
 
 fn save_plain_map(map: &HashMap<String, String>) -> Result<(), VantaError> {
-    let key = read_or_create_key()?;
+    let key = read_or_create_key_file(&key_path(), "secrets")?;
     let cipher = Aes256Gcm::new_from_slice(&key)
         .map_err(|e| format!("Failed to initialize secrets cipher: {}", e))?;
 
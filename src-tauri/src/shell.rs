@@ -0,0 +1,452 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::errors::VantaError;
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+/// Runs the command detached (no terminal, output discarded).
+pub const EXEC_RUN_PREFIX: &str = "shell-run:";
+/// Runs the command inside the configured terminal.
+pub const EXEC_TERMINAL_PREFIX: &str = "shell-terminal:";
+/// Runs the command, waits for it, and copies stdout to the clipboard.
+pub const EXEC_COPY_OUTPUT_PREFIX: &str = "shell-copy:";
+
+const HISTORY_CAP: usize = 100;
+const OUTPUT_CAP_BYTES: usize = 512 * 1024;
+pub const CAPTURE_TIMEOUT: Duration = Duration::from_secs(15);
+
+static PATH_CACHE: OnceLock<Mutex<Option<Vec<PathExecutable>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathExecutable {
+    pub name: String,
+    pub path: String,
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    env::var_os("PATH")
+        .map(|paths| {
+            env::split_paths(&paths)
+                .filter(|dir| dir.is_dir() && seen.insert(dir.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Executables in `dirs`, first directory wins for duplicate names (same as the shell).
+pub fn scan_dirs(dirs: &[PathBuf]) -> Vec<PathExecutable> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || seen.contains(&name) {
+                continue;
+            }
+            let path = entry.path();
+            if is_executable(&path) {
+                seen.insert(name.clone());
+                out.push(PathExecutable {
+                    name,
+                    path: path.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+pub fn executables() -> Vec<PathExecutable> {
+    let cache = PATH_CACHE.get_or_init(|| Mutex::new(None));
+    let Ok(mut guard) = cache.lock() else {
+        return scan_dirs(&path_dirs());
+    };
+    guard.get_or_insert_with(|| scan_dirs(&path_dirs())).clone()
+}
+
+fn refresh_cache() -> usize {
+    let found = scan_dirs(&path_dirs());
+    let count = found.len();
+    if let Ok(mut guard) = PATH_CACHE.get_or_init(|| Mutex::new(None)).lock() {
+        *guard = Some(found);
+    }
+    count
+}
+
+pub fn watch_path_dirs(app_handle: tauri::AppHandle) {
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher: RecommendedWatcher = match Watcher::new(
+        tx,
+        notify::Config::default().with_poll_interval(Duration::from_secs(5)),
+    ) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Failed to create $PATH watcher: {}", e);
+            return;
+        }
+    };
+
+    let dirs = path_dirs();
+    for dir in &dirs {
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            log::warn!("Could not watch {}: {}", dir.display(), e);
+        }
+    }
+
+    log::info!("Watching {} $PATH directories for executables", dirs.len());
+    let mut last_reload = Instant::now() - Duration::from_millis(500);
+
+    for event in rx {
+        match event {
+            Ok(ev) => {
+                // Package managers touch many files at once; debounce and ignore plain writes.
+                let changes_listing = matches!(
+                    ev.kind,
+                    EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(notify::event::ModifyKind::Name(_))
+                );
+                if changes_listing && last_reload.elapsed() > Duration::from_millis(500) {
+                    last_reload = Instant::now();
+                    let count = refresh_cache();
+                    log::debug!("$PATH executables reloaded: {} entries", count);
+                    let _ = app_handle.emit("path-executables-changed", count);
+                }
+            }
+            Err(e) => log::error!("$PATH watcher error: {}", e),
+        }
+    }
+}
+
+/// Command lines run from `!` mode, most recent first. Kept apart from
+/// `History.recent_queries` so shell commands don't leak into query recall.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CommandHistory {
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(skip)]
+    file_path: Option<PathBuf>,
+}
+
+impl CommandHistory {
+    pub fn load_or_create(config_dir: &Path) -> Self {
+        let file_path = config_dir.join("shell_history.json");
+        let mut history: CommandHistory = fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        history.file_path = Some(file_path);
+        history
+    }
+
+    pub fn push(&mut self, command: &str) {
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+        self.commands.retain(|existing| existing != command);
+        self.commands.insert(0, command.to_string());
+        self.commands.truncate(HISTORY_CAP);
+        self.save();
+    }
+
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.file_path {
+            if let Ok(content) = serde_json::to_string_pretty(self) {
+                let _ = fs::write(path, content);
+            }
+        }
+    }
+}
+
+/// Exec string for `launcher::launch` that runs a full command line through `sh -c`.
+/// `%` is doubled so desktop field-code stripping leaves it intact.
+pub fn detached_exec(command: &str) -> String {
+    format!("sh -c {}", shell_words::quote(&command.replace('%', "%%")))
+}
+
+/// Reads `pipe` to the end on a new thread, keeping the first `OUTPUT_CAP_BYTES`.
+/// The rest is discarded rather than closing the pipe, which would kill the
+/// writer with SIGPIPE.
+fn drain<R: Read + Send + 'static>(mut pipe: R) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = (&mut pipe).take(OUTPUT_CAP_BYTES as u64).read_to_end(&mut buf);
+        let _ = std::io::copy(&mut pipe, &mut std::io::sink());
+        buf
+    })
+}
+
+/// Runs `command` through `sh -c` and returns stdout. Fails on non-zero exit or timeout.
+pub fn run_capture(command: &str, timeout: Duration) -> Result<String, VantaError> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+
+    // Drain both pipes off-thread so a chatty command can't block on a full pipe.
+    let stdout = drain(child.stdout.take().ok_or("Failed to capture stdout")?);
+    let stderr = drain(child.stderr.take().ok_or("Failed to capture stderr")?);
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("'{}' timed out after {}s", command, timeout.as_secs()).into());
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(25)),
+            Err(e) => return Err(format!("Failed to wait for '{}': {}", command, e).into()),
+        }
+    };
+
+    let output = stdout.join().unwrap_or_default();
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr.join().unwrap_or_default()).to_string();
+        let detail = stderr.lines().next().unwrap_or_default().trim().to_string();
+        return Err(format!(
+            "'{}' exited with status {}{}",
+            command,
+            status.code().unwrap_or(-1),
+            if detail.is_empty() { String::new() } else { format!(": {}", detail) }
+        )
+        .into());
+    }
+
+    let text = String::from_utf8_lossy(&output);
+    Ok(text.trim_end_matches('\n').to_string())
+}
+
+fn command_actions(command: &str) -> Vec<ActionHint> {
+    vec![
+        ActionHint {
+            label: "Run in Terminal".to_string(),
+            exec: format!("{}{}", EXEC_TERMINAL_PREFIX, command),
+            shortcut: Some("Shift+Enter".to_string()),
+        },
+        ActionHint {
+            label: "Run and Copy Output".to_string(),
+            exec: format!("{}{}", EXEC_COPY_OUTPUT_PREFIX, command),
+            shortcut: Some("Ctrl+Shift+C".to_string()),
+        },
+    ]
+}
+
+fn command_result(command: &str, title: String, subtitle: &str, score: u32, section: &str) -> SearchResult {
+    SearchResult {
+        title,
+        subtitle: Some(subtitle.to_string()),
+        icon: Some("fa-solid fa-terminal".to_string()),
+        exec: format!("{}{}", EXEC_RUN_PREFIX, command),
+        score,
+        match_indices: vec![],
+        source: ResultSource::Application,
+        actions: Some(command_actions(command)),
+        id: Some(format!("shell:{}", command)),
+        group: None,
+        section: Some(section.to_string()),
+    }
+}
+
+/// Results for `!<command line>`: a run entry for the typed line, matching history,
+/// and `$PATH` completions while the first word is being typed.
+pub fn build_shell_results(
+    input: &str,
+    history: &[String],
+    executables: &[PathExecutable],
+    limit: usize,
+) -> Vec<SearchResult> {
+    let input = input.trim();
+    let mut results = Vec::new();
+
+    if !input.is_empty() {
+        results.push(command_result(
+            input,
+            format!("Run: {}", input),
+            "Run detached · Shift+Enter to run in terminal",
+            ranking_config::SHELL_RUN_SCORE,
+            "Shell",
+        ));
+    }
+
+    let mut from_history: Vec<(u32, &String)> = history
+        .iter()
+        .enumerate()
+        .filter(|(_, cmd)| cmd.as_str() != input)
+        .filter_map(|(pos, cmd)| {
+            let recency = ranking_config::SHELL_HISTORY_RECENCY_BONUS
+                .saturating_sub(pos as u32 * ranking_config::SHELL_HISTORY_RECENCY_STEP);
+            if input.is_empty() {
+                return Some((ranking_config::SHELL_HISTORY_BASE + recency, cmd));
+            }
+            let (raw, _) = fuzzy_score_text(input, cmd)?;
+            let prefix = if cmd.starts_with(input) { ranking_config::SHELL_HISTORY_PREFIX_BONUS } else { 0 };
+            Some((ranking_config::SHELL_HISTORY_BASE + raw + prefix + recency, cmd))
+        })
+        .collect();
+    from_history.sort_by(|a, b| b.0.cmp(&a.0));
+    results.extend(
+        from_history
+            .into_iter()
+            .take(limit)
+            .map(|(score, cmd)| command_result(cmd, cmd.clone(), "From command history", score, "History")),
+    );
+
+    // Complete the program name only while it's the sole word.
+    if !input.is_empty() && !input.contains(char::is_whitespace) {
+        let mut matches: Vec<(u32, &PathExecutable)> = executables
+            .iter()
+            .filter(|exe| exe.name != input)
+            .filter_map(|exe| {
+                if exe.name.starts_with(input) {
+                    let shorter = ranking_config::SHELL_EXEC_PREFIX_BONUS
+                        .saturating_sub(exe.name.len() as u32 * 10);
+                    return Some((ranking_config::SHELL_EXEC_BASE + shorter, exe));
+                }
+                let (raw, _) = fuzzy_score_text(input, &exe.name)?;
+                Some((ranking_config::SHELL_EXEC_BASE + raw, exe))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        results.extend(matches.into_iter().take(limit).map(|(score, exe)| SearchResult {
+            title: exe.name.clone(),
+            subtitle: Some(exe.path.clone()),
+            icon: Some("fa-solid fa-gear".to_string()),
+            exec: format!("fill:!{} ", exe.name),
+            score,
+            match_indices: vec![],
+            source: ResultSource::Application,
+            actions: Some(vec![ActionHint {
+                label: "Run".to_string(),
+                exec: format!("{}{}", EXEC_RUN_PREFIX, exe.name),
+                shortcut: None,
+            }]),
+            id: Some(format!("path-exec:{}", exe.name)),
+            group: None,
+            section: Some("Executables".to_string()),
+        }));
+    }
+
+    results.sort_by(|a, b| b.score.cmp(&a.score));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn touch(path: &Path, mode: u32) {
+        fs::write(path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn scans_only_executables_with_first_dir_winning() {
+        let first = tempfile::tempdir().expect("tempdir");
+        let second = tempfile::tempdir().expect("tempdir");
+        touch(&first.path().join("tool"), 0o755);
+        touch(&first.path().join("notes.txt"), 0o644);
+        touch(&second.path().join("tool"), 0o755);
+        touch(&second.path().join("other"), 0o700);
+
+        let found = scan_dirs(&[first.path().to_path_buf(), second.path().to_path_buf()]);
+        let names: Vec<&str> = found.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["other", "tool"]);
+        let tool = found.iter().find(|e| e.name == "tool").unwrap();
+        assert!(tool.path.starts_with(&first.path().to_string_lossy().to_string()));
+    }
+
+    #[test]
+    fn command_history_dedups_and_persists() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut history = CommandHistory::load_or_create(dir.path());
+        history.push("make test");
+        history.push("git status");
+        history.push("make test");
+        history.push("   ");
+        assert_eq!(history.commands(), &["make test".to_string(), "git status".to_string()]);
+
+        let reloaded = CommandHistory::load_or_create(dir.path());
+        assert_eq!(reloaded.commands(), history.commands());
+    }
+
+    #[test]
+    fn detached_exec_survives_field_code_stripping() {
+        assert_eq!(detached_exec("date +%s"), "sh -c 'date +%%s'");
+    }
+
+    #[test]
+    fn captures_stdout_and_reports_failures() {
+        assert_eq!(run_capture("printf 'a\\nb\\n'", CAPTURE_TIMEOUT).unwrap(), "a\nb");
+        let err = run_capture("echo boom >&2; exit 3", CAPTURE_TIMEOUT).unwrap_err();
+        assert!(err.to_string().contains("boom"));
+        assert!(run_capture("sleep 5", Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn keeps_draining_past_the_output_cap() {
+        let timeout = Duration::from_secs(5);
+        let noisy_stderr = "head -c 1000000 /dev/zero >&2; echo done";
+        assert_eq!(run_capture(noisy_stderr, timeout).unwrap(), "done");
+
+        let big = run_capture("head -c 1000000 /dev/zero | tr '\\0' x", timeout).unwrap();
+        assert_eq!(big.len(), OUTPUT_CAP_BYTES);
+    }
+
+    #[test]
+    fn builds_run_history_and_completion_results() {
+        let executables = vec![
+            PathExecutable { name: "htop".to_string(), path: "/usr/bin/htop".to_string() },
+            PathExecutable { name: "htpasswd".to_string(), path: "/usr/bin/htpasswd".to_string() },
+            PathExecutable { name: "ls".to_string(), path: "/usr/bin/ls".to_string() },
+        ];
+        let history = vec!["htop -d 5".to_string(), "ls -la".to_string()];
+
+        let results = build_shell_results("ht", &history, &executables, 5);
+        assert_eq!(results[0].exec, "shell-run:ht");
+        assert!(results.iter().any(|r| r.exec == "fill:!htop "));
+        assert!(results.iter().any(|r| r.exec == "shell-run:htop -d 5"));
+        assert!(!results.iter().any(|r| r.title == "ls"));
+        let actions = results[0].actions.as_ref().unwrap();
+        assert_eq!(actions[1].exec, "shell-copy:ht");
+
+        let empty = build_shell_results("", &history, &executables, 5);
+        let titles: Vec<&str> = empty.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["htop -d 5", "ls -la"]);
+
+        let with_args = build_shell_results("ls -l", &history, &executables, 5);
+        assert!(!with_args.iter().any(|r| r.section.as_deref() == Some("Executables")));
+    }
+}
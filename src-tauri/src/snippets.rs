@@ -0,0 +1,468 @@
+//! Text snippets: a body expanded by typing its keyword. Bodies can carry
+//! placeholders filled in at expansion time: `{date:%Y-%m-%d}` (or `{date}`),
+//! `{clipboard}`, `{uuid}`, `{cursor}` where the caret should end up after
+//! pasting, and `{input:Name}` values typed after the keyword
+//! (`reply Alice; #4512`). Anything else in braces is kept as written, so code
+//! boilerplate survives.
+
+use std::fs;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Snippet, SnippetsConfig};
+use crate::errors::VantaError;
+use crate::matcher::{ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+/// Exec prefix that expands `<keyword> <inputs>` and copies the result.
+pub const EXEC_COPY_PREFIX: &str = "snippet:";
+/// Exec prefix that expands `<keyword> <inputs>` and pastes it.
+pub const EXEC_PASTE_PREFIX: &str = "snippet-paste:";
+
+const ICON: &str = "fa-solid fa-paragraph";
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Separates values for snippets with more than one `{input:…}`.
+const INPUT_SEPARATOR: char = ';';
+const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SnippetExportPayload {
+    schema_version: u32,
+    snippets: Vec<Snippet>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Part<'a> {
+    Text(&'a str),
+    Date(&'a str),
+    Clipboard,
+    Cursor,
+    Uuid,
+    Input(&'a str),
+}
+
+fn placeholder(inner: &str) -> Option<Part<'_>> {
+    match inner {
+        "date" => Some(Part::Date(DEFAULT_DATE_FORMAT)),
+        "clipboard" => Some(Part::Clipboard),
+        "cursor" => Some(Part::Cursor),
+        "uuid" => Some(Part::Uuid),
+        _ => {
+            if let Some(format) = inner.strip_prefix("date:") {
+                let valid = !StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+                return valid.then_some(Part::Date(format));
+            }
+            inner
+                .strip_prefix("input:")
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(Part::Input)
+        }
+    }
+}
+
+fn parse(body: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = body;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        // A `{` right before the candidate restarts it, as in `{{uuid}`.
+        let open = rest[..close].rfind('{').unwrap_or(open);
+        match placeholder(&rest[open + 1..close]) {
+            Some(part) => {
+                if open > 0 {
+                    parts.push(Part::Text(&rest[..open]));
+                }
+                parts.push(part);
+            }
+            None => parts.push(Part::Text(&rest[..=close])),
+        }
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    parts
+}
+
+/// Names of the `{input:…}` placeholders, in order of first use.
+pub fn input_names(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for part in parse(body) {
+        if let Part::Input(name) = part {
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Splits the text typed after a keyword into one value per input. A single
+/// input takes the whole text; several are separated by `;`.
+fn split_inputs(args: &str, count: usize) -> Vec<String> {
+    let args = args.trim();
+    if count <= 1 {
+        return vec![args.to_string()];
+    }
+    args.splitn(count, INPUT_SEPARATOR)
+        .map(|value| value.trim().to_string())
+        .collect()
+}
+
+/// A rendered snippet and how far the cursor should move back from its end.
+#[derive(Debug, PartialEq)]
+pub struct Expansion {
+    pub text: String,
+    pub cursor_back: usize,
+}
+
+/// Renders `body` with the inputs typed as `args`. `clipboard` is only called
+/// when the body uses `{clipboard}`; when it returns nothing the placeholder is
+/// left as written.
+pub fn render(
+    body: &str,
+    args: &str,
+    now: DateTime<Local>,
+    clipboard: impl FnOnce() -> Option<String>,
+) -> Expansion {
+    let names = input_names(body);
+    let values = split_inputs(args, names.len());
+    let mut clipboard = Some(clipboard);
+    let mut clipboard_text = None;
+    let mut text = String::new();
+    let mut cursor = None;
+
+    for part in parse(body) {
+        match part {
+            Part::Text(literal) => text.push_str(literal),
+            Part::Date(format) => text.push_str(&now.format(format).to_string()),
+            Part::Uuid => text.push_str(&crate::transforms::uuid_v4()),
+            Part::Cursor => {
+                cursor.get_or_insert(text.chars().count());
+            }
+            Part::Clipboard => {
+                if let Some(read) = clipboard.take() {
+                    clipboard_text = read();
+                }
+                text.push_str(clipboard_text.as_deref().unwrap_or("{clipboard}"));
+            }
+            Part::Input(name) => {
+                let value = names
+                    .iter()
+                    .position(|known| known == name)
+                    .and_then(|i| values.get(i));
+                text.push_str(value.map(String::as_str).unwrap_or_default());
+            }
+        }
+    }
+
+    let cursor_back = cursor.map_or(0, |at| text.chars().count() - at);
+    Expansion { text, cursor_back }
+}
+
+/// Keywords are typed as the first word of a query, so they can't be empty or
+/// contain whitespace.
+pub fn is_valid_keyword(keyword: &str) -> bool {
+    !keyword.is_empty() && keyword.len() <= 64 && !keyword.chars().any(char::is_whitespace)
+}
+
+fn find_keyword<'a>(cfg: &'a SnippetsConfig, keyword: &str) -> Option<&'a Snippet> {
+    cfg.entries
+        .iter()
+        .find(|snippet| snippet.keyword.eq_ignore_ascii_case(keyword))
+}
+
+/// Expands an exec value of the form `<keyword> <inputs>`.
+pub fn expand(
+    cfg: &SnippetsConfig,
+    value: &str,
+    clipboard: impl FnOnce() -> Option<String>,
+) -> Result<Expansion, VantaError> {
+    let (keyword, args) = value.split_once(' ').unwrap_or((value, ""));
+    let snippet =
+        find_keyword(cfg, keyword).ok_or_else(|| format!("Snippet '{}' not found", keyword))?;
+    Ok(render(&snippet.body, args, Local::now(), clipboard))
+}
+
+/// Adds or replaces snippets by keyword; returns how many were imported.
+pub fn merge(cfg: &mut SnippetsConfig, incoming: Vec<Snippet>) -> Result<usize, VantaError> {
+    if let Some(invalid) = incoming.iter().find(|s| !is_valid_keyword(&s.keyword)) {
+        return Err(format!("Invalid snippet keyword '{}'", invalid.keyword).into());
+    }
+    let count = incoming.len();
+    for snippet in incoming {
+        match cfg
+            .entries
+            .iter()
+            .position(|existing| existing.keyword.eq_ignore_ascii_case(&snippet.keyword))
+        {
+            Some(pos) => cfg.entries[pos] = snippet,
+            None => cfg.entries.push(snippet),
+        }
+    }
+    Ok(count)
+}
+
+pub fn export_to_path(cfg: &SnippetsConfig, output_path: &str) -> Result<(), VantaError> {
+    let payload = SnippetExportPayload {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        snippets: cfg.entries.clone(),
+    };
+    let json = serde_json::to_string_pretty(&payload)
+        .map_err(|e| format!("Failed to serialize snippet export: {}", e))?;
+    Ok(fs::write(output_path, json)
+        .map_err(|e| format!("Failed to write snippet export '{}': {}", output_path, e))?)
+}
+
+/// Merges the snippets exported to `input_path`; returns how many were imported.
+pub fn import_from_path(cfg: &mut SnippetsConfig, input_path: &str) -> Result<usize, VantaError> {
+    let raw = fs::read_to_string(input_path)
+        .map_err(|e| format!("Failed to read snippet import '{}': {}", input_path, e))?;
+    let payload: SnippetExportPayload = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse snippet import '{}': {}", input_path, e))?;
+    if payload.schema_version > EXPORT_SCHEMA_VERSION {
+        return Err(format!(
+            "Snippet export schema {} is newer than supported {}",
+            payload.schema_version, EXPORT_SCHEMA_VERSION
+        )
+        .into());
+    }
+    merge(cfg, payload.snippets)
+}
+
+fn first_line(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    line.trim().chars().take(100).collect()
+}
+
+fn snippet_result(snippet: &Snippet, args: &str, score: u32) -> SearchResult {
+    let names = input_names(&snippet.body);
+    let needs_inputs = !names.is_empty() && args.trim().is_empty();
+    // `{clipboard}` is read when the snippet is used, not on every keystroke.
+    let preview = render(&snippet.body, args, Local::now(), || None);
+
+    let mut subtitle = snippet.keyword.clone();
+    if !snippet.tags.is_empty() {
+        subtitle = format!("{} · {}", subtitle, snippet.tags.join(", "));
+    }
+    if needs_inputs {
+        subtitle = format!(
+            "Type \"{} {}\" to fill in",
+            snippet.keyword,
+            names.join(&format!("{} ", INPUT_SEPARATOR))
+        );
+    }
+
+    let value = format!("{} {}", snippet.keyword, args.trim());
+    let value = value.trim_end();
+    let (exec, actions) = if needs_inputs {
+        (format!("fill:{} ", snippet.keyword), None)
+    } else {
+        (
+            format!("{}{}", EXEC_COPY_PREFIX, value),
+            Some(vec![ActionHint {
+                label: "Paste".to_string(),
+                exec: format!("{}{}", EXEC_PASTE_PREFIX, value),
+                shortcut: Some("Shift+Enter".to_string()),
+            }]),
+        )
+    };
+
+    SearchResult {
+        title: first_line(&preview.text),
+        subtitle: Some(subtitle),
+        icon: Some(ICON.to_string()),
+        exec,
+        score,
+        match_indices: vec![],
+        source: ResultSource::Application,
+        actions,
+        id: Some(format!("snippet:{}", snippet.keyword)),
+        group: None,
+        section: Some("Snippets".to_string()),
+    }
+}
+
+/// Results for `<keyword> <inputs>` queries plus keyword, tag and body hints
+/// while typing.
+pub fn build_snippet_results(query: &str, cfg: &SnippetsConfig, weight: u32) -> Vec<SearchResult> {
+    let trimmed = query.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+
+    let (keyword, args) = trimmed
+        .split_once(char::is_whitespace)
+        .map(|(k, a)| (k, a.trim()))
+        .unwrap_or((trimmed, ""));
+    if let Some(snippet) = find_keyword(cfg, keyword) {
+        return vec![snippet_result(
+            snippet,
+            args,
+            crate::weighted_score(ranking_config::SNIPPET_KEYWORD_SCORE, weight),
+        )];
+    }
+
+    let needle = trimmed.to_lowercase();
+    cfg.entries
+        .iter()
+        .filter(|snippet| {
+            (needle.len() >= 2 && snippet.keyword.to_lowercase().starts_with(&needle))
+                || snippet.tags.iter().any(|tag| tag.to_lowercase() == needle)
+                || (needle.len() >= 3 && snippet.body.to_lowercase().contains(&needle))
+        })
+        .take(ranking_config::SNIPPET_RESULT_LIMIT)
+        .map(|snippet| {
+            snippet_result(
+                snippet,
+                "",
+                crate::weighted_score(ranking_config::SNIPPET_HINT_SCORE, weight),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_snippet_results, export_to_path, import_from_path, input_names, merge, render,
+        Expansion,
+    };
+    use crate::config::{Snippet, SnippetsConfig};
+    use chrono::{Local, TimeZone};
+
+    fn snippet(keyword: &str, body: &str, tags: &[&str]) -> Snippet {
+        Snippet {
+            keyword: keyword.to_string(),
+            body: body.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn render_at_noon(body: &str, args: &str) -> Expansion {
+        let now = Local.with_ymd_and_hms(2026, 3, 14, 12, 0, 0).unwrap();
+        render(body, args, now, || Some("copied".to_string()))
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        assert_eq!(
+            render_at_noon("{date} {date:%d/%m} {clipboard}", "").text,
+            "2026-03-14 14/03 copied"
+        );
+        assert_eq!(render_at_noon("{uuid}", "").text.len(), 36);
+        assert_eq!(
+            render_at_noon(
+                "Hi {input:Name}, re {input:Ticket}. Bye {input:Name}",
+                "Ana; #42"
+            ),
+            Expansion {
+                text: "Hi Ana, re #42. Bye Ana".to_string(),
+                cursor_back: 0,
+            }
+        );
+        assert_eq!(
+            render_at_noon("Hi {input:Name}", "Ana; Bo").text,
+            "Hi Ana; Bo"
+        );
+        assert_eq!(render_at_noon("{clipboard}", "").text, "copied");
+        let unread = render("{clipboard}", "", Local::now(), || None);
+        assert_eq!(unread.text, "{clipboard}");
+    }
+
+    #[test]
+    fn keeps_unknown_braces_and_tracks_the_cursor() {
+        assert_eq!(
+            render_at_noon("fn main() {\n    {cursor}\n}", ""),
+            Expansion {
+                text: "fn main() {\n    \n}".to_string(),
+                cursor_back: 2,
+            }
+        );
+        assert_eq!(
+            render_at_noon("{{uuid}} {date:%Q} {input:} {", "")
+                .text
+                .len(),
+            "{".len() + 36 + "} {date:%Q} {input:} {".len()
+        );
+        assert_eq!(
+            input_names("{input:A} {input: B } {input:A}"),
+            vec!["A", "B"]
+        );
+    }
+
+    #[test]
+    fn finds_snippets_by_keyword_tag_and_body() {
+        let cfg = SnippetsConfig {
+            entries: vec![
+                snippet("sig", "Best,\nAna", &["email"]),
+                snippet(
+                    "reply",
+                    "Hi {input:Name}, thanks for reaching out.",
+                    &["support"],
+                ),
+            ],
+        };
+
+        let exact = build_snippet_results("SIG", &cfg, 100);
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].title, "Best,");
+        assert_eq!(exact[0].exec, "snippet:sig");
+
+        let prompt = build_snippet_results("reply", &cfg, 100);
+        assert_eq!(prompt[0].exec, "fill:reply ");
+        assert!(prompt[0]
+            .subtitle
+            .as_deref()
+            .unwrap()
+            .contains("reply Name"));
+
+        let filled = build_snippet_results("reply Bo", &cfg, 100);
+        assert_eq!(filled[0].title, "Hi Bo, thanks for reaching out.");
+        assert_eq!(filled[0].exec, "snippet:reply Bo");
+        assert_eq!(
+            filled[0].actions.as_ref().unwrap()[0].exec,
+            "snippet-paste:reply Bo"
+        );
+
+        assert_eq!(build_snippet_results("support", &cfg, 100).len(), 1);
+        assert_eq!(build_snippet_results("reaching", &cfg, 100).len(), 1);
+        assert!(build_snippet_results("s", &cfg, 100).is_empty());
+    }
+
+    #[test]
+    fn merges_imports_by_keyword_and_round_trips_exports() {
+        let mut cfg = SnippetsConfig {
+            entries: vec![snippet("sig", "old", &[])],
+        };
+        let imported = merge(
+            &mut cfg,
+            vec![
+                snippet("SIG", "new", &[]),
+                snippet("addr", "1 Main St", &[]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(imported, 2);
+        assert_eq!(cfg.entries.len(), 2);
+        assert_eq!(cfg.entries[0].body, "new");
+        assert!(merge(&mut cfg, vec![snippet("two words", "", &[])]).is_err());
+        assert_eq!(cfg.entries.len(), 2);
+
+        let path = std::env::temp_dir().join(format!("vanta-snippets-{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        export_to_path(&cfg, &path).unwrap();
+        let mut restored = SnippetsConfig::default();
+        assert_eq!(import_from_path(&mut restored, &path).unwrap(), 2);
+        assert_eq!(restored, cfg);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,503 @@
+use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
+use globset::Glob;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+/// Runs the command after it in the configured terminal.
+pub const EXEC_TERMINAL_PREFIX: &str = "terminal:";
+/// Opens the URI after it with the desktop's default handler.
+pub const EXEC_OPEN_URI_PREFIX: &str = "open-uri:";
+
+const MAX_INCLUDE_DEPTH: usize = 8;
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+static SSH_CACHE: OnceLock<Mutex<Option<(Instant, SshInventory)>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SshHostOrigin {
+    Config,
+    KnownHosts,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshHost {
+    pub alias: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    pub origin: SshHostOrigin,
+}
+
+/// Salted SHA-1 HMAC from a `|1|salt|hash` known_hosts entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashedHost {
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SshInventory {
+    pub hosts: Vec<SshHost>,
+    pub hashed: Vec<HashedHost>,
+}
+
+fn ssh_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".ssh"))
+}
+
+fn is_pattern(value: &str) -> bool {
+    value.contains('*') || value.contains('?') || value.starts_with('!')
+}
+
+fn split_directive(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (key, rest) = line
+        .split_once(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or((line, ""));
+    let value = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '=').trim();
+    Some((key.to_ascii_lowercase(), value.to_string()))
+}
+
+/// Expand an `Include` argument: `~` and relative paths resolve against `ssh_dir`,
+/// and a glob in the file name is matched against the directory listing.
+fn resolve_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let expanded = if let Some(rest) = pattern.strip_prefix("~/") {
+        dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| ssh_dir.join(rest))
+    } else if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        ssh_dir.join(pattern)
+    };
+
+    if !is_pattern(&expanded.to_string_lossy()) {
+        return vec![expanded];
+    }
+
+    let (Some(dir), Some(name)) = (expanded.parent(), expanded.file_name()) else {
+        return Vec::new();
+    };
+    let Ok(matcher) = Glob::new(&name.to_string_lossy()).map(|g| g.compile_matcher()) else {
+        return Vec::new();
+    };
+    let mut out: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.file_name().map(|n| matcher.is_match(n)).unwrap_or(false))
+                .collect()
+        })
+        .unwrap_or_default();
+    out.sort();
+    out
+}
+
+fn parse_config_into(path: &Path, ssh_dir: &Path, depth: usize, out: &mut Vec<SshHost>) {
+    if depth > MAX_INCLUDE_DEPTH {
+        log::warn!("SSH config include depth exceeded at {}", path.display());
+        return;
+    }
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    // Indices into `out` for the aliases declared by the current Host block.
+    let mut current: Vec<usize> = Vec::new();
+
+    for line in raw.lines() {
+        let Some((key, value)) = split_directive(line) else {
+            continue;
+        };
+        match key.as_str() {
+            "host" => {
+                current.clear();
+                for alias in value.split_whitespace() {
+                    // Wildcards and negations describe defaults, not connectable hosts.
+                    if is_pattern(alias) || out.iter().any(|h| h.alias == alias) {
+                        continue;
+                    }
+                    out.push(SshHost {
+                        alias: alias.to_string(),
+                        hostname: None,
+                        user: None,
+                        port: None,
+                        origin: SshHostOrigin::Config,
+                    });
+                    current.push(out.len() - 1);
+                }
+            }
+            "match" => current.clear(),
+            "include" => {
+                for pattern in value.split_whitespace() {
+                    for file in resolve_include(pattern, ssh_dir) {
+                        parse_config_into(&file, ssh_dir, depth + 1, out);
+                    }
+                }
+            }
+            "hostname" | "user" | "port" => {
+                for idx in &current {
+                    let host = &mut out[*idx];
+                    match key.as_str() {
+                        "hostname" if host.hostname.is_none() => host.hostname = Some(value.clone()),
+                        "user" if host.user.is_none() => host.user = Some(value.clone()),
+                        "port" if host.port.is_none() => host.port = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn parse_ssh_config(path: &Path, ssh_dir: &Path) -> Vec<SshHost> {
+    let mut out = Vec::new();
+    parse_config_into(path, ssh_dir, 0, &mut out);
+    out
+}
+
+fn parse_known_host_name(raw: &str) -> Option<(String, Option<u16>)> {
+    if let Some(rest) = raw.strip_prefix('[') {
+        let (host, port) = rest.split_once("]:")?;
+        return Some((host.to_string(), port.parse().ok()));
+    }
+    Some((raw.to_string(), None))
+}
+
+pub fn parse_known_hosts(raw: &str) -> (Vec<SshHost>, Vec<HashedHost>) {
+    let mut hosts: Vec<SshHost> = Vec::new();
+    let mut hashed = Vec::new();
+
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let names = fields.next().unwrap_or_default();
+        if names.starts_with('@') {
+            // @cert-authority / @revoked lines don't describe connectable hosts.
+            continue;
+        }
+        if fields.next().is_none() {
+            continue;
+        }
+
+        if let Some(rest) = names.strip_prefix("|1|") {
+            if let Some((salt, hash)) = rest.split_once('|') {
+                if let (Ok(salt), Ok(hash)) = (B64.decode(salt), B64.decode(hash)) {
+                    hashed.push(HashedHost { salt, hash });
+                }
+            }
+            continue;
+        }
+
+        for name in names.split(',') {
+            if is_pattern(name) {
+                continue;
+            }
+            let Some((host, port)) = parse_known_host_name(name) else {
+                continue;
+            };
+            // Skip bare IPs when the same line also names the host.
+            if host.parse::<std::net::IpAddr>().is_ok() && names.split(',').count() > 1 {
+                continue;
+            }
+            if hosts.iter().any(|h| h.alias == host && h.port == port) {
+                continue;
+            }
+            hosts.push(SshHost {
+                alias: host,
+                hostname: None,
+                user: None,
+                port,
+                origin: SshHostOrigin::KnownHosts,
+            });
+        }
+    }
+
+    (hosts, hashed)
+}
+
+/// Check a hostname against a hashed known_hosts entry (`HashKnownHosts yes`).
+pub fn hashed_host_matches(entry: &HashedHost, host: &str, port: Option<u16>) -> bool {
+    let name = match port {
+        Some(p) if p != 22 => format!("[{}]:{}", host, p),
+        _ => host.to_string(),
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(&entry.salt) else {
+        return false;
+    };
+    mac.update(name.as_bytes());
+    mac.verify_slice(&entry.hash).is_ok()
+}
+
+pub fn load_inventory() -> SshInventory {
+    let Some(dir) = ssh_dir() else {
+        return SshInventory::default();
+    };
+    let mut hosts = parse_ssh_config(&dir.join("config"), &dir);
+    let mut hashed = Vec::new();
+
+    if let Ok(raw) = std::fs::read_to_string(dir.join("known_hosts")) {
+        let (known, known_hashed) = parse_known_hosts(&raw);
+        hashed = known_hashed;
+        for host in known {
+            let covered = hosts.iter().any(|h| {
+                h.alias == host.alias || h.hostname.as_deref() == Some(host.alias.as_str())
+            });
+            if !covered {
+                hosts.push(host);
+            }
+        }
+    }
+
+    SshInventory { hosts, hashed }
+}
+
+/// Cached inventory; ssh config and known_hosts are re-read at most once a minute.
+pub fn cached_inventory() -> SshInventory {
+    let cache = SSH_CACHE.get_or_init(|| Mutex::new(None));
+    let Ok(mut guard) = cache.lock() else {
+        return load_inventory();
+    };
+    match guard.as_ref() {
+        Some((loaded_at, inventory)) if loaded_at.elapsed() < CACHE_TTL => inventory.clone(),
+        _ => {
+            let inventory = load_inventory();
+            *guard = Some((Instant::now(), inventory.clone()));
+            inventory
+        }
+    }
+}
+
+pub fn ssh_command(host: &SshHost) -> String {
+    let mut parts = vec!["ssh".to_string()];
+    if host.origin == SshHostOrigin::KnownHosts {
+        if let Some(port) = host.port.filter(|p| *p != 22) {
+            parts.push("-p".to_string());
+            parts.push(port.to_string());
+        }
+    }
+    parts.push(shell_words::quote(&host.alias).into_owned());
+    parts.join(" ")
+}
+
+pub fn sftp_uri(host: &SshHost) -> String {
+    let target = host.hostname.as_deref().unwrap_or(&host.alias);
+    let user = host
+        .user
+        .as_deref()
+        .map(|u| format!("{}@", u))
+        .unwrap_or_default();
+    let port = host
+        .port
+        .filter(|p| *p != 22)
+        .map(|p| format!(":{}", p))
+        .unwrap_or_default();
+    format!("sftp://{}{}{}/", user, target, port)
+}
+
+fn host_result(host: &SshHost, subtitle_hint: &str, score: u32, match_indices: Vec<u32>) -> SearchResult {
+    let command = ssh_command(host);
+    let mut detail = host.hostname.clone().unwrap_or_else(|| host.alias.clone());
+    if let Some(user) = &host.user {
+        detail = format!("{}@{}", user, detail);
+    }
+    if let Some(port) = host.port.filter(|p| *p != 22) {
+        detail = format!("{}:{}", detail, port);
+    }
+
+    SearchResult {
+        title: host.alias.clone(),
+        subtitle: Some(format!("{} · {}", detail, subtitle_hint)),
+        icon: Some("fa-solid fa-server".to_string()),
+        exec: format!("{}{}", EXEC_TERMINAL_PREFIX, command),
+        score,
+        match_indices,
+        source: ResultSource::Application,
+        actions: Some(vec![
+            ActionHint {
+                label: "Copy SSH Command".to_string(),
+                exec: format!("copy:{}", command),
+                shortcut: Some("Ctrl+Shift+C".to_string()),
+            },
+            ActionHint {
+                label: "Open in File Manager (SFTP)".to_string(),
+                exec: format!("{}{}", EXEC_OPEN_URI_PREFIX, sftp_uri(host)),
+                shortcut: Some("Shift+Enter".to_string()),
+            },
+        ]),
+        id: Some(format!("ssh:{}", host.alias)),
+        group: None,
+        section: Some("SSH".to_string()),
+    }
+}
+
+fn looks_like_hostname(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+}
+
+/// SSH host results. A leading `ssh ` narrows the query to hosts and boosts them.
+pub fn search_hosts(query: &str, inventory: &SshInventory, limit: usize, weight: u32) -> Vec<SearchResult> {
+    let trimmed = query.trim();
+    let (needle, explicit) = match trimmed.strip_prefix("ssh ") {
+        Some(rest) => (rest.trim(), true),
+        None => (trimmed, false),
+    };
+    if needle.is_empty() || (!explicit && needle.len() < 2) {
+        return Vec::new();
+    }
+    let explicit_bonus = if explicit { ranking_config::SSH_EXPLICIT_BONUS } else { 0 };
+
+    let mut out: Vec<SearchResult> = inventory
+        .hosts
+        .iter()
+        .filter_map(|host| {
+            let (raw, indices) = match fuzzy_score_text(needle, &host.alias) {
+                Some(hit) => hit,
+                None => (fuzzy_score_text(needle, host.hostname.as_deref()?)?.0, Vec::new()),
+            };
+            let base = ranking_config::SSH_FUZZY_BASE
+                .saturating_add(raw.saturating_mul(ranking_config::SSH_FUZZY_MULTIPLIER))
+                .saturating_add(explicit_bonus);
+            let hint = match host.origin {
+                SshHostOrigin::Config => "~/.ssh/config",
+                SshHostOrigin::KnownHosts => "known_hosts",
+            };
+            Some(host_result(host, hint, crate::weighted_score(base, weight), indices))
+        })
+        .collect();
+
+    // Hashed known_hosts entries can't be listed, but we can confirm a typed host.
+    if explicit && looks_like_hostname(needle) && !out.iter().any(|r| r.title == needle) {
+        let known = inventory
+            .hashed
+            .iter()
+            .any(|entry| hashed_host_matches(entry, needle, None));
+        if known {
+            let host = SshHost {
+                alias: needle.to_string(),
+                hostname: None,
+                user: None,
+                port: None,
+                origin: SshHostOrigin::KnownHosts,
+            };
+            let base = ranking_config::SSH_FUZZY_BASE.saturating_add(explicit_bonus);
+            out.push(host_result(
+                &host,
+                "known_hosts (hashed)",
+                crate::weighted_score(base, weight),
+                Vec::new(),
+            ));
+        }
+    }
+
+    out.sort_by(|a, b| b.score.cmp(&a.score));
+    out.truncate(limit);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, contents: &str) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn parses_host_blocks_includes_and_skips_wildcards() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(
+            &dir.path().join("config"),
+            "Host *\n  ServerAliveInterval 30\n\nHost bastion prod-db !staging\n  HostName 10.0.0.5\n  User ops\n  Port 2222\n\nInclude config.d/*.conf\n\nHost web-?\n  User nobody\n",
+        );
+        write(
+            &dir.path().join("config.d/team.conf"),
+            "Host build build.internal\n  HostName=build.example.com\n",
+        );
+
+        let hosts = parse_ssh_config(&dir.path().join("config"), dir.path());
+        let aliases: Vec<&str> = hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["bastion", "prod-db", "build", "build.internal"]);
+
+        let bastion = &hosts[0];
+        assert_eq!(bastion.hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(bastion.user.as_deref(), Some("ops"));
+        assert_eq!(bastion.port, Some(2222));
+        assert_eq!(hosts[1].hostname.as_deref(), Some("10.0.0.5"));
+        assert_eq!(hosts[2].hostname.as_deref(), Some("build.example.com"));
+    }
+
+    #[test]
+    fn include_cycles_are_bounded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        write(&dir.path().join("config"), "Include config\nHost loop\n");
+        let hosts = parse_ssh_config(&dir.path().join("config"), dir.path());
+        assert_eq!(hosts.len(), 1);
+    }
+
+    #[test]
+    fn parses_known_hosts_plain_ports_and_hashed() {
+        let salt = B64.encode(b"0123456789abcdef0123");
+        let mut mac = Hmac::<Sha1>::new_from_slice(b"0123456789abcdef0123").unwrap();
+        mac.update(b"secret.example.com");
+        let hash = B64.encode(mac.finalize().into_bytes());
+
+        let raw = format!(
+            "# comment\ngit.example.com,192.168.1.10 ssh-ed25519 AAAA\n[jump.example.com]:2200 ssh-rsa AAAA\n@cert-authority *.example.com ssh-rsa AAAA\n|1|{}|{} ssh-ed25519 AAAA\n",
+            salt, hash
+        );
+        let (hosts, hashed) = parse_known_hosts(&raw);
+        let aliases: Vec<&str> = hosts.iter().map(|h| h.alias.as_str()).collect();
+        assert_eq!(aliases, vec!["git.example.com", "jump.example.com"]);
+        assert_eq!(hosts[1].port, Some(2200));
+        assert_eq!(ssh_command(&hosts[1]), "ssh -p 2200 jump.example.com");
+
+        assert_eq!(hashed.len(), 1);
+        assert!(hashed_host_matches(&hashed[0], "secret.example.com", None));
+        assert!(!hashed_host_matches(&hashed[0], "other.example.com", None));
+
+        let inventory = SshInventory { hosts, hashed };
+        let results = search_hosts("ssh secret.example.com", &inventory, 5, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exec, "terminal:ssh secret.example.com");
+    }
+
+    #[test]
+    fn results_offer_copy_and_sftp_actions() {
+        let inventory = SshInventory {
+            hosts: vec![SshHost {
+                alias: "bastion".to_string(),
+                hostname: Some("10.0.0.5".to_string()),
+                user: Some("ops".to_string()),
+                port: Some(2222),
+                origin: SshHostOrigin::Config,
+            }],
+            hashed: Vec::new(),
+        };
+        let results = search_hosts("bast", &inventory, 5, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exec, "terminal:ssh bastion");
+        let actions = results[0].actions.as_ref().unwrap();
+        assert_eq!(actions[0].exec, "copy:ssh bastion");
+        assert_eq!(actions[1].exec, "open-uri:sftp://ops@10.0.0.5:2222/");
+        assert!(search_hosts("b", &inventory, 5, 100).is_empty());
+    }
+}
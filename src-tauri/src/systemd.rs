@@ -0,0 +1,598 @@
+use serde_json::json;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::errors::VantaError;
+use crate::extensions;
+use crate::matcher::{fuzzy_score_text, ActionHint, ResultSource, SearchResult};
+use crate::permissions::{self, Capability, Decision};
+use crate::ranking_config;
+
+/// Permission subject used with the shared decision store.
+pub const PERMISSION_ID: &str = "builtin.systemd";
+pub const EXEC_PREFIX: &str = "systemd:";
+
+const CACHE_TTL: Duration = Duration::from_secs(5);
+const SHOW_PROPERTIES: &str =
+    "Id,Description,LoadState,ActiveState,SubState,UnitFileState,Result,ExecMainStatus";
+
+#[derive(Default)]
+struct UnitCache {
+    loaded_at: Option<Instant>,
+    /// Bumped by `invalidate_cache` so a refresh started earlier doesn't count as fresh.
+    generation: u64,
+    units: Arc<Vec<UserUnit>>,
+    refreshing: Option<u64>,
+}
+
+static UNIT_CACHE: OnceLock<Mutex<UnitCache>> = OnceLock::new();
+/// `is_authorized` for the permissions revision it was computed at.
+static AUTHORIZED: Mutex<Option<(u64, bool)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitKind {
+    Service,
+    Timer,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserUnit {
+    pub name: String,
+    pub kind: UnitKind,
+    pub description: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub unit_file_state: String,
+    pub result: String,
+    pub exec_main_status: Option<i32>,
+}
+
+impl UserUnit {
+    pub fn is_active(&self) -> bool {
+        matches!(
+            self.active_state.as_str(),
+            "active" | "activating" | "reloading"
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.unit_file_state.starts_with("enabled")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitVerb {
+    Start,
+    Stop,
+    Restart,
+    Enable,
+    Disable,
+    Journal,
+}
+
+impl UnitVerb {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "start" => Some(Self::Start),
+            "stop" => Some(Self::Stop),
+            "restart" => Some(Self::Restart),
+            "enable" => Some(Self::Enable),
+            "disable" => Some(Self::Disable),
+            "journal" => Some(Self::Journal),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+            Self::Enable => "enable",
+            Self::Disable => "disable",
+            Self::Journal => "journal",
+        }
+    }
+}
+
+fn unit_kind(name: &str) -> Option<UnitKind> {
+    if name.ends_with(".service") {
+        Some(UnitKind::Service)
+    } else if name.ends_with(".timer") {
+        Some(UnitKind::Timer)
+    } else {
+        None
+    }
+}
+
+/// Names from `systemctl --user list-unit-files --plain --no-legend`. Template units
+/// (`foo@.service`) are skipped since they can't be started without an instance.
+pub fn parse_unit_files(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|name| unit_kind(name).is_some() && !name.contains("@."))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names from `systemctl --user list-units --all --plain --no-legend`, which also
+/// covers transient and instantiated units that have no unit file of their own.
+pub fn parse_loaded_units(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim_start_matches('●').split_whitespace().next())
+        .filter(|name| unit_kind(name).is_some())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse `systemctl show -p …` output: one `Key=Value` block per unit, blank-line separated.
+pub fn parse_show(output: &str) -> Vec<UserUnit> {
+    let mut units = Vec::new();
+    for block in output.split("\n\n") {
+        let mut unit = UserUnit {
+            name: String::new(),
+            kind: UnitKind::Service,
+            description: String::new(),
+            active_state: String::new(),
+            sub_state: String::new(),
+            unit_file_state: String::new(),
+            result: String::new(),
+            exec_main_status: None,
+        };
+        let mut load_state = String::new();
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key {
+                "Id" => unit.name = value.to_string(),
+                "Description" => unit.description = value.to_string(),
+                "LoadState" => load_state = value.to_string(),
+                "ActiveState" => unit.active_state = value.to_string(),
+                "SubState" => unit.sub_state = value.to_string(),
+                "UnitFileState" => unit.unit_file_state = value.to_string(),
+                "Result" => unit.result = value.to_string(),
+                "ExecMainStatus" => unit.exec_main_status = value.parse().ok(),
+                _ => {}
+            }
+        }
+        let Some(kind) = unit_kind(&unit.name) else {
+            continue;
+        };
+        if load_state == "not-found" {
+            continue;
+        }
+        unit.kind = kind;
+        units.push(unit);
+    }
+    units
+}
+
+fn systemctl_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn load_units() -> Vec<UserUnit> {
+    let listing = [
+        "--type=service,timer",
+        "--plain",
+        "--no-legend",
+        "--no-pager",
+    ];
+
+    let mut names = systemctl_output(&[&["list-unit-files"][..], &listing[..]].concat())
+        .map(|out| parse_unit_files(&out))
+        .unwrap_or_default();
+    if let Some(out) = systemctl_output(&[&["list-units", "--all"][..], &listing[..]].concat()) {
+        for name in parse_loaded_units(&out) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    if names.is_empty() {
+        return Vec::new();
+    }
+
+    let mut args: Vec<&str> = vec!["show", "--no-pager", "-p", SHOW_PROPERTIES];
+    args.extend(names.iter().map(String::as_str));
+    let mut units = systemctl_output(&args)
+        .map(|out| parse_show(&out))
+        .unwrap_or_default();
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    units
+}
+
+fn unit_cache() -> &'static Mutex<UnitCache> {
+    UNIT_CACHE.get_or_init(|| Mutex::new(UnitCache::default()))
+}
+
+/// Snapshot of the user units. Listing them takes several `systemctl` calls, so
+/// it is redone on a background thread when stale and searches use the last
+/// snapshot meanwhile, which is empty until the first listing finishes.
+pub fn cached_units() -> Arc<Vec<UserUnit>> {
+    let Ok(mut guard) = unit_cache().lock() else {
+        return Arc::new(Vec::new());
+    };
+    let stale = guard
+        .loaded_at
+        .map(|t| t.elapsed() >= CACHE_TTL)
+        .unwrap_or(true);
+    let generation = guard.generation;
+    if stale && guard.refreshing != Some(generation) {
+        guard.refreshing = Some(generation);
+        std::thread::spawn(move || {
+            let units = Arc::new(load_units());
+            let Ok(mut guard) = unit_cache().lock() else {
+                return;
+            };
+            if guard.generation == generation {
+                guard.units = units;
+                guard.loaded_at = Some(Instant::now());
+            }
+            if guard.refreshing == Some(generation) {
+                guard.refreshing = None;
+            }
+        });
+    }
+    Arc::clone(&guard.units)
+}
+
+/// Marks the units stale so the next search lists them again, e.g. after an action.
+pub fn invalidate_cache() {
+    if let Ok(mut guard) = unit_cache().lock() {
+        guard.loaded_at = None;
+        guard.generation += 1;
+    }
+}
+
+/// Whether searching may list units without prompting. Listing runs `systemctl`,
+/// so it sits behind the same `Capability::Shell` decision as the actions.
+/// The answer is cached until the permissions store is saved again, since this
+/// runs on every keystroke.
+pub fn is_authorized() -> bool {
+    let revision = permissions::revision();
+    if let Ok(guard) = AUTHORIZED.lock() {
+        if let Some((cached_at, allowed)) = *guard {
+            if cached_at == revision {
+                return allowed;
+            }
+        }
+    }
+    let response = permissions::get_decision_for(PERMISSION_ID, &[Capability::Shell]);
+    let allowed = response.decision == Decision::Allow && response.missing_caps.is_empty();
+    if let Ok(mut guard) = AUTHORIZED.lock() {
+        *guard = Some((revision, allowed));
+    }
+    allowed
+}
+
+/// Runs the shared permission check and maps it to the `PERMISSION_NEEDED:` /
+/// `PERMISSION_DENIED:` errors the launcher turns into a prompt.
+pub fn ensure_permission() -> Result<(), VantaError> {
+    let caps = [Capability::Shell];
+    match extensions::check_extension_permissions(PERMISSION_ID, &caps) {
+        Ok(()) => Ok(()),
+        Err(extensions::PermissionError::NeedsPrompt { missing_caps }) => {
+            let payload = json!({
+                "script_id": PERMISSION_ID,
+                "missing_caps": missing_caps,
+                "requested_caps": caps,
+            });
+            Err(format!("PERMISSION_NEEDED:{}", payload).into())
+        }
+        Err(extensions::PermissionError::Deny) => {
+            let payload = json!({
+                "script_id": PERMISSION_ID,
+                "requested_caps": caps,
+            });
+            Err(format!("PERMISSION_DENIED:{}", payload).into())
+        }
+    }
+}
+
+pub fn journal_command(unit: &str) -> String {
+    format!(
+        "journalctl --user -u {} -n 200 -f",
+        shell_words::quote(unit)
+    )
+}
+
+/// Run a unit action. Journal opens in the terminal; everything else goes through
+/// `systemctl --user` and is recorded in the audit log.
+pub fn run_action(verb: UnitVerb, unit: &str) -> Result<(), VantaError> {
+    ensure_permission()?;
+    if unit_kind(unit).is_none() || unit.starts_with('-') {
+        return Err(format!("Not a user service or timer: {}", unit).into());
+    }
+
+    if verb == UnitVerb::Journal {
+        return crate::launcher::launch_terminal_command(&journal_command(unit));
+    }
+
+    let output = Command::new("systemctl")
+        .args(["--user", verb.as_str(), unit])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+    invalidate_cache();
+
+    let outcome = if output.status.success() {
+        "ok"
+    } else {
+        "failed"
+    };
+    let _ = permissions::record_audit_event(
+        "systemd_unit_action",
+        "user",
+        unit,
+        outcome,
+        Some(format!("verb={}", verb.as_str())),
+    );
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!(
+            "systemctl --user {} {} failed: {}",
+            verb.as_str(),
+            unit,
+            stderr.lines().next().unwrap_or("unknown error").trim()
+        )
+        .into())
+    }
+}
+
+fn status_line(unit: &UserUnit) -> String {
+    let mut parts = Vec::new();
+    if !unit.description.is_empty() {
+        parts.push(unit.description.clone());
+    }
+    parts.push(format!("{} ({})", unit.active_state, unit.sub_state));
+    if !unit.unit_file_state.is_empty() {
+        parts.push(unit.unit_file_state.clone());
+    }
+    if unit.kind == UnitKind::Service && !unit.is_active() {
+        match (unit.exec_main_status, unit.result.as_str()) {
+            (Some(code), "success") | (Some(code), "") => parts.push(format!("last exit {}", code)),
+            (Some(code), result) => parts.push(format!("last exit {} ({})", code, result)),
+            (None, _) => {}
+        }
+    }
+    parts.join(" · ")
+}
+
+fn action(label: &str, verb: UnitVerb, unit: &str, shortcut: Option<&str>) -> ActionHint {
+    ActionHint {
+        label: label.to_string(),
+        exec: format!("{}{}:{}", EXEC_PREFIX, verb.as_str(), unit),
+        shortcut: shortcut.map(str::to_string),
+    }
+}
+
+fn unit_result(unit: &UserUnit, score: u32, match_indices: Vec<u32>) -> SearchResult {
+    let run = if unit.is_active() {
+        ("Stop", UnitVerb::Stop)
+    } else {
+        ("Start", UnitVerb::Start)
+    };
+    let toggle = if unit.is_enabled() {
+        ("Disable", UnitVerb::Disable)
+    } else {
+        ("Enable", UnitVerb::Enable)
+    };
+
+    SearchResult {
+        title: unit.name.clone(),
+        subtitle: Some(status_line(unit)),
+        icon: Some(
+            match unit.kind {
+                UnitKind::Service => "fa-solid fa-gears",
+                UnitKind::Timer => "fa-solid fa-clock",
+            }
+            .to_string(),
+        ),
+        // Enter only opens the journal; anything that changes the unit is an action.
+        exec: format!(
+            "{}{}:{}",
+            EXEC_PREFIX,
+            UnitVerb::Journal.as_str(),
+            unit.name
+        ),
+        score,
+        match_indices,
+        source: ResultSource::Application,
+        actions: Some(vec![
+            action(run.0, run.1, &unit.name, None),
+            action("Restart", UnitVerb::Restart, &unit.name, Some("Ctrl+R")),
+            action(toggle.0, toggle.1, &unit.name, None),
+        ]),
+        id: Some(format!("systemd:{}", unit.name)),
+        group: Some(
+            match unit.kind {
+                UnitKind::Service => "Services",
+                UnitKind::Timer => "Timers",
+            }
+            .to_string(),
+        ),
+        section: Some("systemd".to_string()),
+    }
+}
+
+/// Split an explicit `systemd …` / `unit …` query into its remainder.
+fn explicit_query(query: &str) -> Option<&str> {
+    let trimmed = query.trim();
+    [
+        "systemd",
+        "systemctl",
+        "unit",
+        "units",
+        "service",
+        "services",
+    ]
+    .iter()
+    .find_map(|kw| {
+        let rest = trimmed.strip_prefix(kw)?;
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+    })
+}
+
+pub fn is_explicit_query(query: &str) -> bool {
+    explicit_query(query).is_some()
+}
+
+/// Shown instead of units until the user has allowed `Capability::Shell`.
+pub fn authorize_result(query: &str) -> Option<SearchResult> {
+    explicit_query(query)?;
+    Some(SearchResult {
+        title: "Allow systemd user units".to_string(),
+        subtitle: Some("Listing and controlling user units needs shell permission".to_string()),
+        icon: Some("fa-solid fa-shield-halved".to_string()),
+        exec: format!("{}authorize:", EXEC_PREFIX),
+        score: ranking_config::SYSTEMD_EXPLICIT_BONUS,
+        match_indices: vec![],
+        source: ResultSource::Application,
+        actions: None,
+        id: Some("systemd:authorize".to_string()),
+        group: None,
+        section: Some("systemd".to_string()),
+    })
+}
+
+pub fn search_units(
+    query: &str,
+    units: &[UserUnit],
+    limit: usize,
+    weight: u32,
+) -> Vec<SearchResult> {
+    let (needle, explicit) = match explicit_query(query) {
+        Some(rest) => (rest, true),
+        None => (query.trim(), false),
+    };
+    if !explicit && needle.len() < 3 {
+        return Vec::new();
+    }
+    let bonus = if explicit {
+        ranking_config::SYSTEMD_EXPLICIT_BONUS
+    } else {
+        0
+    };
+
+    let mut out: Vec<SearchResult> = units
+        .iter()
+        .filter_map(|unit| {
+            if needle.is_empty() {
+                let base = ranking_config::SYSTEMD_FUZZY_BASE + bonus;
+                return Some(unit_result(
+                    unit,
+                    crate::weighted_score(base, weight),
+                    Vec::new(),
+                ));
+            }
+            let (raw, indices) = match fuzzy_score_text(needle, &unit.name) {
+                Some(hit) => hit,
+                None if explicit => (fuzzy_score_text(needle, &unit.description)?.0, Vec::new()),
+                None => return None,
+            };
+            let base = ranking_config::SYSTEMD_FUZZY_BASE
+                .saturating_add(raw.saturating_mul(ranking_config::SYSTEMD_FUZZY_MULTIPLIER))
+                .saturating_add(bonus);
+            Some(unit_result(
+                unit,
+                crate::weighted_score(base, weight),
+                indices,
+            ))
+        })
+        .collect();
+    out.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+    out.truncate(limit);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOW: &str = "Id=syncthing.service\nDescription=Syncthing - Open Source Continuous File Synchronization\nLoadState=loaded\nActiveState=active\nSubState=running\nUnitFileState=enabled\nResult=success\nExecMainStatus=0\n\nId=backup.timer\nDescription=Nightly backup\nLoadState=loaded\nActiveState=inactive\nSubState=dead\nUnitFileState=disabled\nResult=success\nExecMainStatus=\n\nId=devproxy.service\nDescription=Dev proxy\nLoadState=loaded\nActiveState=failed\nSubState=failed\nUnitFileState=enabled\nResult=exit-code\nExecMainStatus=1\n\nId=gone.service\nLoadState=not-found\nActiveState=inactive\n";
+
+    #[test]
+    fn parses_listings_and_skips_templates() {
+        let files = "syncthing.service enabled enabled\ngetty@.service static -\nbackup.timer disabled enabled\ndefault.target static -\n";
+        assert_eq!(
+            parse_unit_files(files),
+            vec!["syncthing.service", "backup.timer"]
+        );
+
+        let loaded = "  app-foo@autostart.service loaded active running Foo\n● devproxy.service loaded failed failed Dev proxy\n";
+        assert_eq!(
+            parse_loaded_units(loaded),
+            vec!["app-foo@autostart.service", "devproxy.service"]
+        );
+    }
+
+    #[test]
+    fn parses_show_blocks() {
+        let units = parse_show(SHOW);
+        assert_eq!(units.len(), 3);
+        assert!(units[0].is_active() && units[0].is_enabled());
+        assert_eq!(units[1].kind, UnitKind::Timer);
+        assert_eq!(units[1].exec_main_status, None);
+        assert_eq!(units[2].exec_main_status, Some(1));
+        assert_eq!(
+            status_line(&units[2]),
+            "Dev proxy · failed (failed) · enabled · last exit 1 (exit-code)"
+        );
+    }
+
+    #[test]
+    fn enter_opens_the_journal_and_actions_follow_state() {
+        let units = parse_show(SHOW);
+        let results = search_units("syncthing", &units, 5, 100);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].exec, "systemd:journal:syncthing.service");
+        let actions = results[0].actions.as_ref().unwrap();
+        let execs: Vec<&str> = actions.iter().map(|a| a.exec.as_str()).collect();
+        assert_eq!(
+            execs,
+            vec![
+                "systemd:stop:syncthing.service",
+                "systemd:restart:syncthing.service",
+                "systemd:disable:syncthing.service",
+            ]
+        );
+
+        let proxy = search_units("devproxy", &units, 5, 100);
+        assert_eq!(proxy[0].exec, "systemd:journal:devproxy.service");
+        let actions = proxy[0].actions.as_ref().unwrap();
+        assert_eq!(actions[0].exec, "systemd:start:devproxy.service");
+    }
+
+    #[test]
+    fn explicit_keyword_lists_all_and_matches_descriptions() {
+        let units = parse_show(SHOW);
+        assert_eq!(search_units("systemd", &units, 10, 100).len(), 3);
+        let nightly = search_units("unit nightly", &units, 10, 100);
+        assert_eq!(nightly[0].title, "backup.timer");
+        assert!(search_units("sy", &units, 10, 100).is_empty());
+        assert!(authorize_result("services").is_some());
+        assert!(authorize_result("syncthing").is_none());
+        assert_eq!(
+            journal_command("my unit.service"),
+            "journalctl --user -u 'my unit.service' -n 200 -f"
+        );
+    }
+}
@@ -0,0 +1,511 @@
+//! Text transforms: `b64 hello`, `unb64 aGVsbG8=`, `urlencode a b`, `sha256 x`,
+//! `uuid`, `json {"a":1}` and `#ff8800 to hsl`. The same transforms are offered
+//! as actions on clipboard results.
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use rand::RngCore;
+use regex::Regex;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::sync::LazyLock;
+
+use crate::matcher::{ActionHint, ResultSource, SearchResult};
+use crate::ranking_config;
+
+const ICON: &str = "fa-solid fa-wand-magic-sparkles";
+/// Longest title shown for a transform output; the copy keeps everything.
+const TITLE_CHARS: usize = 100;
+
+static COLOR_CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(#[0-9a-f]{3,8}|(?:rgba?|hsla?)\([^)]*\))\s+(?:to|in|as)\s+(hex|rgb|hsl)\s*$",
+    )
+    .expect("color conversion regex must be valid")
+});
+
+static COLOR_FUNCTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(rgba?|hsla?)\(\s*([^)]*)\)$").expect("color function regex must be valid")
+});
+
+/// A transform output plus other renderings of the same value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub label: &'static str,
+    pub output: String,
+    pub alternates: Vec<(&'static str, String)>,
+}
+
+impl Transform {
+    fn new(label: &'static str, output: String) -> Self {
+        Self {
+            label,
+            output,
+            alternates: Vec::new(),
+        }
+    }
+}
+
+pub fn base64_encode(text: &str) -> String {
+    STANDARD.encode(text)
+}
+
+/// Accepts standard and URL-safe alphabets, padded or not. Binary payloads
+/// that aren't UTF-8 come back as `None`.
+pub fn base64_decode(text: &str) -> Option<String> {
+    let cleaned: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&cleaned).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+pub fn sha256_hex(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+pub fn sha1_hex(text: &str) -> String {
+    hex::encode(Sha1::digest(text.as_bytes()))
+}
+
+/// A random (version 4) UUID.
+pub fn uuid_v4() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// `(pretty, minified)` for valid JSON.
+pub fn json_formats(text: &str) -> Option<(String, String)> {
+    let value: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    let pretty = serde_json::to_string_pretty(&value).ok()?;
+    let minified = serde_json::to_string(&value).ok()?;
+    Some((pretty, minified))
+}
+
+/// An sRGB color with alpha in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: f64,
+}
+
+fn parse_channel(raw: &str, scale: f64) -> Option<f64> {
+    let raw = raw.trim();
+    match raw.strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|p| p / 100.0 * scale),
+        None => raw.parse::<f64>().ok(),
+    }
+}
+
+fn parse_alpha(raw: &str) -> Option<f64> {
+    let alpha = parse_channel(raw, 1.0)?;
+    (0.0..=1.0).contains(&alpha).then_some(alpha)
+}
+
+/// `hsl(h, s%, l%)` to sRGB channels in `0.0..=255.0`.
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    ((r + m) * 255.0, (g + m) * 255.0, (b + m) * 255.0)
+}
+
+impl Color {
+    /// `#f80`, `#ff8800`, `#ff880080`, `rgb(255, 136, 0)`, `rgba(…)`, `hsl(32, 100%, 50%)`.
+    pub fn parse(text: &str) -> Option<Color> {
+        let trimmed = text.trim();
+        if let Some(hex) = trimmed.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let expanded: String = match hex.len() {
+                3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+                6 | 8 => hex.to_string(),
+                _ => return None,
+            };
+            let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+            let a = match expanded.len() {
+                8 => channel(6)? as f64 / 255.0,
+                _ => 1.0,
+            };
+            return Some(Color {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+                a,
+            });
+        }
+
+        let caps = COLOR_FUNCTION_RE.captures(trimmed)?;
+        let function = caps.get(1)?.as_str().to_ascii_lowercase();
+        let args: Vec<&str> = caps
+            .get(2)?
+            .as_str()
+            .split([',', '/', ' '])
+            .filter(|part| !part.trim().is_empty())
+            .collect();
+        if !(3..=4).contains(&args.len()) {
+            return None;
+        }
+        let a = args.get(3).map_or(Some(1.0), |raw| parse_alpha(raw))?;
+        let (r, g, b) = if function.starts_with("rgb") {
+            (
+                parse_channel(args[0], 255.0)?,
+                parse_channel(args[1], 255.0)?,
+                parse_channel(args[2], 255.0)?,
+            )
+        } else {
+            let h = args[0].trim().trim_end_matches("deg").parse::<f64>().ok()?;
+            let s = parse_channel(args[1], 1.0)?;
+            let l = parse_channel(args[2], 1.0)?;
+            if !(0.0..=1.0).contains(&s) || !(0.0..=1.0).contains(&l) {
+                return None;
+            }
+            hsl_to_rgb(h, s, l)
+        };
+        let to_u8 = |v: f64| (0.0..=255.0).contains(&v).then(|| v.round() as u8);
+        Some(Color {
+            r: to_u8(r)?,
+            g: to_u8(g)?,
+            b: to_u8(b)?,
+            a,
+        })
+    }
+
+    pub fn to_hex(&self) -> String {
+        let mut hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        if self.a < 1.0 {
+            hex.push_str(&format!("{:02x}", (self.a * 255.0).round() as u8));
+        }
+        hex
+    }
+
+    pub fn to_rgb(&self) -> String {
+        if self.a < 1.0 {
+            format!(
+                "rgba({}, {}, {}, {})",
+                self.r,
+                self.g,
+                self.b,
+                format_alpha(self.a)
+            )
+        } else {
+            format!("rgb({}, {}, {})", self.r, self.g, self.b)
+        }
+    }
+
+    pub fn to_hsl(&self) -> String {
+        let (r, g, b) = (
+            self.r as f64 / 255.0,
+            self.g as f64 / 255.0,
+            self.b as f64 / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        let (h, s) = if delta == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+            let h = if max == r {
+                60.0 * ((g - b) / delta).rem_euclid(6.0)
+            } else if max == g {
+                60.0 * ((b - r) / delta + 2.0)
+            } else {
+                60.0 * ((r - g) / delta + 4.0)
+            };
+            (h, s)
+        };
+        let (h, s, l) = (
+            h.round() as u32 % 360,
+            (s * 100.0).round(),
+            (l * 100.0).round(),
+        );
+        if self.a < 1.0 {
+            format!("hsla({}, {}%, {}%, {})", h, s, l, format_alpha(self.a))
+        } else {
+            format!("hsl({}, {}%, {}%)", h, s, l)
+        }
+    }
+
+    /// Every rendering as `(label, value)`, in hex, rgb, hsl order.
+    fn renderings(&self) -> [(&'static str, String); 3] {
+        [
+            ("Copy Hex", self.to_hex()),
+            ("Copy RGB", self.to_rgb()),
+            ("Copy HSL", self.to_hsl()),
+        ]
+    }
+}
+
+fn format_alpha(alpha: f64) -> String {
+    format!("{:.2}", alpha)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn convert_color(query: &str) -> Option<Transform> {
+    let caps = COLOR_CONVERSION_RE.captures(query)?;
+    let color = Color::parse(caps.get(1)?.as_str())?;
+    let target = caps.get(2)?.as_str().to_ascii_lowercase();
+    let (label, output) = match target.as_str() {
+        "hex" => ("Hex Color", color.to_hex()),
+        "rgb" => ("RGB Color", color.to_rgb()),
+        _ => ("HSL Color", color.to_hsl()),
+    };
+    let alternates = color
+        .renderings()
+        .into_iter()
+        .filter(|(_, value)| *value != output)
+        .collect();
+    Some(Transform {
+        label,
+        output,
+        alternates,
+    })
+}
+
+/// Recognises a transform keyword at the start of `query` and applies it.
+pub fn evaluate(query: &str) -> Option<Transform> {
+    let trimmed = query.trim();
+    if let Some(transform) = convert_color(trimmed) {
+        return Some(transform);
+    }
+
+    let (keyword, rest) = trimmed
+        .split_once(char::is_whitespace)
+        .map_or((trimmed, ""), |(keyword, rest)| {
+            (keyword, rest.trim_start())
+        });
+    let keyword = keyword.to_ascii_lowercase();
+    if keyword == "uuid" && rest.is_empty() {
+        let uuid = uuid_v4();
+        return Some(Transform {
+            alternates: vec![("Copy Uppercase", uuid.to_uppercase())],
+            ..Transform::new("UUID v4", uuid)
+        });
+    }
+    if rest.is_empty() {
+        return None;
+    }
+
+    match keyword.as_str() {
+        "b64" | "base64" => Some(Transform::new("Base64", base64_encode(rest))),
+        "unb64" | "b64d" => base64_decode(rest).map(|text| Transform::new("Base64 Decoded", text)),
+        "urlencode" => Some(Transform::new(
+            "URL Encoded",
+            urlencoding::encode(rest).into_owned(),
+        )),
+        "urldecode" => urlencoding::decode(rest)
+            .ok()
+            .map(|text| Transform::new("URL Decoded", text.into_owned())),
+        "sha256" => Some(Transform::new("SHA-256", sha256_hex(rest))),
+        "sha1" => Some(Transform::new("SHA-1", sha1_hex(rest))),
+        "json" => json_formats(rest).map(|(pretty, minified)| Transform {
+            alternates: vec![("Copy Minified", minified)],
+            ..Transform::new("Pretty JSON", pretty)
+        }),
+        _ => None,
+    }
+}
+
+fn title_for(output: &str) -> String {
+    let flattened = output.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() > TITLE_CHARS {
+        let mut title: String = flattened.chars().take(TITLE_CHARS).collect();
+        title.push('…');
+        title
+    } else {
+        flattened
+    }
+}
+
+fn copy_action(label: &str, value: &str) -> ActionHint {
+    ActionHint {
+        label: label.to_string(),
+        exec: format!("copy:{}", value),
+        shortcut: None,
+    }
+}
+
+pub fn search_transforms(query: &str, weight: u32) -> Vec<SearchResult> {
+    let Some(transform) = evaluate(query) else {
+        return Vec::new();
+    };
+    let actions = (!transform.alternates.is_empty()).then(|| {
+        transform
+            .alternates
+            .iter()
+            .map(|(label, value)| copy_action(label, value))
+            .collect()
+    });
+    vec![SearchResult {
+        title: title_for(&transform.output),
+        subtitle: Some(format!("{} · Click to Copy", transform.label)),
+        icon: Some(ICON.to_string()),
+        exec: format!("copy:{}", transform.output),
+        score: crate::weighted_score(ranking_config::TRANSFORM_SCORE, weight),
+        match_indices: Vec::new(),
+        source: ResultSource::Transform,
+        actions,
+        id: None,
+        group: None,
+        section: Some("Transform".to_string()),
+    }]
+}
+
+/// Transform actions for a clipboard entry, chosen by its detected content type.
+pub fn clipboard_actions(content: &str, content_type: &str) -> Vec<ActionHint> {
+    let trimmed = content.trim();
+    let mut actions = Vec::new();
+    match content_type {
+        "json" => {
+            if let Some((pretty, minified)) = json_formats(trimmed) {
+                actions.push(copy_action("Copy Pretty JSON", &pretty));
+                actions.push(copy_action("Copy Minified JSON", &minified));
+            }
+        }
+        "color" => {
+            if let Some(color) = Color::parse(trimmed) {
+                for (label, value) in color.renderings() {
+                    if value != trimmed {
+                        actions.push(copy_action(label, &value));
+                    }
+                }
+            }
+        }
+        "url" => {
+            if let Ok(decoded) = urlencoding::decode(trimmed) {
+                if decoded != trimmed {
+                    actions.push(copy_action("Copy URL Decoded", &decoded));
+                }
+            }
+        }
+        _ => {
+            if !trimmed.contains(char::is_whitespace) {
+                if let Some(decoded) = base64_decode(trimmed).filter(|text| !text.is_empty()) {
+                    actions.push(copy_action("Copy Base64 Decoded", &decoded));
+                }
+            }
+            actions.push(copy_action("Copy as Base64", &base64_encode(content)));
+            actions.push(copy_action(
+                "Copy URL Encoded",
+                &urlencoding::encode(content),
+            ));
+        }
+    }
+    actions.push(copy_action("Copy SHA-256", &sha256_hex(content)));
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clipboard_actions, evaluate, search_transforms, Color};
+
+    fn output(query: &str) -> String {
+        evaluate(query).expect(query).output
+    }
+
+    #[test]
+    fn encodes_decodes_and_hashes() {
+        assert_eq!(output("b64 hello world"), "aGVsbG8gd29ybGQ=");
+        assert_eq!(output("unb64 aGVsbG8gd29ybGQ="), "hello world");
+        assert_eq!(output("unb64 aGVsbG8gd29ybGQ"), "hello world");
+        assert_eq!(output("urlencode a b&c"), "a%20b%26c");
+        assert_eq!(output("urldecode a%20b%26c"), "a b&c");
+        assert_eq!(
+            output("sha256 abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(evaluate("b64").is_none());
+        assert!(evaluate("firefox").is_none());
+
+        let uuid = output("uuid");
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+    }
+
+    #[test]
+    fn formats_json_both_ways() {
+        let transform = evaluate(r#"json {"a": [1, 2], "b": null}"#).unwrap();
+        assert_eq!(
+            transform.output,
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": null\n}"
+        );
+        assert_eq!(transform.alternates[0].1, r#"{"a":[1,2],"b":null}"#);
+        assert!(evaluate("json {not json").is_none());
+
+        let results = search_transforms(r#"json {"a":1}"#, 100);
+        assert_eq!(results[0].title, "{ \"a\": 1 }");
+        assert_eq!(results[0].exec, "copy:{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn converts_colors() {
+        assert_eq!(output("#ff8800 to hsl"), "hsl(32, 100%, 50%)");
+        assert_eq!(output("#f80 to rgb"), "rgb(255, 136, 0)");
+        assert_eq!(output("hsl(32, 100%, 50%) to hex"), "#ff8800");
+        assert_eq!(output("rgba(255, 0, 0, 0.5) to hex"), "#ff000080");
+        assert_eq!(
+            Color::parse("#ff000080").unwrap().to_hsl(),
+            "hsla(0, 100%, 50%, 0.5)"
+        );
+        assert!(evaluate("#ggg to rgb").is_none());
+        assert!(evaluate("rgb(300, 0, 0) to hex").is_none());
+    }
+
+    #[test]
+    fn offers_actions_by_clipboard_content_type() {
+        let labels = |content: &str, content_type: &str| -> Vec<String> {
+            clipboard_actions(content, content_type)
+                .into_iter()
+                .map(|action| action.label)
+                .collect()
+        };
+        assert_eq!(
+            labels("#ff8800", "color"),
+            vec!["Copy RGB", "Copy HSL", "Copy SHA-256"]
+        );
+        assert_eq!(
+            labels("[1, 2]", "json"),
+            vec!["Copy Pretty JSON", "Copy Minified JSON", "Copy SHA-256"]
+        );
+        assert_eq!(
+            labels("aGVsbG8=", "text")[0],
+            "Copy Base64 Decoded".to_string()
+        );
+        let text_actions = clipboard_actions("hi there", "text");
+        assert_eq!(text_actions[0].exec, "copy:aGkgdGhlcmU=");
+    }
+}
@@ -0,0 +1,718 @@
+//! Dimensional unit conversion for the calculator.
+//!
+//! Every unit is a scale factor (plus an offset for absolute temperatures) relative to
+//! the SI base of its dimension. Compound expressions like `km/h`, `m/s^2` or `sq ft`
+//! are folded into a single factor and dimension, so any two expressions with matching
+//! dimensions convert into each other.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Exponents over the base dimensions: length, mass, time, temperature, information, angle.
+pub type Dim = [i8; 6];
+
+const NONE: Dim = [0, 0, 0, 0, 0, 0];
+const LENGTH: Dim = [1, 0, 0, 0, 0, 0];
+const AREA: Dim = [2, 0, 0, 0, 0, 0];
+const VOLUME: Dim = [3, 0, 0, 0, 0, 0];
+const MASS: Dim = [0, 1, 0, 0, 0, 0];
+const TIME: Dim = [0, 0, 1, 0, 0, 0];
+const FREQUENCY: Dim = [0, 0, -1, 0, 0, 0];
+const SPEED: Dim = [1, 0, -1, 0, 0, 0];
+const FORCE: Dim = [1, 1, -2, 0, 0, 0];
+const ENERGY: Dim = [2, 1, -2, 0, 0, 0];
+const POWER: Dim = [2, 1, -3, 0, 0, 0];
+const PRESSURE: Dim = [-1, 1, -2, 0, 0, 0];
+const TEMPERATURE: Dim = [0, 0, 0, 1, 0, 0];
+const INFORMATION: Dim = [0, 0, 0, 0, 1, 0];
+const BITRATE: Dim = [0, 0, -1, 0, 1, 0];
+const ANGLE: Dim = [0, 0, 0, 0, 0, 1];
+
+/// How a unit accepts prefixes.
+#[derive(Clone, Copy, PartialEq)]
+enum Prefixes {
+    None,
+    /// SI prefixes from atto to yotta (`km`, `mg`, `kWh`).
+    Si,
+    /// Only multiplying SI prefixes plus binary ones (`kB`, `MiB`, `Gbit`).
+    Data,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    pub factor: f64,
+    pub offset: f64,
+    pub dim: Dim,
+}
+
+impl Unit {
+    const fn new(factor: f64, dim: Dim) -> Self {
+        Self {
+            factor,
+            offset: 0.0,
+            dim,
+        }
+    }
+
+    /// Value in this unit → value in the SI base unit.
+    fn base_value(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    /// Value in the SI base unit → value in this unit.
+    fn scaled_value(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+}
+
+struct UnitDef {
+    symbols: &'static [&'static str],
+    /// Matched case-insensitively, singular and plural.
+    names: &'static [&'static str],
+    unit: Unit,
+    prefixes: Prefixes,
+}
+
+const fn def(
+    symbols: &'static [&'static str],
+    names: &'static [&'static str],
+    factor: f64,
+    dim: Dim,
+    prefixes: Prefixes,
+) -> UnitDef {
+    UnitDef {
+        symbols,
+        names,
+        unit: Unit::new(factor, dim),
+        prefixes,
+    }
+}
+
+const PI: f64 = std::f64::consts::PI;
+const INCH: f64 = 0.0254;
+const FOOT: f64 = 0.3048;
+const MILE: f64 = 1_609.344;
+const POUND: f64 = 0.453_592_37;
+const LITRE: f64 = 0.001;
+const US_GALLON: f64 = 3.785_411_784 * LITRE;
+const HOUR: f64 = 3_600.0;
+const DAY: f64 = 86_400.0;
+
+static UNITS: &[UnitDef] = &[
+    // Length
+    def(&["m"], &["meter", "metre"], 1.0, LENGTH, Prefixes::Si),
+    def(
+        &["in", "\""],
+        &["inch", "inches"],
+        INCH,
+        LENGTH,
+        Prefixes::None,
+    ),
+    def(
+        &["ft", "'"],
+        &["foot", "feet"],
+        FOOT,
+        LENGTH,
+        Prefixes::None,
+    ),
+    def(&["yd"], &["yard"], 3.0 * FOOT, LENGTH, Prefixes::None),
+    def(&["mi"], &["mile"], MILE, LENGTH, Prefixes::None),
+    def(&["nmi"], &["nauticalmile"], 1_852.0, LENGTH, Prefixes::None),
+    def(&["Å"], &["angstrom"], 1e-10, LENGTH, Prefixes::None),
+    def(&["au"], &[], 149_597_870_700.0, LENGTH, Prefixes::None),
+    def(
+        &["ly"],
+        &["lightyear"],
+        9_460_730_472_580_800.0,
+        LENGTH,
+        Prefixes::None,
+    ),
+    def(
+        &["pc"],
+        &["parsec"],
+        3.085_677_581_491_367e16,
+        LENGTH,
+        Prefixes::None,
+    ),
+    // Area (only named units; everything else is a length squared)
+    def(&["ha"], &["hectare"], 10_000.0, AREA, Prefixes::None),
+    def(&["ac"], &["acre"], 4_046.856_422_4, AREA, Prefixes::None),
+    // Volume
+    def(
+        &["L", "l"],
+        &["liter", "litre"],
+        LITRE,
+        VOLUME,
+        Prefixes::Si,
+    ),
+    def(&["cc"], &[], 1e-6, VOLUME, Prefixes::None),
+    def(&["gal"], &["gallon"], US_GALLON, VOLUME, Prefixes::None),
+    def(&["qt"], &["quart"], US_GALLON / 4.0, VOLUME, Prefixes::None),
+    def(&["pt"], &["pint"], US_GALLON / 8.0, VOLUME, Prefixes::None),
+    def(&[], &["cup"], US_GALLON / 16.0, VOLUME, Prefixes::None),
+    def(
+        &["floz"],
+        &["fluidounce"],
+        US_GALLON / 128.0,
+        VOLUME,
+        Prefixes::None,
+    ),
+    def(
+        &["tbsp"],
+        &["tablespoon"],
+        US_GALLON / 256.0,
+        VOLUME,
+        Prefixes::None,
+    ),
+    def(
+        &["tsp"],
+        &["teaspoon"],
+        US_GALLON / 768.0,
+        VOLUME,
+        Prefixes::None,
+    ),
+    // Mass
+    def(&["g"], &["gram", "gramme"], 0.001, MASS, Prefixes::Si),
+    def(
+        &["t"],
+        &["tonne", "metricton"],
+        1_000.0,
+        MASS,
+        Prefixes::None,
+    ),
+    def(&["lb", "lbs"], &["pound"], POUND, MASS, Prefixes::None),
+    def(&["oz"], &["ounce"], POUND / 16.0, MASS, Prefixes::None),
+    def(&["st"], &["stone"], 14.0 * POUND, MASS, Prefixes::None),
+    def(
+        &["ton"],
+        &["shortton"],
+        2_000.0 * POUND,
+        MASS,
+        Prefixes::None,
+    ),
+    def(&["ct"], &["carat"], 0.000_2, MASS, Prefixes::None),
+    // Time
+    def(&["s", "sec"], &["second"], 1.0, TIME, Prefixes::Si),
+    def(&["min"], &["minute"], 60.0, TIME, Prefixes::None),
+    def(&["h", "hr"], &["hour"], HOUR, TIME, Prefixes::None),
+    def(&["d"], &["day"], DAY, TIME, Prefixes::None),
+    def(&["wk"], &["week"], 7.0 * DAY, TIME, Prefixes::None),
+    def(&["mo"], &["month"], 30.436_875 * DAY, TIME, Prefixes::None),
+    def(
+        &["yr", "y"],
+        &["year"],
+        365.242_5 * DAY,
+        TIME,
+        Prefixes::None,
+    ),
+    def(&["Hz"], &["hertz"], 1.0, FREQUENCY, Prefixes::Si),
+    def(&["rpm"], &[], 1.0 / 60.0, FREQUENCY, Prefixes::None),
+    // Speed
+    def(&["mph"], &[], MILE / HOUR, SPEED, Prefixes::None),
+    def(&["kph", "kmh"], &[], 1_000.0 / HOUR, SPEED, Prefixes::None),
+    def(
+        &["kn", "kt"],
+        &["knot"],
+        1_852.0 / HOUR,
+        SPEED,
+        Prefixes::None,
+    ),
+    def(&["fps"], &[], FOOT, SPEED, Prefixes::None),
+    // Force, energy, power, pressure
+    def(&["N"], &["newton"], 1.0, FORCE, Prefixes::Si),
+    def(&["lbf"], &[], 4.448_221_615_260_5, FORCE, Prefixes::None),
+    def(&["J"], &["joule"], 1.0, ENERGY, Prefixes::Si),
+    def(&["cal"], &["calorie"], 4.184, ENERGY, Prefixes::Si),
+    def(&["Wh"], &[], HOUR, ENERGY, Prefixes::Si),
+    def(&["eV"], &[], 1.602_176_634e-19, ENERGY, Prefixes::Si),
+    def(
+        &["BTU", "Btu"],
+        &[],
+        1_055.055_852_62,
+        ENERGY,
+        Prefixes::None,
+    ),
+    def(&["W"], &["watt"], 1.0, POWER, Prefixes::Si),
+    def(
+        &["hp"],
+        &["horsepower"],
+        745.699_871_582_270_2,
+        POWER,
+        Prefixes::None,
+    ),
+    def(&["Pa"], &["pascal"], 1.0, PRESSURE, Prefixes::Si),
+    def(&["bar"], &[], 100_000.0, PRESSURE, Prefixes::Si),
+    def(
+        &["atm"],
+        &["atmosphere"],
+        101_325.0,
+        PRESSURE,
+        Prefixes::None,
+    ),
+    def(&["psi"], &[], 6_894.757_293_168, PRESSURE, Prefixes::None),
+    def(&["mmHg"], &[], 133.322_387_415, PRESSURE, Prefixes::None),
+    def(&["inHg"], &[], 3_386.388_64, PRESSURE, Prefixes::None),
+    def(
+        &["Torr", "torr"],
+        &[],
+        101_325.0 / 760.0,
+        PRESSURE,
+        Prefixes::None,
+    ),
+    // Information (base: bit)
+    def(&["bit", "b"], &["bit"], 1.0, INFORMATION, Prefixes::Data),
+    def(&["B"], &["byte"], 8.0, INFORMATION, Prefixes::Data),
+    def(&["bps"], &[], 1.0, BITRATE, Prefixes::Data),
+    // Angles (base: radian)
+    def(&["rad"], &["radian"], 1.0, ANGLE, Prefixes::Si),
+    def(
+        &["deg", "°"],
+        &["degree"],
+        PI / 180.0,
+        ANGLE,
+        Prefixes::None,
+    ),
+    def(
+        &["grad", "gon"],
+        &["gradian"],
+        PI / 200.0,
+        ANGLE,
+        Prefixes::None,
+    ),
+    def(
+        &["arcmin"],
+        &["arcminute"],
+        PI / 10_800.0,
+        ANGLE,
+        Prefixes::None,
+    ),
+    def(
+        &["arcsec"],
+        &["arcsecond"],
+        PI / 648_000.0,
+        ANGLE,
+        Prefixes::None,
+    ),
+    def(
+        &["turn", "rev"],
+        &["revolution"],
+        2.0 * PI,
+        ANGLE,
+        Prefixes::None,
+    ),
+];
+
+/// Absolute temperature scales carry an offset, so they are kept out of the factor table.
+static TEMPERATURES: &[(&[&str], Unit)] = &[
+    (&["K", "kelvin", "kelvins"], Unit::new(1.0, TEMPERATURE)),
+    (
+        &["°C", "C", "c", "degC", "celsius"],
+        Unit {
+            factor: 1.0,
+            offset: 273.15,
+            dim: TEMPERATURE,
+        },
+    ),
+    (
+        &["°F", "F", "f", "degF", "fahrenheit"],
+        Unit {
+            factor: 5.0 / 9.0,
+            offset: 459.67 * 5.0 / 9.0,
+            dim: TEMPERATURE,
+        },
+    ),
+    (
+        &["°R", "degR", "rankine"],
+        Unit::new(5.0 / 9.0, TEMPERATURE),
+    ),
+];
+
+static SI_PREFIXES: &[(&str, f64)] = &[
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("da", 1e1),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("μ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+];
+
+static BINARY_PREFIXES: &[(&str, f64)] = &[
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("Ei", 1_152_921_504_606_846_976.0),
+];
+
+/// Exact symbol lookup (case-sensitive) plus lowercase names and plurals.
+struct UnitTables {
+    symbols: HashMap<&'static str, Unit>,
+    names: HashMap<String, Unit>,
+}
+
+static LOOKUP: LazyLock<UnitTables> = LazyLock::new(|| {
+    let mut symbols = HashMap::new();
+    let mut names = HashMap::new();
+    for def in UNITS {
+        for symbol in def.symbols {
+            symbols.entry(*symbol).or_insert(def.unit);
+        }
+        for name in def.names {
+            names.insert(name.to_string(), def.unit);
+            names.insert(format!("{}s", name), def.unit);
+        }
+    }
+    for (aliases, unit) in TEMPERATURES {
+        for alias in *aliases {
+            symbols.insert(*alias, *unit);
+            names.insert(alias.to_lowercase(), *unit);
+        }
+    }
+    UnitTables { symbols, names }
+});
+
+static PHRASE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(light[\s-]?years?|nautical\s+miles?|fluid\s+ounces?|fl\.?\s*oz|metric\s+tons?|short\s+tons?)\b",
+    )
+    .expect("unit phrase regex must be valid")
+});
+
+static TERM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([^\d\^]+?)(?:\^?(-?\d))?$").expect("unit term regex must be valid")
+});
+
+fn prefixed(token: &str) -> Option<Unit> {
+    for def in UNITS {
+        if def.prefixes == Prefixes::None {
+            continue;
+        }
+        for symbol in def.symbols {
+            let Some(prefix) = token.strip_suffix(symbol) else {
+                continue;
+            };
+            let scale = match def.prefixes {
+                Prefixes::Data => BINARY_PREFIXES
+                    .iter()
+                    .chain(SI_PREFIXES.iter().filter(|(_, f)| *f >= 1e3))
+                    .find(|(p, _)| *p == prefix)
+                    .map(|(_, f)| *f),
+                _ => SI_PREFIXES
+                    .iter()
+                    .find(|(p, _)| *p == prefix)
+                    .map(|(_, f)| *f),
+            };
+            if let Some(scale) = scale {
+                return Some(Unit::new(def.unit.factor * scale, def.unit.dim));
+            }
+        }
+    }
+    None
+}
+
+/// Lowercase data sizes as people usually type them: `mb` is megabytes, `mbit` megabits.
+fn casual_data_unit(lower: &str) -> Option<Unit> {
+    let (stem, base) = if let Some(stem) = lower.strip_suffix("bps") {
+        (stem, Unit::new(1.0, BITRATE))
+    } else if let Some(stem) = lower.strip_suffix("bit") {
+        (stem, Unit::new(1.0, INFORMATION))
+    } else if let Some(stem) = lower.strip_suffix('b') {
+        (stem, Unit::new(8.0, INFORMATION))
+    } else {
+        return None;
+    };
+    let scale = BINARY_PREFIXES
+        .iter()
+        .chain(SI_PREFIXES.iter().filter(|(_, f)| *f >= 1e3))
+        .find(|(p, _)| p.to_lowercase() == stem)
+        .map(|(_, f)| *f)?;
+    Some(Unit::new(base.factor * scale, base.dim))
+}
+
+/// Resolve a single unit token (no powers or operators).
+pub fn lookup_unit(token: &str) -> Option<Unit> {
+    let UnitTables { symbols, names } = &*LOOKUP;
+    if let Some(unit) = symbols.get(token) {
+        return Some(*unit);
+    }
+    if let Some(unit) = prefixed(token) {
+        return Some(unit);
+    }
+    let lower = token.to_lowercase();
+    if let Some(unit) = names.get(&lower) {
+        return Some(*unit);
+    }
+    // `kilometers`, `megabytes`: spelled-out prefix on a named unit.
+    for (word, scale) in [
+        ("kilo", 1e3),
+        ("mega", 1e6),
+        ("giga", 1e9),
+        ("tera", 1e12),
+        ("centi", 1e-2),
+        ("milli", 1e-3),
+        ("micro", 1e-6),
+        ("nano", 1e-9),
+    ] {
+        if let Some(rest) = lower.strip_prefix(word) {
+            if let Some(unit) = names.get(rest).filter(|u| u.offset == 0.0) {
+                return Some(Unit::new(unit.factor * scale, unit.dim));
+            }
+        }
+    }
+    casual_data_unit(&lower)
+}
+
+fn combine(dim: &mut Dim, other: Dim, power: i8) {
+    for (slot, value) in dim.iter_mut().zip(other) {
+        *slot += value * power;
+    }
+}
+
+/// Parse a unit expression such as `km/h`, `m/s^2`, `sq ft`, `kWh`, `°F` or `miles per hour`.
+pub fn parse_unit_expr(raw: &str) -> Option<Unit> {
+    let text = PHRASE_RE.replace_all(raw.trim(), |caps: &regex::Captures| {
+        let phrase = caps[1].to_lowercase();
+        if phrase.starts_with("light") {
+            "ly"
+        } else if phrase.starts_with("nautical") {
+            "nmi"
+        } else if phrase.starts_with("metric") {
+            "t"
+        } else if phrase.starts_with("short") {
+            "ton"
+        } else {
+            "floz"
+        }
+        .to_string()
+    });
+    let text = text.replace('²', "^2").replace('³', "^3").replace('·', "*");
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut factor = 1.0;
+    let mut offset = 0.0;
+    let mut dim = NONE;
+    let mut terms = 0;
+
+    for (index, part) in text.split('/').enumerate() {
+        let mut sign: i8 = if index == 0 { 1 } else { -1 };
+        let mut pending_power = 1i8;
+        let mut part_terms = 0;
+        for word in part
+            .split(|c: char| c.is_whitespace() || c == '*')
+            .filter(|w| !w.is_empty())
+        {
+            match word.to_lowercase().as_str() {
+                "sq" | "square" => {
+                    pending_power = 2;
+                    continue;
+                }
+                "cu" | "cubic" => {
+                    pending_power = 3;
+                    continue;
+                }
+                // `per` splits like `/`: the terms after it go in the denominator.
+                "per" => {
+                    if part_terms == 0 {
+                        return None;
+                    }
+                    sign = -1;
+                    part_terms = 0;
+                    continue;
+                }
+                _ => {}
+            }
+            let caps = TERM_RE.captures(word)?;
+            let name = caps.get(1)?.as_str();
+            let power = match caps.get(2) {
+                Some(p) => p.as_str().parse::<i8>().ok()?,
+                None => 1,
+            } * pending_power
+                * sign;
+            pending_power = 1;
+            let unit = lookup_unit(name)?;
+            factor *= unit.factor.powi(power as i32);
+            offset = unit.offset;
+            combine(&mut dim, unit.dim, power);
+            terms += 1;
+            part_terms += 1;
+        }
+        if part_terms == 0 {
+            return None;
+        }
+    }
+
+    // Offsets only make sense for a bare absolute temperature; in compounds the unit
+    // is a difference (`°C/s`).
+    if terms != 1 || dim != TEMPERATURE {
+        offset = 0.0;
+    }
+    Some(Unit {
+        factor,
+        offset,
+        dim,
+    })
+}
+
+/// Convert `value` between two unit expressions with identical dimensions.
+pub fn convert(value: f64, from: &str, to: &str) -> Option<f64> {
+    let from = parse_unit_expr(from)?;
+    let to = parse_unit_expr(to)?;
+    if from.dim != to.dim {
+        return None;
+    }
+    Some(to.scaled_value(from.base_value(value)))
+}
+
+/// Split `<expression> <unit>` at the first position where the tail parses as a unit
+/// and the head evaluates, so `(2+3) ft` and `1e3 m` both work.
+fn split_quantity(text: &str, eval: &impl Fn(&str) -> Option<f64>) -> Option<(f64, String)> {
+    let mut prev: Option<char> = None;
+    for (index, ch) in text.char_indices() {
+        let starts_word = ch.is_alphabetic() || matches!(ch, '°' | 'µ' | 'Å' | '"' | '\'');
+        let after_word = prev.is_some_and(|p| p.is_alphabetic() || p == '°');
+        prev = Some(ch);
+        if !starts_word || after_word || index == 0 {
+            continue;
+        }
+        let (head, tail) = text.split_at(index);
+        let head = head.trim();
+        if head.is_empty() || parse_unit_expr(tail).is_none() {
+            continue;
+        }
+        if let Some(value) = eval(head) {
+            return Some((value, tail.trim().to_string()));
+        }
+    }
+    None
+}
+
+/// A conversion query: `<expr> <unit> (to|in|as|->) <unit>`, or `<expr><unit> <unit>`.
+/// Returns the converted value and the target unit as the user typed it.
+pub fn evaluate_conversion(
+    query: &str,
+    eval: impl Fn(&str) -> Option<f64>,
+) -> Option<(f64, String)> {
+    let trimmed = query.trim();
+    // ASCII lowercasing keeps byte offsets valid for slicing `trimmed`.
+    let lower = trimmed.to_ascii_lowercase();
+
+    let split = [" to ", " in ", " as ", " -> ", " => ", "->", "=>"]
+        .iter()
+        .filter_map(|kw| lower.rfind(kw).map(|pos| (pos, kw.len())))
+        .max_by_key(|(pos, _)| *pos)
+        .map(|(pos, len)| (&trimmed[..pos], &trimmed[pos + len..]));
+
+    let (source, target) = match split {
+        Some((source, target)) => (source.trim(), target.trim()),
+        None => {
+            // Legacy shorthand: `10lb kg`.
+            let (source, target) = trimmed.rsplit_once(char::is_whitespace)?;
+            (source.trim(), target.trim())
+        }
+    };
+
+    let target_unit = parse_unit_expr(target)?;
+    let (value, from) = split_quantity(source, &eval)?;
+    let from_unit = parse_unit_expr(&from)?;
+    if from_unit.dim != target_unit.dim {
+        return None;
+    }
+    let converted = target_unit.scaled_value(from_unit.base_value(value));
+    converted
+        .is_finite()
+        .then(|| (converted, target.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        ((a - b) / b.abs().max(1e-12)).abs() < 1e-9
+    }
+
+    fn eval(expr: &str) -> Option<f64> {
+        meval::eval_str(expr).ok()
+    }
+
+    #[test]
+    fn converts_si_prefixes_and_compounds() {
+        assert!(close(convert(1.0, "km/h", "m/s").unwrap(), 1.0 / 3.6));
+        assert!(close(convert(60.0, "mph", "km/h").unwrap(), 96.56064));
+        assert!(close(convert(1.0, "m/s^2", "km/h/s").unwrap(), 3.6));
+        assert!(close(convert(2.0, "sq ft", "in²").unwrap(), 288.0));
+        assert!(close(convert(1.0, "kWh", "MJ").unwrap(), 3.6));
+        assert!(close(
+            convert(1.0, "miles per hour", "knots").unwrap(),
+            0.868_976_241_9
+        ));
+        assert!(close(convert(1.0, "ha", "acres").unwrap(), 2.471_053_814_7));
+        assert!(close(convert(1.0, "gal", "L").unwrap(), 3.785_411_784));
+        assert!(close(convert(1.0, "atm", "psi").unwrap(), 14.695_948_775_5));
+        assert!(close(convert(180.0, "deg", "rad").unwrap(), PI));
+        assert!(convert(1.0, "kg", "m").is_none());
+    }
+
+    #[test]
+    fn converts_data_sizes() {
+        assert!(close(convert(3.0, "GiB", "MB").unwrap(), 3_221.225_472));
+        assert!(close(convert(1.0, "MB", "KiB").unwrap(), 976.5625));
+        assert!(close(convert(1.0, "B", "bit").unwrap(), 8.0));
+        assert!(close(convert(100.0, "Mbps", "MB/s").unwrap(), 12.5));
+        assert!(close(convert(1.0, "gb", "mb").unwrap(), 1_000.0));
+    }
+
+    #[test]
+    fn converts_temperatures_with_offsets() {
+        assert!(close(convert(100.0, "°C", "°F").unwrap(), 212.0));
+        assert!(close(convert(-40.0, "F", "C").unwrap(), -40.0));
+        assert!(close(convert(0.0, "celsius", "K").unwrap(), 273.15));
+        // Compound temperature units are differences.
+        assert!(close(convert(1.0, "C/s", "K/min").unwrap(), 60.0));
+    }
+
+    #[test]
+    fn evaluates_conversion_queries() {
+        let (value, unit) = evaluate_conversion("(2+3) ft to cm", eval).unwrap();
+        assert!(close(value, 152.4));
+        assert_eq!(unit, "cm");
+        let (value, _) = evaluate_conversion("60 mph in km/h", eval).unwrap();
+        assert!(close(value, 96.56064));
+        let (value, _) = evaluate_conversion("10lb kg", eval).unwrap();
+        assert!(close(value, 4.535_923_7));
+        let (value, _) = evaluate_conversion("1e3 m to km", eval).unwrap();
+        assert!(close(value, 1.0));
+        assert!(evaluate_conversion("go to bed", eval).is_none());
+        assert!(evaluate_conversion("10 usd to eur", eval).is_none());
+        assert!(evaluate_conversion("5 kg to m", eval).is_none());
+    }
+
+    #[test]
+    fn dangling_per_is_rejected() {
+        for expr in ["per", "per hour", "mph per", "m per per s"] {
+            assert!(parse_unit_expr(expr).is_none(), "{:?} should not parse", expr);
+        }
+        assert!(evaluate_conversion("paid per", eval).is_none());
+        assert!(evaluate_conversion("cost per", eval).is_none());
+        let speed = parse_unit_expr("m per s per s").unwrap();
+        assert_eq!(speed.dim, parse_unit_expr("m/s^2").unwrap().dim);
+    }
+}
@@ -36,6 +36,11 @@ pub fn init_window(_window: &WebviewWindow, app_handle: &AppHandle) -> Result<()
 
 /// Show the Vanta window and focus it.
 pub fn show_window(window: &WebviewWindow) -> Result<(), VantaError> {
+    // Remember where focus was so "Paste" can hand it back.
+    if !window.is_visible().unwrap_or(false) {
+        crate::windows::remember_previous_window();
+    }
+
     let start = Instant::now();
 
     window
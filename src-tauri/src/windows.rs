@@ -4,6 +4,7 @@ use std::env;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
@@ -281,6 +282,45 @@ pub fn list_windows() -> Vec<WindowEntry> {
     list_windows_uncached(&recency)
 }
 
+/// The most recently focused window, which is the active one on compositors
+/// that report focus.
+pub fn focused_window() -> Option<WindowEntry> {
+    list_windows().into_iter().max_by_key(|w| w.last_active)
+}
+
+/// Window classes Vanta itself runs under, skipped when remembering the
+/// window to return to.
+const OWN_CLASSES: [&str; 2] = ["vanta", "dev.vanta.app"];
+
+static PREVIOUS_WINDOW: Mutex<Option<WindowEntry>> = Mutex::new(None);
+
+/// Bumped on every show so a slow lookup can't overwrite a newer one.
+static PREVIOUS_LOOKUP: AtomicU64 = AtomicU64::new(0);
+
+/// Records the focused window so actions like paste can return to it once
+/// Vanta hides. Call before showing the launcher. The lookup bypasses the list
+/// cache, so a window focused since the last search isn't missed, and runs on
+/// its own thread to keep it out of the show path.
+pub fn remember_previous_window() {
+    let lookup = PREVIOUS_LOOKUP.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || {
+        let focused = list_windows_uncached(&RecencyStore::new())
+            .into_iter()
+            .filter(|w| !OWN_CLASSES.iter().any(|own| w.class.eq_ignore_ascii_case(own)))
+            .max_by_key(|w| w.last_active);
+        if let Ok(mut previous) = PREVIOUS_WINDOW.lock() {
+            if PREVIOUS_LOOKUP.load(Ordering::SeqCst) == lookup {
+                *previous = focused;
+            }
+        }
+    });
+}
+
+/// The window that had focus before Vanta was last shown.
+pub fn previous_window() -> Option<WindowEntry> {
+    PREVIOUS_WINDOW.lock().ok().and_then(|previous| previous.clone())
+}
+
 fn group_windows(entries: Vec<WindowEntry>, max_items: usize) -> Vec<WindowGroup> {
     let mut grouped: HashMap<String, Vec<WindowEntry>> = HashMap::new();
     for w in entries {
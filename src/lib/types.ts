@@ -158,6 +158,8 @@ export interface FilesConfig {
     allowed_extensions: string[];
     type_filter: string;
     indexed_at?: number | null;
+    repo_roots?: string[];
+    repo_max_depth?: number;
 }
 
 export interface SourcePreference {
@@ -170,9 +172,31 @@ export interface SearchConfig {
     windows: SourcePreference;
     calculator: SourcePreference;
     files: SourcePreference;
+    web?: SourcePreference;
+    recent?: SourcePreference;
+    ssh?: SourcePreference;
+    emoji?: SourcePreference;
+    systemd?: SourcePreference;
+    repos?: SourcePreference;
+    transforms?: SourcePreference;
     windows_max_results?: number;
     show_explain_panel: boolean;
     layout_mode?: "single" | "multi" | string;
+    quicklinks?: QuicklinksConfig;
+}
+
+export interface Quicklink {
+    keyword: string;
+    name: string;
+    url: string;
+    icon?: string | null;
+    enabled: boolean;
+}
+
+export interface QuicklinksConfig {
+    entries: Quicklink[];
+    web_search_fallback: boolean;
+    fallback_keyword: string;
 }
 
 export interface MacroArg {
@@ -344,7 +368,84 @@ export interface VantaConfig {
     profiles?: ProfilesConfig;
     policy?: PolicyConfig;
     notes?: NotesConfig;
+    snippets?: SnippetsConfig;
     bookmarks?: BookmarksConfig;
+    browser?: BrowserSourceConfig;
+    currency?: CurrencyConfig;
+    calculator?: CalculatorConfig;
+    clipboard?: ClipboardConfig;
+    paste?: PasteConfig;
+}
+
+export interface BrowserProfileToggle {
+    id: string;
+    enabled: boolean;
+}
+
+export interface BrowserSourceConfig {
+    include_history: boolean;
+    history_limit: number;
+    profiles: BrowserProfileToggle[];
+}
+
+export interface CurrencyConfig {
+    auto_refresh: boolean;
+    provider: "ecb" | "json" | string;
+    rates_url: string;
+    refresh_hours: number;
+    stale_after_days: number;
+}
+
+export type RoundingMode = "half_up" | "half_down" | "half_even" | "down" | "up" | "floor" | "ceiling";
+
+export interface CalculatorConfig {
+    precision: number;
+    rounding: RoundingMode;
+}
+
+export type ClipboardBackendPreference = "auto" | "wl_clipboard" | "xclip" | "xsel" | "in_process";
+
+export type ClipboardDedup = "bump_to_top" | "consecutive" | "off";
+
+export interface ClipboardConfig {
+    max_items?: number;
+    max_age_days?: number | null;
+    retention_days?: Record<string, number>;
+    max_text_bytes?: number;
+    dedup?: ClipboardDedup;
+    vacuum_interval_hours?: number;
+    max_image_bytes: number;
+    backend?: ClipboardBackendPreference;
+    respect_password_hint?: boolean;
+    deny_patterns?: string[];
+    excluded_window_classes?: string[];
+    sensitive_expire_secs?: number | null;
+}
+
+export type PasteMode = "keystroke" | "type";
+
+export interface PasteConfig {
+    mode?: PasteMode;
+    chord?: string;
+    terminal_chord?: string;
+    terminal_classes?: string[];
+    delay_ms?: number;
+}
+
+export interface CurrencyRatesInfo {
+    provider: string;
+    base: string;
+    date?: string | null;
+    currencies: number;
+}
+
+export interface BrowserProfile {
+    id: string;
+    browser: string;
+    name: string;
+    kind: "firefox" | "chromium";
+    path: string;
+    enabled: boolean;
 }
 
 export interface QuickNote {
@@ -358,6 +459,16 @@ export interface NotesConfig {
     max_entries: number;
 }
 
+export interface Snippet {
+    keyword: string;
+    body: string;
+    tags?: string[];
+}
+
+export interface SnippetsConfig {
+    entries: Snippet[];
+}
+
 export interface FileBookmark {
     path: string;
     created_at_ms: number;
@@ -375,10 +486,42 @@ export interface PerfStats {
     max_ms: number;
 }
 
+export interface LatencyBucket {
+    le: string;
+    count: number;
+}
+
+export interface LatencySummary {
+    name: string;
+    count: number;
+    avg_ms: number;
+    p50_ms: number;
+    p95_ms: number;
+    p99_ms: number;
+    max_ms: number;
+    buckets: LatencyBucket[];
+}
+
+export interface SourceTiming {
+    source: string;
+    ms: number;
+    results: number;
+}
+
+export interface SlowQueryRecord {
+    query_chars: number;
+    total_ms: number;
+    timestamp: number;
+    sources: SourceTiming[];
+}
+
 export interface SearchDiagnostics {
     search: PerfStats;
     suggestions: PerfStats;
     launch: PerfStats;
+    operations: LatencySummary[];
+    sources: LatencySummary[];
+    slow_queries: SlowQueryRecord[];
 }
 
 export interface HealthCheck {
@@ -504,6 +647,10 @@ export type ResultSource =
     | "Window"
     | "File"
     | "Clipboard"
+    | "Web"
+    | "Recent"
+    | "Emoji"
+    | "Transform"
     | { Extension: { ext_id: string } };
 
 export interface ClipboardItem {
@@ -512,6 +659,13 @@ export interface ClipboardItem {
     timestamp: string;
     pinned: boolean;
     content_type: string;
+    mime_type: string;
+    html?: string;
+    image_path?: string;
+    thumbnail_path?: string;
+    width?: number;
+    height?: number;
+    byte_size?: number;
 }
 
 export interface BlurStatus {